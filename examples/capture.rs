@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Records OpenFMB traffic on NATS to a capture file or replays one
+//!
+//! ```text
+//! capture record <file>
+//! capture replay <file> [original|max|<speed factor>]
+//! ```
+//!
+//! Replays may be filtered with comma separated MODULES, PROFILES and MRIDS
+//! environment variables. Topics namespaced with a prefix, e.g. `site-a` for
+//! `site-a.openfmb.switchmodule...`, are recorded with the PREFIX environment
+//! variable set to it.
+
+use log::info;
+use openfmb::{
+    capture::{record, replay, CaptureReader, Recorder, ReplayFilter, Speed},
+    encoding::ProtobufEncoding,
+    prelude::*,
+};
+use std::env;
+use std::str::FromStr;
+use uuid::Uuid;
+
+fn usage() -> Box<dyn std::error::Error> {
+    "usage: capture record <file> | capture replay <file> [original|max|<speed factor>]".into()
+}
+
+fn env_list(name: &str) -> Vec<String> {
+    env::var(name)
+        .map(|val| {
+            val.split(',')
+                .filter(|item| !item.is_empty())
+                .map(|item| item.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn replay_filter() -> Result<ReplayFilter, Box<dyn std::error::Error>> {
+    let mut filter = ReplayFilter::new();
+    for module in env_list("MODULES") {
+        filter = filter.module(
+            Module::from_str(&module).map_err(|_err| format!("unknown module {}", module))?,
+        );
+    }
+    for profile in env_list("PROFILES") {
+        filter = filter.profile(
+            Profile::from_str(&profile).map_err(|_err| format!("unknown profile {}", profile))?,
+        );
+    }
+    for mrid in env_list("MRIDS") {
+        filter = filter.mrid(Uuid::parse_str(&mrid)?);
+    }
+    Ok(filter)
+}
+
+#[tokio::main]
+pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    pretty_env_logger::init();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (command, path) = match (args.first(), args.get(1)) {
+        (Some(command), Some(path)) => (command.as_str(), path),
+        _ => return Err(usage()),
+    };
+    let nats_url = env::var("NATS_URL")?;
+    let nc = nats::connect(&nats_url)?;
+    let mut bus = openfmb::bus::NatsBus::<ProtobufEncoding>::new(nc);
+    match command {
+        "record" => {
+            let mut recorder = Recorder::append(path)?;
+            let prefix = env::var("PREFIX")
+                .map(|prefix| format!("{}.", prefix))
+                .unwrap_or_default();
+            let topic = TopicBuf::from_subject(&format!("{}openfmb.>", prefix));
+            info!("Recording OpenFMB from {:?} to {:?}", nats_url, path);
+            record(&mut bus, topic.iter(), &mut recorder).await?;
+        }
+        "replay" => {
+            let speed = match args.get(2).map(String::as_str) {
+                None | Some("original") => Speed::Original,
+                Some("max") => Speed::Max,
+                Some(factor) => Speed::Scaled(factor.parse()?),
+            };
+            let reader = CaptureReader::open(path)?;
+            let published = replay(&mut bus, reader, speed, &replay_filter()?).await?;
+            info!("Replayed {} messages from {:?}", published, path);
+        }
+        _ => return Err(usage()),
+    }
+    Ok(())
+}
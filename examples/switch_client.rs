@@ -39,7 +39,11 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
     } else {
-        let bus = openfmb::bus::ZenohBus::<ProtobufEncoding>::new();
+        let mut config = openfmb::bus::ZenohConfig::default();
+        if let Ok(peers) = env::var("ZENOH_PEERS") {
+            config = config.peers(peers.split(','));
+        }
+        let bus = openfmb::bus::ZenohBus::<ProtobufEncoding>::connect(config);
 
         println!("Connected to switch {:?} using zenoh bus {:?}", mrid, &bus);
         let mut switch = openfmb::client::Switch::new(bus, mrid);
@@ -24,33 +24,59 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     let mut controls = switch.control().await?;
     let mut poll_interval = time::interval(time::Duration::from_secs(1));
-    let mut identified_object: IdentifiedObject = Default::default();
-    identified_object.description = Some(format!("OpenFMB-RS Example Switch Device {}", mrid));
-    identified_object.m_rid = Some(format!("{}", mrid));
-    let message_info = MessageInfo {
-        identified_object: Some(identified_object),
-        message_time_stamp: None,
-    };
-    let dynamic_test = DynamicTestKind::None;
-    let ens_dynamic_test = EnsDynamicTestKind {
-        st_val: dynamic_test.into(),
-        ..Default::default()
-    };
-    let mut status_dps: StatusDps = Default::default();
-    status_dps.st_val = DbPosKind::Closed.into();
-    let mut switch_status_xswi: SwitchStatusXswi = Default::default();
-    switch_status_xswi.dynamic_test = Some(ens_dynamic_test);
-    switch_status_xswi.pos = Some(PhaseDps {
-        phs3: Some(status_dps),
-        ..Default::default()
-    });
-    let mut switch_status: SwitchStatus = Default::default();
-    switch_status.switch_status_xswi = Some(switch_status_xswi);
-    let mut status: SwitchStatusProfile = Default::default();
-    status.switch_status = Some(switch_status);
-    status.status_message_info = Some(StatusMessageInfo {
-        message_info: Some(message_info),
-    });
+    let time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
+    let message_info = MessageInfo::builder()
+        .identified_object(
+            IdentifiedObject::builder()
+                .description(format!("OpenFMB-RS Example Switch Device {}", mrid))
+                .m_rid(mrid.to_string())
+                .build()?,
+        )
+        .message_time_stamp(
+            Timestamp::builder()
+                .seconds(time.as_secs())
+                .nanoseconds(time.subsec_nanos())
+                .build()?,
+        )
+        .build()?;
+    let switch_status_xswi = SwitchStatusXswi::builder()
+        .dynamic_test(
+            EnsDynamicTestKind::builder()
+                .st_val(DynamicTestKind::None)
+                .build()?,
+        )
+        .pos(
+            PhaseDps::builder()
+                .phs3(StatusDps::builder().st_val(DbPosKind::Closed).build()?)
+                .build()?,
+        )
+        .protection_pickup(
+            PhaseSps::builder()
+                .phs3(StatusSps::builder().st_val(false).build()?)
+                .build()?,
+        )
+        .build()?;
+    let mut status = SwitchStatusProfile::builder()
+        .status_message_info(
+            StatusMessageInfo::builder()
+                .message_info(message_info)
+                .build()?,
+        )
+        .protected_switch(
+            ProtectedSwitch::builder()
+                .conducting_equipment(
+                    ConductingEquipment::builder()
+                        .m_rid(mrid.to_string())
+                        .build()?,
+                )
+                .build()?,
+        )
+        .switch_status(
+            SwitchStatus::builder()
+                .switch_status_xswi(switch_status_xswi)
+                .build()?,
+        )
+        .build()?;
     loop {
         tokio::select! {
                     ctl = controls.next() => {
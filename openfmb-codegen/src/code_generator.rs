@@ -19,7 +19,7 @@ use proto_types::{
 
 use crate::ast::{Comments, Method, Service};
 use crate::extern_paths::ExternPaths;
-use crate::ident::{match_ident, to_shouty_snake, to_snake, to_upper_camel};
+use crate::ident::{match_ident, to_json_name, to_shouty_snake, to_snake, to_upper_camel};
 use crate::message_graph::MessageGraph;
 use crate::message_inheritance::MessageInheritance;
 use crate::Config;
@@ -41,7 +41,7 @@ pub struct CodeGenerator<'a> {
     depth: u8,
     path: Vec<i32>,
     buf: &'a mut String,
-    profiles: &'a mut HashSet<String>,
+    profiles: &'a mut HashMap<String, String>,
 }
 
 impl<'a> CodeGenerator<'a> {
@@ -52,7 +52,7 @@ impl<'a> CodeGenerator<'a> {
         message_inherits: &MessageInheritance,
         file: FileDescriptorProto,
         buf: &mut String,
-        profiles: &mut HashSet<String>,
+        profiles: &mut HashMap<String, String>,
     ) {
         let mut source_info = file
             .source_code_info
@@ -195,7 +195,8 @@ impl<'a> CodeGenerator<'a> {
             self.push_indent();
 
             if message_options.openfmb_profile == Some(true) {
-                self.profiles.insert(String::from(&message_name));
+                self.profiles
+                    .insert(String::from(&message_name), self.package.clone());
             }
             let comment = format!(
                 "/// OpenFMB Profile Message: {}\n",
@@ -362,6 +363,70 @@ impl<'a> CodeGenerator<'a> {
             }
         }
 
+        // fallible accessors for the optional fields, naming the field when
+        // it is not set
+        for (field, _idx) in &fields {
+            if !self.optional(&field) {
+                continue;
+            }
+            let ty = self.resolve_type(&field);
+            let boxed = field.r#type() == Type::Message
+                && self
+                    .message_graph
+                    .is_nested(field.type_name(), &fq_message_name);
+            self.push_indent();
+            self.buf.push_str("pub fn try_");
+            self.buf.push_str(&to_snake(field.name()));
+            self.buf.push_str("(&self) -> ::std::result::Result<&");
+            self.buf.push_str(&ty);
+            self.buf.push_str(", crate::MissingField> {\n");
+            self.depth += 1;
+            self.push_indent();
+            self.buf.push_str("self.");
+            self.buf.push_str(&to_snake(field.name()));
+            if boxed {
+                self.buf.push_str(".as_deref()");
+            } else {
+                self.buf.push_str(".as_ref()");
+            }
+            self.buf
+                .push_str(".ok_or_else(|| crate::MissingField::new(");
+            self.buf
+                .push_str(&format!("{:?}", self.field_path(&message_name, &field)));
+            self.buf.push_str("))\n");
+            self.depth -= 1;
+            self.push_indent();
+            self.buf.push_str("}\n");
+
+            // for fields of a generated message, a chain helper that names
+            // the missing field by its path from this message
+            if field.r#type() != Type::Message
+                || self.extern_paths.resolve_ident(field.type_name()).is_some()
+            {
+                continue;
+            }
+            self.push_indent();
+            self.buf.push_str("pub fn try_");
+            self.buf.push_str(&to_snake(field.name()));
+            self.buf
+                .push_str("_and_then<'a, R>(&'a self, f: impl FnOnce(&'a ");
+            self.buf.push_str(&ty);
+            self.buf.push_str(
+                ") -> ::std::result::Result<R, crate::MissingField>) -> ::std::result::Result<R, crate::MissingField> {\n",
+            );
+            self.depth += 1;
+            self.push_indent();
+            self.buf.push_str("f(self.try_");
+            self.buf.push_str(&to_snake(field.name()));
+            self.buf.push_str("()?).map_err(|err| err.within(");
+            self.buf
+                .push_str(&format!("{:?}", self.field_path(&message_name, &field)));
+            self.buf.push_str("))\n");
+            self.depth -= 1;
+            self.push_indent();
+            self.buf.push_str("}\n");
+        }
+
         // Add prost encode
         self.push_indent();
         self.buf.push_str(
@@ -606,6 +671,11 @@ impl<'a> CodeGenerator<'a> {
             }
         }
 
+        self.append_full_name(&message_name);
+        self.append_validate(&message_name, &fields);
+        self.append_visit(&message_name, &fields);
+        self.append_builder(&message_name, &fields);
+
         if !message.enum_type.is_empty() || !nested_types.is_empty() || !oneof_fields.is_empty() {
             self.push_mod(&message_name);
             self.path.push(3);
@@ -638,6 +708,425 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
+    /// FullName impl giving the fully qualified proto name of the message
+    fn append_full_name(&mut self, message_name: &str) {
+        self.push_indent();
+        self.buf.push_str("impl crate::FullName for ");
+        self.buf.push_str(&to_upper_camel(message_name));
+        self.buf.push_str(" {\n");
+        self.depth += 1;
+        self.push_indent();
+        self.buf.push_str(&format!(
+            "const FULL_NAME: &'static str = \"{}.{}\";\n",
+            self.package, message_name
+        ));
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
+    }
+
+    /// Validate impl checking the required_field, multiplicity_min/max and
+    /// uuid options of the fields, recursing into the fields holding
+    /// generated messages
+    fn append_validate(&mut self, message_name: &str, fields: &[(FieldDescriptorProto, usize)]) {
+        let mut checks = Vec::new();
+        for (field, _idx) in fields {
+            let name = field.name();
+            let member = format!("self.{}", to_snake(name));
+            let repeated = field.label == Some(Label::Repeated as i32);
+            let optional = self.optional(field);
+            let (min, max) = self.multiplicity(field);
+            let required = self.required(field);
+            let uuid = field
+                .options
+                .as_ref()
+                .and_then(|options| options.uuid)
+                .unwrap_or_default();
+            let validated = field.r#type() == Type::Message
+                && self.extern_paths.resolve_ident(field.type_name()).is_none();
+            // only the wrapper types mapped to a string hold an mRID
+            let string = field.r#type() == Type::String
+                || field.type_name() == ".google.protobuf.StringValue";
+
+            if repeated {
+                if required || max.is_some() {
+                    checks.push(format!(
+                        "crate::validate::check_multiplicity(path, {:?}, {}.len(), {}, {:?}, violations);",
+                        name,
+                        member,
+                        min.max(required as usize),
+                        max
+                    ));
+                }
+                if validated {
+                    checks.push(format!(
+                        "for (idx, value) in {}.iter().enumerate() {{ value.validate_at(&crate::validate::index_path(path, {:?}, idx), violations); }}",
+                        member, name
+                    ));
+                } else if uuid && string {
+                    checks.push(format!(
+                        "for (idx, value) in {}.iter().enumerate() {{ crate::validate::check_uuid(&crate::validate::index_path(path, {:?}, idx), value, violations); }}",
+                        member, name
+                    ));
+                }
+            } else if optional {
+                if required {
+                    checks.push(format!(
+                        "crate::validate::check_required(path, {:?}, {}.is_some(), violations);",
+                        name, member
+                    ));
+                }
+                if validated {
+                    checks.push(format!(
+                        "if let Some(ref value) = {} {{ value.validate_at(&crate::validate::field_path(path, {:?}), violations); }}",
+                        member, name
+                    ));
+                } else if uuid && string {
+                    checks.push(format!(
+                        "if let Some(ref value) = {} {{ crate::validate::check_uuid(&crate::validate::field_path(path, {:?}), value, violations); }}",
+                        member, name
+                    ));
+                }
+            } else if string {
+                // proto3 has no presence for scalars, an empty string is unset
+                if required {
+                    checks.push(format!(
+                        "crate::validate::check_required(path, {:?}, !{}.is_empty(), violations);",
+                        name, member
+                    ));
+                }
+                if uuid {
+                    checks.push(format!(
+                        "crate::validate::check_uuid(&crate::validate::field_path(path, {:?}), &{}, violations);",
+                        name, member
+                    ));
+                }
+            }
+        }
+
+        self.push_indent();
+        self.buf.push_str("impl crate::Validate for ");
+        self.buf.push_str(&to_upper_camel(message_name));
+        self.buf.push_str(" {\n");
+        self.depth += 1;
+        self.push_indent();
+        if checks.is_empty() {
+            self.buf.push_str("fn validate_at(&self, _path: &str, _violations: &mut ::std::vec::Vec<crate::Violation>) {}\n");
+        } else {
+            self.buf.push_str("fn validate_at(&self, path: &str, violations: &mut ::std::vec::Vec<crate::Violation>) {\n");
+            self.depth += 1;
+            for check in checks {
+                self.push_indent();
+                self.buf.push_str(&check);
+                self.buf.push('\n');
+            }
+            self.depth -= 1;
+            self.push_indent();
+            self.buf.push_str("}\n");
+        }
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
+    }
+
+    /// Visit and VisitMut impls walking the fields in order, calling the
+    /// visitor for the analog values, Timestamps, Qualities, enums and mRIDs
+    /// and recursing into the fields holding other generated messages
+    fn append_visit(&mut self, message_name: &str, fields: &[(FieldDescriptorProto, usize)]) {
+        let mut visits = Vec::new();
+        let mut visits_mut = Vec::new();
+        for (field, _idx) in fields {
+            let name = field.name();
+            let member = format!("self.{}", to_snake(name));
+            let uuid = field
+                .options
+                .as_ref()
+                .and_then(|options| options.uuid)
+                .unwrap_or_default();
+            let string = field.r#type() == Type::String
+                || field.type_name() == ".google.protobuf.StringValue";
+            let float =
+                field.r#type() == Type::Float || field.type_name() == ".google.protobuf.FloatValue";
+
+            // the visit of a reference to the field or one of its elements
+            let (visit, visit_mut) = match (field.r#type(), field.type_name()) {
+                (Type::Message, ".commonmodule.Timestamp") => (
+                    "visitor.visit_timestamp(PATH, value);".to_string(),
+                    "visitor.visit_timestamp_mut(PATH, value);".to_string(),
+                ),
+                (Type::Message, ".commonmodule.Quality") => (
+                    "visitor.visit_quality(PATH, value);".to_string(),
+                    "visitor.visit_quality_mut(PATH, value);".to_string(),
+                ),
+                (Type::Double, _)
+                | (Type::Message, ".google.protobuf.DoubleValue")
+                | (Type::Float, _)
+                | (Type::Message, ".google.protobuf.FloatValue")
+                    if float =>
+                {
+                    (
+                        "visitor.visit_analog(PATH, f64::from(*value));".to_string(),
+                        "let mut analog = f64::from(*value); visitor.visit_analog_mut(PATH, &mut analog); *value = analog as f32;".to_string(),
+                    )
+                }
+                (Type::Double, _) | (Type::Message, ".google.protobuf.DoubleValue") => (
+                    "visitor.visit_analog(PATH, *value);".to_string(),
+                    "visitor.visit_analog_mut(PATH, value);".to_string(),
+                ),
+                (Type::Enum, type_name) => {
+                    let enum_name = type_name.trim_start_matches('.');
+                    (
+                        format!("visitor.visit_enum(PATH, {:?}, *value);", enum_name),
+                        format!("visitor.visit_enum_mut(PATH, {:?}, value);", enum_name),
+                    )
+                }
+                (Type::Message, type_name)
+                    if self.extern_paths.resolve_ident(type_name).is_none() =>
+                {
+                    (
+                        "value.visit_at(PATH, visitor);".to_string(),
+                        "value.visit_mut_at(PATH, visitor);".to_string(),
+                    )
+                }
+                _ if uuid && string => (
+                    "visitor.visit_mrid(PATH, value);".to_string(),
+                    "visitor.visit_mrid_mut(PATH, value);".to_string(),
+                ),
+                _ => continue,
+            };
+
+            let (head, head_mut, path) = if field.label == Some(Label::Repeated as i32) {
+                (
+                    format!("for (idx, value) in {}.iter().enumerate()", member),
+                    format!("for (idx, value) in {}.iter_mut().enumerate()", member),
+                    format!("&crate::validate::index_path(path, {:?}, idx)", name),
+                )
+            } else if self.optional(field) {
+                (
+                    format!("if let Some(ref value) = {}", member),
+                    format!("if let Some(ref mut value) = {}", member),
+                    format!("&crate::validate::field_path(path, {:?})", name),
+                )
+            } else {
+                (
+                    format!("{{ let value = &{};", member),
+                    format!("{{ let value = &mut {};", member),
+                    format!("&crate::validate::field_path(path, {:?})", name),
+                )
+            };
+            let block = |head: &str, visit: &str| {
+                if head.starts_with('{') {
+                    format!("{} {} }}", head, visit.replace("PATH", &path))
+                } else {
+                    format!("{} {{ {} }}", head, visit.replace("PATH", &path))
+                }
+            };
+            visits.push(block(&head, &visit));
+            visits_mut.push(block(&head_mut, &visit_mut));
+        }
+
+        for (trait_name, signature, visits) in vec![
+            (
+                "Visit",
+                "fn visit_at<V: crate::Visitor + ?Sized>(&self, PATH: &str, VISITOR: &mut V)",
+                visits,
+            ),
+            (
+                "VisitMut",
+                "fn visit_mut_at<V: crate::VisitorMut + ?Sized>(&mut self, PATH: &str, VISITOR: &mut V)",
+                visits_mut,
+            ),
+        ] {
+            let (path, visitor) = if visits.is_empty() {
+                ("_path", "_visitor")
+            } else {
+                ("path", "visitor")
+            };
+            self.push_indent();
+            self.buf.push_str(&format!(
+                "impl crate::{} for {} {{\n",
+                trait_name,
+                to_upper_camel(message_name)
+            ));
+            self.depth += 1;
+            self.push_indent();
+            self.buf.push_str(
+                &signature
+                    .replace("PATH", path)
+                    .replace("VISITOR", visitor),
+            );
+            if visits.is_empty() {
+                self.buf.push_str(" {}\n");
+            } else {
+                self.buf.push_str(" {\n");
+                self.depth += 1;
+                for visit in visits {
+                    self.push_indent();
+                    self.buf.push_str(&visit);
+                    self.buf.push('\n');
+                }
+                self.depth -= 1;
+                self.push_indent();
+                self.buf.push_str("}\n");
+            }
+            self.depth -= 1;
+            self.push_indent();
+            self.buf.push_str("}\n");
+        }
+    }
+
+    /// Fluent builder setting the fields by value, with the enum fields
+    /// taking their Rust enum, and failing to build when a field required by
+    /// the UML model is missing
+    fn append_builder(&mut self, message_name: &str, fields: &[(FieldDescriptorProto, usize)]) {
+        let msg_type = to_upper_camel(message_name);
+        let builder_type = format!("{}Builder", msg_type);
+        let fq_message_name = format!(".{}.{}", self.package, message_name);
+
+        self.push_indent();
+        self.buf.push_str("impl ");
+        self.buf.push_str(&msg_type);
+        self.buf.push_str(" {\n");
+        self.depth += 1;
+        self.push_indent();
+        self.buf
+            .push_str(&format!("pub fn builder() -> {} {{\n", builder_type));
+        self.depth += 1;
+        self.push_indent();
+        self.buf.push_str(&builder_type);
+        self.buf.push_str("::default()\n");
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
+
+        self.push_indent();
+        self.buf.push_str(&format!(
+            "/// Builder for [`{}`], its `build` fails when a required field is not set\n",
+            msg_type
+        ));
+        self.push_indent();
+        self.buf.push_str("#[derive(Clone, Debug, Default)]\n");
+        self.push_indent();
+        self.buf
+            .push_str(&format!("pub struct {} {{\n", builder_type));
+        self.depth += 1;
+        self.push_indent();
+        self.buf.push_str(&format!("msg: {},\n", msg_type));
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
+
+        self.push_indent();
+        self.buf.push_str(&format!("impl {} {{\n", builder_type));
+        self.depth += 1;
+        let mut missing = Vec::new();
+        for (field, _idx) in fields {
+            let name = field.name();
+            let member = to_snake(name);
+            let repeated = field.label == Some(Label::Repeated as i32);
+            let optional = self.optional(field);
+            let boxed = !repeated
+                && field.r#type() == Type::Message
+                && self
+                    .message_graph
+                    .is_nested(field.type_name(), &fq_message_name);
+            let enumeration = field.r#type() == Type::Enum;
+            let string = field.r#type() == Type::String
+                || field.type_name() == ".google.protobuf.StringValue";
+            let (arg_ty, mut value) = if enumeration {
+                (
+                    self.resolve_ident(field.type_name()),
+                    "value as i32".to_string(),
+                )
+            } else if string && !repeated {
+                (
+                    "impl ::std::convert::Into<::std::string::String>".to_string(),
+                    "value.into()".to_string(),
+                )
+            } else {
+                (self.resolve_type(field), "value".to_string())
+            };
+            if boxed {
+                value = format!("::std::boxed::Box::new({})", value);
+            }
+
+            self.push_indent();
+            if repeated {
+                self.buf.push_str(&format!(
+                    "pub fn {}(mut self, values: ::std::vec::Vec<{}>) -> Self {{\n",
+                    member, arg_ty
+                ));
+                self.depth += 1;
+                self.push_indent();
+                if enumeration {
+                    self.buf.push_str(&format!(
+                        "self.msg.{} = values.into_iter().map(|value| {}).collect();\n",
+                        member, value
+                    ));
+                } else {
+                    self.buf
+                        .push_str(&format!("self.msg.{} = values;\n", member));
+                }
+            } else {
+                self.buf.push_str(&format!(
+                    "pub fn {}(mut self, value: {}) -> Self {{\n",
+                    member, arg_ty
+                ));
+                self.depth += 1;
+                self.push_indent();
+                if optional {
+                    value = format!("Some({})", value);
+                }
+                self.buf
+                    .push_str(&format!("self.msg.{} = {};\n", member, value));
+            }
+            self.push_indent();
+            self.buf.push_str("self\n");
+            self.depth -= 1;
+            self.push_indent();
+            self.buf.push_str("}\n");
+
+            // scalars have no presence in proto3, only strings can be missing
+            if self.required(field) && (repeated || optional || string) {
+                let unset = if optional { "is_none" } else { "is_empty" };
+                missing.push((member, unset, self.field_path(message_name, field)));
+            }
+        }
+
+        self.push_indent();
+        self.buf.push_str(&format!(
+            "pub fn build(self) -> ::std::result::Result<{}, crate::MissingField> {{\n",
+            msg_type
+        ));
+        self.depth += 1;
+        for (member, unset, name) in missing {
+            self.push_indent();
+            self.buf
+                .push_str(&format!("if self.msg.{}.{}() {{\n", member, unset));
+            self.depth += 1;
+            self.push_indent();
+            self.buf.push_str(&format!(
+                "return Err(crate::MissingField::new({:?}));\n",
+                name
+            ));
+            self.depth -= 1;
+            self.push_indent();
+            self.buf.push_str("}\n");
+        }
+        self.push_indent();
+        self.buf.push_str("Ok(self.msg)\n");
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
+
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
+    }
+
     fn append_type_attributes(&mut self, msg_name: &str) {
         assert_eq!(b'.', msg_name.as_bytes()[0]);
         // TODO: this clone is dirty, but expedious.
@@ -650,21 +1139,11 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
-    fn append_field_attributes(&mut self, msg_name: &str, field_name: &str, is_enum: bool) {
-        let name_matches_snake = to_snake(field_name) == field_name;
-        let add_serde_attrs = !name_matches_snake || !is_enum;
-        if add_serde_attrs {
+    fn append_field_attributes(&mut self, msg_name: &str, field_name: &str, serde_args: &[String]) {
+        if !serde_args.is_empty() {
             self.push_indent();
             self.buf.push_str("#[serde(");
-            if !is_enum {
-                self.buf.push_str("default");
-            }
-            if !name_matches_snake {
-                if !is_enum {
-                    self.buf.push_str(", ");
-                }
-                self.buf.push_str(&format!("rename = \"{}\"", field_name));
-            }
+            self.buf.push_str(&serde_args.join(", "));
             self.buf.push_str(")]\n");
         }
         assert_eq!(b'.', msg_name.as_bytes()[0]);
@@ -804,7 +1283,8 @@ impl<'a> CodeGenerator<'a> {
         }
 
         self.buf.push_str("\")]\n");
-        self.append_field_attributes(msg_name, field.name(), false);
+        let serde_args = self.field_serde_args(&field, optional);
+        self.append_field_attributes(msg_name, field.name(), &serde_args);
         self.push_indent();
         self.buf.push_str("pub ");
         self.buf.push_str(&to_snake(field.name()));
@@ -827,6 +1307,54 @@ impl<'a> CodeGenerator<'a> {
         self.buf.push_str(",\n");
     }
 
+    /// serde arguments mapping a field to its proto3 JSON form, named by its
+    /// JSON name with enums as their value names, 64-bit integers as strings
+    /// and non-finite floats as `"NaN"`, `"Infinity"` or `"-Infinity"`
+    fn field_serde_args(&self, field: &FieldDescriptorProto, optional: bool) -> Vec<String> {
+        let mut args = vec!["default".to_string()];
+        args.extend(json_name_args(field.name(), &to_snake(field.name())));
+        if field.label() != Label::Repeated {
+            let helper = match (field.r#type(), field.type_name()) {
+                (Type::Enum, _) => {
+                    let enum_type = self.resolve_ident(field.type_name());
+                    args.push(format!(
+                        "serialize_with = \"crate::json::enumeration::serialize::<{}, _>\"",
+                        enum_type
+                    ));
+                    args.push(format!(
+                        "deserialize_with = \"crate::json::enumeration::deserialize::<{}, _>\"",
+                        enum_type
+                    ));
+                    None
+                }
+                (Type::Int64, _)
+                | (Type::Sint64, _)
+                | (Type::Sfixed64, _)
+                | (Type::Uint64, _)
+                | (Type::Fixed64, _) => Some("int64"),
+                (Type::Float, _) | (Type::Double, _) => Some("float"),
+                (Type::Message, ".google.protobuf.Int64Value")
+                | (Type::Message, ".google.protobuf.UInt64Value") => Some("optional_int64"),
+                (Type::Message, ".google.protobuf.FloatValue")
+                | (Type::Message, ".google.protobuf.DoubleValue") => Some("optional_float"),
+                (Type::Message, _) | (Type::Group, _) => None,
+                _ => {
+                    args.push(NULLABLE.to_string());
+                    None
+                }
+            };
+            if let Some(helper) = helper {
+                args.push(format!("with = \"crate::json::{}\"", helper));
+            }
+        } else {
+            args.push(NULLABLE.to_string());
+        }
+        if optional {
+            args.push("skip_serializing_if = \"::std::option::Option::is_none\"".to_string());
+        }
+        args
+    }
+
     fn append_map_field(
         &mut self,
         msg_name: &str,
@@ -867,7 +1395,9 @@ impl<'a> CodeGenerator<'a> {
             value_tag,
             field.number()
         ));
-        self.append_field_attributes(msg_name, field.name(), false);
+        let mut serde_args = vec!["default".to_string()];
+        serde_args.extend(json_name_args(field.name(), &to_snake(field.name())));
+        self.append_field_attributes(msg_name, field.name(), &serde_args);
         self.push_indent();
         self.buf.push_str(&format!(
             "pub {}: ::std::collections::{}<{}, {}>,\n",
@@ -900,7 +1430,8 @@ impl<'a> CodeGenerator<'a> {
                 .map(|&(ref field, _)| field.number())
                 .join(", ")
         ));
-        self.append_field_attributes(fq_message_name, oneof.name(), true);
+        // proto3 JSON has the set oneof field alongside the other fields
+        self.append_field_attributes(fq_message_name, oneof.name(), &["flatten".to_string()]);
         self.push_indent();
         self.buf.push_str(&format!(
             "pub {}: ::std::option::Option<{}>,\n",
@@ -949,7 +1480,8 @@ impl<'a> CodeGenerator<'a> {
                 ty_tag,
                 field.number()
             ));
-            self.append_field_attributes(&oneof_name, field.name(), true);
+            let serde_args = json_name_args(field.name(), &to_upper_camel(field.name()));
+            self.append_field_attributes(&oneof_name, field.name(), &serde_args);
 
             self.push_indent();
             let ty = self.resolve_type(&field);
@@ -1042,6 +1574,35 @@ impl<'a> CodeGenerator<'a> {
 
         self.push_indent();
         self.buf.push_str("}\n");
+
+        // lets the proto3 JSON mapping name the i32 enum fields
+        let rust_name = to_upper_camel(desc.name());
+        self.push_indent();
+        self.buf.push_str("impl crate::json::ProtoEnum for ");
+        self.buf.push_str(&rust_name);
+        self.buf.push_str(" {\n");
+        self.depth += 1;
+        self.push_indent();
+        self.buf
+            .push_str("fn from_i32(value: i32) -> ::std::option::Option<Self> {\n");
+        self.depth += 1;
+        self.push_indent();
+        self.buf.push_str(&rust_name);
+        self.buf.push_str("::from_i32(value)\n");
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
+        self.push_indent();
+        self.buf.push_str("fn as_i32(self) -> i32 {\n");
+        self.depth += 1;
+        self.push_indent();
+        self.buf.push_str("self as i32\n");
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
+        self.depth -= 1;
+        self.push_indent();
+        self.buf.push_str("}\n");
     }
 
     fn append_enum_value(
@@ -1051,13 +1612,19 @@ impl<'a> CodeGenerator<'a> {
         prefix_to_strip: Option<String>,
     ) {
         self.append_doc();
-        self.append_field_attributes(fq_enum_name, &value.name(), true);
-        self.push_indent();
         let name = to_upper_camel(value.name());
         let name_unprefixed = match prefix_to_strip {
             Some(prefix) => strip_enum_prefix(&prefix, &name),
             None => &name,
         };
+        // proto3 JSON uses the value name as given in the proto file
+        let serde_args = if name_unprefixed != value.name() {
+            vec![format!("rename = \"{}\"", value.name())]
+        } else {
+            Vec::new()
+        };
+        self.append_field_attributes(fq_enum_name, &value.name(), &serde_args);
+        self.push_indent();
         self.buf.push_str(name_unprefixed);
         self.buf.push_str(" = ");
         self.buf.push_str(&value.number().to_string());
@@ -1301,6 +1868,36 @@ impl<'a> CodeGenerator<'a> {
         }
     }
 
+    /// Fully qualified proto name of a field of the message, as carried by
+    /// MissingField, e.g. `switchmodule.SwitchStatusXSWI.Pos`
+    fn field_path(&self, message_name: &str, field: &FieldDescriptorProto) -> String {
+        format!("{}.{}.{}", self.package, message_name, field.name())
+    }
+
+    /// Whether the UML model requires the field, either by the
+    /// required_field option or a multiplicity_min of at least one
+    fn required(&self, field: &FieldDescriptorProto) -> bool {
+        if let Some(ref field_options) = field.options {
+            field_options.required_field.unwrap_or_default() || self.multiplicity(field).0 > 0
+        } else {
+            false
+        }
+    }
+
+    /// The multiplicity_min and multiplicity_max options of the field
+    fn multiplicity(&self, field: &FieldDescriptorProto) -> (usize, Option<usize>) {
+        if let Some(ref field_options) = field.options {
+            (
+                field_options.multiplicity_min.unwrap_or(0).max(0) as usize,
+                field_options
+                    .multiplicity_max
+                    .map(|max| max.max(0) as usize),
+            )
+        } else {
+            (0, None)
+        }
+    }
+
     fn parent(&self, field: &FieldDescriptorProto) -> bool {
         if let Some(ref field_options) = field.options {
             field_options.parent_message.unwrap_or_default()
@@ -1437,6 +2034,23 @@ fn unescape_c_escape_string(s: &str) -> Vec<u8> {
     dst
 }
 
+/// proto3 JSON reads `null` as the default value of any field
+const NULLABLE: &str = "deserialize_with = \"crate::json::nullable::deserialize\"";
+
+/// serde arguments naming a field by its proto3 JSON name rather than its
+/// Rust identifier, accepting the name from the proto file as well
+fn json_name_args(field_name: &str, rust_name: &str) -> Vec<String> {
+    let json_name = to_json_name(field_name);
+    let mut args = Vec::new();
+    if json_name != rust_name {
+        args.push(format!("rename = \"{}\"", json_name));
+    }
+    if json_name != field_name {
+        args.push(format!("alias = \"{}\"", field_name));
+    }
+    args
+}
+
 /// Strip an enum's type name from the prefix of an enum value.
 ///
 /// This function assumes that both have been formatted to Rust's
@@ -48,6 +48,24 @@ pub fn to_upper_camel(s: &str) -> String {
     ident
 }
 
+/// Converts a field name to its proto3 JSON name the way protoc does, dropping
+/// underscores and capitalizing the letter following each one.
+pub fn to_json_name(s: &str) -> String {
+    let mut name = String::with_capacity(s.len());
+    let mut capitalize = false;
+    for c in s.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            name.extend(c.to_uppercase());
+            capitalize = false;
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
 /// Matches a 'matcher' against a fully qualified identifier.
 pub fn match_ident(matcher: &str, msg: &str, field: Option<&str>) -> bool {
     assert_eq!(b'.', msg.as_bytes()[0]);
@@ -199,6 +217,15 @@ mod tests {
         assert_eq!("Self_", &to_upper_camel("self"));
     }
 
+    #[test]
+    fn test_to_json_name() {
+        assert_eq!("fooBar", &to_json_name("foo_bar"));
+        assert_eq!("fooBar", &to_json_name("fooBar"));
+        assert_eq!("Pos", &to_json_name("Pos"));
+        assert_eq!("mRID", &to_json_name("mRID"));
+        assert_eq!("limitNegativeDpDt", &to_json_name("limitNegative_dp_dt"));
+    }
+
     #[test]
     fn test_match_ident() {
         // Prefix matches
@@ -114,7 +114,7 @@ mod ident;
 mod message_graph;
 mod message_inheritance;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::default;
 use std::env;
 use std::fs;
@@ -539,9 +539,11 @@ impl Config {
 
     fn generate(&mut self, files: Vec<FileDescriptorProto>) -> Result<HashMap<Module, String>> {
         let mut modules = HashMap::new();
-        // openfmb profiles to be output in a seperate file
-        let mut profiles = HashSet::new();
+        // openfmb profiles to be output in a seperate file, with their package
+        let mut profiles = HashMap::new();
         let mut packages = HashMap::new();
+        // paths of every generated message, for the for_each_message macro
+        let mut messages = Vec::new();
 
         let message_graph = MessageGraph::new(&files)
             .map_err(|error| Error::new(ErrorKind::InvalidInput, error))?;
@@ -555,6 +557,17 @@ impl Config {
         for file in files {
             let module = self.module(&file);
 
+            for message in file.message_type.iter() {
+                let fq_message_name = format!(".{}.{}", file.package(), message.name());
+                if extern_paths.resolve_ident(&fq_message_name).is_none() {
+                    messages.push(format!(
+                        "{}::{}",
+                        module.join("::"),
+                        to_upper_camel(message.name())
+                    ));
+                }
+            }
+
             // Only record packages that have services
             if !file.service.is_empty() {
                 packages.insert(module.clone(), file.package().to_string());
@@ -578,7 +591,7 @@ impl Config {
                 service_generator.finalize_package(&package, buf);
             }
         }
-        let mut sorted_profiles: Vec<String> = profiles.into_iter().collect();
+        let mut sorted_profiles: Vec<String> = profiles.keys().cloned().collect();
         sorted_profiles.sort();
 
         let mut profile_buf = String::new();
@@ -691,6 +704,131 @@ impl Config {
         variant_buf.push_str("        }\n");
         variant_buf.push_str("    }\n");
 
+        // the JSON of a ProfileMessage is that of the profile it holds, which
+        // like the protobuf encoding is told by the topic rather than the payload
+        variant_buf.push_str("    pub fn deserialize_profile<'de, D: serde::Deserializer<'de>>(profile: Profile, deserializer: D) -> Result<ProfileMessage, D::Error> {\n");
+        variant_buf.push_str("        match profile {\n");
+        for profile in sorted_profiles.iter() {
+            variant_buf.push_str("            Profile::");
+            variant_buf.push_str(&profile);
+            variant_buf.push_str(" => Ok(ProfileMessage::");
+            variant_buf.push_str(&profile);
+            variant_buf.push_str("(<");
+            variant_buf.push_str(&to_upper_camel(profile));
+            variant_buf.push_str(" as serde::Deserialize>::deserialize(deserializer)?)),\n");
+        }
+        variant_buf.push_str("        }\n");
+        variant_buf.push_str("    }\n");
+
+        variant_buf.push_str("    pub fn decode_profile<D: crate::ProfileDecoder>(profile: Profile, decoder: D) -> Result<ProfileMessage, D::Error> {\n");
+        variant_buf.push_str("        match profile {\n");
+        for profile in sorted_profiles.iter() {
+            variant_buf.push_str("            Profile::");
+            variant_buf.push_str(&profile);
+            variant_buf.push_str(" => Ok(ProfileMessage::");
+            variant_buf.push_str(&profile);
+            variant_buf.push_str("(decoder.decode::<");
+            variant_buf.push_str(&to_upper_camel(profile));
+            variant_buf.push_str(">()?)),\n");
+        }
+        variant_buf.push_str("        }\n");
+        variant_buf.push_str("    }\n");
+
+        variant_buf.push_str("}\n");
+
+        variant_buf.push_str("impl serde::Serialize for ProfileMessage {\n");
+        variant_buf.push_str("    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {\n");
+        variant_buf.push_str("        match self {\n");
+        for profile in sorted_profiles.iter() {
+            variant_buf.push_str("            ProfileMessage::");
+            variant_buf.push_str(&profile);
+            variant_buf.push_str("(msg) => msg.serialize(serializer),\n");
+        }
+        variant_buf.push_str("        }\n");
+        variant_buf.push_str("    }\n");
+        variant_buf.push_str("}\n");
+
+        variant_buf.push_str("impl crate::Validate for ProfileMessage {\n");
+        variant_buf.push_str(
+            "    fn validate_at(&self, path: &str, violations: &mut Vec<crate::Violation>) {\n",
+        );
+        variant_buf.push_str("        match self {\n");
+        for profile in sorted_profiles.iter() {
+            variant_buf.push_str("            ProfileMessage::");
+            variant_buf.push_str(&profile);
+            variant_buf.push_str("(msg) => msg.validate_at(path, violations),\n");
+        }
+        variant_buf.push_str("        }\n");
+        variant_buf.push_str("    }\n");
+        variant_buf.push_str("}\n");
+
+        variant_buf.push_str("impl crate::Visit for ProfileMessage {\n");
+        variant_buf.push_str(
+            "    fn visit_at<V: crate::Visitor + ?Sized>(&self, path: &str, visitor: &mut V) {\n",
+        );
+        variant_buf.push_str("        match self {\n");
+        for profile in sorted_profiles.iter() {
+            variant_buf.push_str("            ProfileMessage::");
+            variant_buf.push_str(&profile);
+            variant_buf.push_str("(msg) => msg.visit_at(path, visitor),\n");
+        }
+        variant_buf.push_str("        }\n");
+        variant_buf.push_str("    }\n");
+        variant_buf.push_str("}\n");
+
+        variant_buf.push_str("impl crate::VisitMut for ProfileMessage {\n");
+        variant_buf.push_str(
+            "    fn visit_mut_at<V: crate::VisitorMut + ?Sized>(&mut self, path: &str, visitor: &mut V) {\n",
+        );
+        variant_buf.push_str("        match self {\n");
+        for profile in sorted_profiles.iter() {
+            variant_buf.push_str("            ProfileMessage::");
+            variant_buf.push_str(&profile);
+            variant_buf.push_str("(msg) => msg.visit_mut_at(path, visitor),\n");
+        }
+        variant_buf.push_str("        }\n");
+        variant_buf.push_str("    }\n");
+        variant_buf.push_str("}\n");
+
+        // lets other crates implement their own traits for every profile,
+        // which they could not do with a blanket impl without conflicting
+        // with one for ProfileMessage
+        variant_buf.push_str("/// Calls the given macro with the path of every profile message\n");
+        variant_buf.push_str("#[macro_export]\n");
+        variant_buf.push_str("macro_rules! for_each_profile {\n");
+        variant_buf.push_str("    ($callback:ident) => {\n");
+        variant_buf.push_str("        $callback!(\n");
+        for profile in sorted_profiles.iter() {
+            let module: Vec<String> = profiles[profile]
+                .split('.')
+                .filter(|s| !s.is_empty())
+                .map(to_snake)
+                .collect();
+            variant_buf.push_str("            $crate::");
+            variant_buf.push_str(&module.join("::"));
+            variant_buf.push_str("::");
+            variant_buf.push_str(&to_upper_camel(profile));
+            variant_buf.push_str(",\n");
+        }
+        variant_buf.push_str("        );\n");
+        variant_buf.push_str("    };\n");
+        variant_buf.push_str("}\n");
+
+        // the same for every generated message, profile or not
+        messages.sort();
+        variant_buf
+            .push_str("/// Calls the given macro with the path of every generated message\n");
+        variant_buf.push_str("#[macro_export]\n");
+        variant_buf.push_str("macro_rules! for_each_message {\n");
+        variant_buf.push_str("    ($callback:ident) => {\n");
+        variant_buf.push_str("        $callback!(\n");
+        for message in messages.iter() {
+            variant_buf.push_str("            $crate::");
+            variant_buf.push_str(message);
+            variant_buf.push_str(",\n");
+        }
+        variant_buf.push_str("        );\n");
+        variant_buf.push_str("    };\n");
         variant_buf.push_str("}\n");
 
         modules.insert(vec!["variant".to_string()], variant_buf);
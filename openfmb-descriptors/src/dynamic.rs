@@ -0,0 +1,891 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Messages decoded at runtime from their descriptors rather than from
+//! generated code, so tools may handle any OpenFMB payload including
+//! profiles of a newer schema than they were built with.
+
+use crate::OPENFMB_DESCRIPTORS;
+use lazy_static::lazy_static;
+use prost::bytes::{Buf, BufMut};
+use prost::encoding::{self, DecodeContext, WireType};
+use prost::{DecodeError, Message};
+use proto_types::field_descriptor_proto::{Label, Type};
+use proto_types::{DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorSet};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+lazy_static! {
+    static ref GLOBAL_POOL: RwLock<Arc<DescriptorPool>> =
+        RwLock::new(Arc::new(DescriptorPool::openfmb()));
+}
+
+/// Message and enum descriptors by their full name, e.g.
+/// `switchmodule.SwitchStatusProfile`
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorPool {
+    messages: HashMap<String, Arc<DescriptorProto>>,
+    enums: HashMap<String, Arc<EnumDescriptorProto>>,
+}
+
+impl DescriptorPool {
+    pub fn new() -> DescriptorPool {
+        DescriptorPool::default()
+    }
+
+    /// The descriptors of the OpenFMB schema this crate was built with
+    pub fn openfmb() -> DescriptorPool {
+        let mut pool = DescriptorPool::new();
+        pool.add(&OPENFMB_DESCRIPTORS);
+        pool
+    }
+
+    /// A pool of the encoded FileDescriptorSet written by
+    /// `protoc --include_imports --descriptor_set_out`
+    pub fn decode<B: Buf>(buf: B) -> Result<DescriptorPool, DecodeError> {
+        let mut pool = DescriptorPool::new();
+        pool.add(&FileDescriptorSet::decode(buf)?);
+        Ok(pool)
+    }
+
+    /// Add the messages and enums of every file in the set, replacing those
+    /// of the same name already in the pool
+    pub fn add(&mut self, set: &FileDescriptorSet) {
+        for file in set.file.iter() {
+            let package = file.package();
+            for message in file.message_type.iter() {
+                self.add_message(package, message);
+            }
+            for enumeration in file.enum_type.iter() {
+                self.enums.insert(
+                    full_name(package, enumeration.name()),
+                    Arc::new(enumeration.clone()),
+                );
+            }
+        }
+    }
+
+    fn add_message(&mut self, scope: &str, message: &DescriptorProto) {
+        let name = full_name(scope, message.name());
+        for nested in message.nested_type.iter() {
+            self.add_message(&name, nested);
+        }
+        for enumeration in message.enum_type.iter() {
+            self.enums.insert(
+                full_name(&name, enumeration.name()),
+                Arc::new(enumeration.clone()),
+            );
+        }
+        self.messages.insert(name, Arc::new(message.clone()));
+    }
+
+    /// The descriptor of a message by its full name
+    pub fn message(&self, name: &str) -> Option<&DescriptorProto> {
+        self.messages
+            .get(name.trim_start_matches('.'))
+            .map(Arc::as_ref)
+    }
+
+    /// The descriptor of an enum by its full name
+    pub fn enumeration(&self, name: &str) -> Option<&EnumDescriptorProto> {
+        self.enums
+            .get(name.trim_start_matches('.'))
+            .map(Arc::as_ref)
+    }
+
+    /// The pool buses decode dynamic messages with, by default the one of
+    /// the schema this crate was built with
+    pub fn global() -> Arc<DescriptorPool> {
+        GLOBAL_POOL
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Replace the global pool, e.g. with the descriptors of a newer schema
+    ///
+    /// Every bus of the process decodes with the new pool from then on, a
+    /// pool for some messages only is given to DynamicMessage::decode_type
+    /// or `openfmb::encoding::decode_dynamic` instead.
+    pub fn set_global(pool: DescriptorPool) {
+        *GLOBAL_POOL
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(pool);
+    }
+}
+
+fn full_name(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", scope, name)
+    }
+}
+
+/// The value of a field of a DynamicMessage
+///
+/// Repeated fields hold a List, enums their number.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    I32(i32),
+    I64(i64),
+    U32(u32),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Enum(i32),
+    Message(DynamicMessage),
+    List(Vec<Value>),
+}
+
+impl Value {
+    pub fn as_message(&self) -> Option<&DynamicMessage> {
+        match self {
+            Value::Message(msg) => Some(msg),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::I32(value) | Value::Enum(value) => write!(f, "{}", value),
+            Value::I64(value) => write!(f, "{}", value),
+            Value::U32(value) => write!(f, "{}", value),
+            Value::U64(value) => write!(f, "{}", value),
+            Value::F32(value) => write!(f, "{}", value),
+            Value::F64(value) => write!(f, "{}", value),
+            Value::String(value) => write!(f, "{:?}", value),
+            Value::Bytes(value) => write!(f, "{:?}", value),
+            Value::Message(msg) => fmt::Display::fmt(msg, f),
+            Value::List(values) => {
+                f.write_str("[")?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    fmt::Display::fmt(value, f)?;
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+/// Why a path could not be read or written, with the path up to the
+/// segment at fault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    /// A segment is not a field name with an optional `[index]`
+    InvalidSegment(String),
+    /// The message has no field of the name
+    UnknownField(String),
+    /// The pool does not know the message type of a field, or of the
+    /// message itself for an empty path
+    UnknownType(String),
+    /// A field followed by more segments is not a message
+    NotAMessage(String),
+    /// A repeated field followed by more segments has no index, a field that
+    /// is not repeated has one, or an index is past the end of the list
+    InvalidIndex(String),
+    /// The value set does not match the type of the field
+    TypeMismatch(String),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// A protobuf message of any type known to a DescriptorPool
+///
+/// Fields are set when they were on the wire, which in proto3 leaves out
+/// scalars at their default value. Fields not in the descriptor, such as
+/// those of a newer schema than the pool, are skipped when decoding.
+#[derive(Clone)]
+pub struct DynamicMessage {
+    pool: Arc<DescriptorPool>,
+    descriptor: Arc<DescriptorProto>,
+    type_name: String,
+    fields: BTreeMap<i32, Value>,
+}
+
+impl DynamicMessage {
+    /// An empty message of the given type, if the pool knows it
+    pub fn new(pool: Arc<DescriptorPool>, type_name: &str) -> Option<DynamicMessage> {
+        let type_name = type_name.trim_start_matches('.');
+        let descriptor = pool.messages.get(type_name)?.clone();
+        Some(DynamicMessage {
+            pool,
+            descriptor,
+            type_name: type_name.to_string(),
+            fields: BTreeMap::new(),
+        })
+    }
+
+    /// Decode a message of the given type, fails if the pool does not know
+    /// it
+    pub fn decode_type<B: Buf>(
+        pool: Arc<DescriptorPool>,
+        type_name: &str,
+        buf: B,
+    ) -> Result<DynamicMessage, DecodeError> {
+        let mut msg = DynamicMessage::new(pool, type_name)
+            .ok_or_else(|| DecodeError::new(format!("unknown message type {}", type_name)))?;
+        msg.merge(buf)?;
+        Ok(msg)
+    }
+
+    /// Full name of the message type, e.g. `switchmodule.SwitchStatusProfile`
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    pub fn descriptor(&self) -> &DescriptorProto {
+        &self.descriptor
+    }
+
+    /// The value of a field by its proto or JSON name, names differing only
+    /// in case match as well
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        let field = self.field(name)?;
+        self.fields.get(&field.number())
+    }
+
+    /// The value at a path of field names separated by dots, with an index
+    /// for elements of repeated fields, e.g. `switchStatus.switchStatusXSWI.Pos`
+    /// or `ess.conductingEquipment.mRID` or `values[2].mag`
+    ///
+    /// The value as it was on the wire, None for fields that were not, see
+    /// value_at for the value the generated code would have.
+    pub fn get_raw_path(&self, path: &str) -> Option<&Value> {
+        let mut msg = self;
+        let mut segments = path.split('.').peekable();
+        while let Some(segment) = segments.next() {
+            let (name, index) = parse_segment(segment)?;
+            let mut value = msg.get(name)?;
+            if let Some(index) = index {
+                value = value.as_list()?.get(index)?;
+            }
+            if segments.peek().is_none() {
+                return Some(value);
+            }
+            msg = value.as_message()?;
+        }
+        None
+    }
+
+    /// The value at a path as the generated code would have it: unset
+    /// scalars are their default, an unset repeated field is an empty list
+    /// and wrapper types such as `google.protobuf.FloatValue` are their
+    /// inner value. None when a message along the path, a wrapper or an
+    /// element is not set.
+    pub fn value_at(&self, path: &str) -> Result<Option<Value>, PathError> {
+        let mut msg = self;
+        let mut segments = path.split('.').peekable();
+        let mut end = 0;
+        while let Some(segment) = segments.next() {
+            end += segment.len();
+            let walked = &path[..end];
+            end += 1;
+            let (field, index) = msg.path_field(walked, segment)?;
+            let value = match (msg.fields.get(&field.number()), index) {
+                (Some(Value::List(values)), Some(index)) => values.get(index),
+                (value, None) => value,
+                (_, Some(_)) => None,
+            };
+            if segments.peek().is_none() {
+                return Ok(match value {
+                    Some(Value::Message(wrapper)) if is_wrapper(field) => {
+                        wrapper.fields.get(&1).cloned().or_else(|| {
+                            let inner = wrapper.descriptor.field.first()?;
+                            Some(wrapper.default_value(inner))
+                        })
+                    }
+                    Some(value) => Some(value.clone()),
+                    None if index.is_some() || field.r#type() == Type::Message => None,
+                    None => Some(msg.default_value(field)),
+                });
+            }
+            if field.r#type() != Type::Message {
+                return Err(PathError::NotAMessage(walked.to_string()));
+            }
+            if field.label() == Label::Repeated && index.is_none() {
+                return Err(PathError::InvalidIndex(walked.to_string()));
+            }
+            msg = match value {
+                Some(Value::Message(msg)) => msg,
+                _ => return Ok(None),
+            };
+        }
+        Ok(None)
+    }
+
+    /// Set the value at a path, creating the messages along it that are not
+    /// set
+    ///
+    /// Elements of repeated fields are replaced by their index, or appended
+    /// with the index one past the last element. A whole repeated field is
+    /// set with a List. Wrapper types are set by their inner value and enums
+    /// by their number or value name, with or without the prefix of the enum
+    /// name, e.g. `DbPosKind_closed` or `closed`. The message is left as it
+    /// was when the path or value is invalid.
+    pub fn set_path(&mut self, path: &str, value: Value) -> Result<(), PathError> {
+        // the messages along the path are created before the leaf is known
+        // to take the value, put the fields back when it does not
+        let fields = self.fields.clone();
+        let set = self.set_path_from(path, 0, value);
+        if set.is_err() {
+            self.fields = fields;
+        }
+        set
+    }
+
+    fn set_path_from(&mut self, path: &str, start: usize, value: Value) -> Result<(), PathError> {
+        let rest = &path[start..];
+        let (segment, next) = match rest.find('.') {
+            Some(dot) => (&rest[..dot], Some(start + dot + 1)),
+            None => (rest, None),
+        };
+        let walked = &path[..start + segment.len()];
+        let (field, index) = self.path_field(walked, segment)?;
+        let field = field.clone();
+        let value = match next {
+            None => self
+                .convert(&field, value, index.is_none())
+                .ok_or_else(|| PathError::TypeMismatch(walked.to_string()))?,
+            Some(_) if field.r#type() != Type::Message => {
+                return Err(PathError::NotAMessage(walked.to_string()));
+            }
+            Some(_) if field.label() == Label::Repeated && index.is_none() => {
+                return Err(PathError::InvalidIndex(walked.to_string()));
+            }
+            Some(_) => value,
+        };
+        let empty = || match next {
+            Some(_) => DynamicMessage::new(self.pool.clone(), field.type_name())
+                .map(Value::Message)
+                .ok_or_else(|| PathError::UnknownType(walked.to_string())),
+            None => Ok(value.clone()),
+        };
+        let slot = match index {
+            None => match self.fields.get(&field.number()) {
+                Some(_) if next.is_some() => self.fields.get_mut(&field.number()).unwrap(),
+                _ => {
+                    let empty = empty()?;
+                    self.fields.insert(field.number(), empty);
+                    self.fields.get_mut(&field.number()).unwrap()
+                }
+            },
+            Some(index) => {
+                let empty = empty()?;
+                let list = match self
+                    .fields
+                    .entry(field.number())
+                    .or_insert_with(|| Value::List(Vec::new()))
+                {
+                    Value::List(list) => list,
+                    _ => return Err(PathError::InvalidIndex(walked.to_string())),
+                };
+                match index.cmp(&list.len()) {
+                    std::cmp::Ordering::Less if next.is_none() => list[index] = empty,
+                    std::cmp::Ordering::Less => (),
+                    std::cmp::Ordering::Equal => list.push(empty),
+                    std::cmp::Ordering::Greater => {
+                        return Err(PathError::InvalidIndex(walked.to_string()))
+                    }
+                }
+                &mut list[index]
+            }
+        };
+        match (next, slot) {
+            (Some(next), Value::Message(msg)) => msg.set_path_from(path, next, value),
+            (Some(_), _) => Err(PathError::NotAMessage(walked.to_string())),
+            (None, _) => Ok(()),
+        }
+    }
+
+    /// The field named by a path segment, along with its index
+    fn path_field(
+        &self,
+        walked: &str,
+        segment: &str,
+    ) -> Result<(&FieldDescriptorProto, Option<usize>), PathError> {
+        let (name, index) =
+            parse_segment(segment).ok_or_else(|| PathError::InvalidSegment(walked.to_string()))?;
+        let field = self
+            .field(name)
+            .ok_or_else(|| PathError::UnknownField(walked.to_string()))?;
+        if index.is_some() && field.label() != Label::Repeated {
+            return Err(PathError::InvalidIndex(walked.to_string()));
+        }
+        Ok((field, index))
+    }
+
+    /// The value the generated code has for a field that is not set
+    fn default_value(&self, field: &FieldDescriptorProto) -> Value {
+        if field.label() == Label::Repeated {
+            return Value::List(Vec::new());
+        }
+        match field.r#type() {
+            Type::Double => Value::F64(0.0),
+            Type::Float => Value::F32(0.0),
+            Type::Int64 | Type::Sfixed64 | Type::Sint64 => Value::I64(0),
+            Type::Uint64 | Type::Fixed64 => Value::U64(0),
+            Type::Int32 | Type::Sfixed32 | Type::Sint32 => Value::I32(0),
+            Type::Uint32 | Type::Fixed32 => Value::U32(0),
+            Type::Bool => Value::Bool(false),
+            Type::Enum => Value::Enum(0),
+            Type::String => Value::String(String::new()),
+            Type::Bytes | Type::Group | Type::Message => Value::Bytes(Vec::new()),
+        }
+    }
+
+    /// The value as stored for the field, a list of them for a whole
+    /// repeated field, None if it does not match the type of the field
+    fn convert(&self, field: &FieldDescriptorProto, value: Value, whole: bool) -> Option<Value> {
+        if whole && field.label() == Label::Repeated {
+            return match value {
+                Value::List(values) => values
+                    .into_iter()
+                    .map(|value| self.convert(field, value, false))
+                    .collect::<Option<Vec<_>>>()
+                    .map(Value::List),
+                _ => None,
+            };
+        }
+        match (field.r#type(), value) {
+            (Type::Message, Value::Message(msg))
+                if msg.type_name == field.type_name().trim_start_matches('.') =>
+            {
+                Some(Value::Message(msg))
+            }
+            (Type::Message, value) if is_wrapper(field) => {
+                let mut wrapper = DynamicMessage::new(self.pool.clone(), field.type_name())?;
+                let inner = wrapper.descriptor.field.first()?.clone();
+                let value = wrapper.convert(&inner, value, false)?;
+                wrapper.fields.insert(inner.number(), value);
+                Some(Value::Message(wrapper))
+            }
+            (Type::Enum, Value::Enum(number)) | (Type::Enum, Value::I32(number)) => {
+                Some(Value::Enum(number))
+            }
+            (Type::Enum, Value::String(name)) => {
+                let enumeration = self.pool.enumeration(field.type_name())?;
+                let prefix = format!("{}_", enumeration.name());
+                enumeration
+                    .value
+                    .iter()
+                    .find(|value| {
+                        value.name() == name
+                            || matches!(value.name().strip_prefix(&prefix),
+                                Some(short) if short.eq_ignore_ascii_case(&name))
+                    })
+                    .map(|value| Value::Enum(value.number()))
+            }
+            (ty, value) if scalar_type(&value) == Some(ty) || scalar_alias(ty, &value) => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// The set fields in the order of the descriptor, by their proto name
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.descriptor.field.iter().filter_map(move |field| {
+            self.fields
+                .get(&field.number())
+                .map(|value| (field.name(), value))
+        })
+    }
+
+    fn field(&self, name: &str) -> Option<&FieldDescriptorProto> {
+        let fields = &self.descriptor.field;
+        fields
+            .iter()
+            .find(|field| field.name() == name || field.json_name() == name)
+            .or_else(|| {
+                fields.iter().find(|field| {
+                    field.name().eq_ignore_ascii_case(name)
+                        || field.json_name().eq_ignore_ascii_case(name)
+                })
+            })
+    }
+
+    fn enum_name(&self, field: &FieldDescriptorProto, number: i32) -> Option<&str> {
+        self.pool
+            .enumeration(field.type_name())?
+            .value
+            .iter()
+            .find(|value| value.number() == number)
+            .map(|value| value.name())
+    }
+
+    fn fmt_value(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        field: &FieldDescriptorProto,
+        value: &Value,
+        indent: usize,
+    ) -> fmt::Result {
+        match value {
+            Value::Message(msg) => {
+                write!(f, "{} {{", field.name())?;
+                msg.fmt_fields(f, indent + 1)?;
+                if f.alternate() {
+                    write!(f, "\n{:width$}}}", "", width = indent * 2)
+                } else {
+                    f.write_str(" }")
+                }
+            }
+            Value::Enum(number) => match self.enum_name(field, *number) {
+                Some(name) => write!(f, "{}: {}", field.name(), name),
+                None => write!(f, "{}: {}", field.name(), number),
+            },
+            value => write!(f, "{}: {}", field.name(), value),
+        }
+    }
+
+    /// Writes the fields in the protobuf text format, on one line or with
+    /// one field per line for the alternate `{:#}` format
+    fn fmt_fields(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        for field in self.descriptor.field.iter() {
+            let values = match self.fields.get(&field.number()) {
+                Some(Value::List(values)) => &values[..],
+                Some(value) => std::slice::from_ref(value),
+                None => continue,
+            };
+            for value in values {
+                if f.alternate() {
+                    write!(f, "\n{:width$}", "", width = indent * 2)?;
+                } else {
+                    f.write_str(" ")?;
+                }
+                self.fmt_value(f, field, value, indent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A path segment split into the field name and an optional index
+fn parse_segment(segment: &str) -> Option<(&str, Option<usize>)> {
+    match segment.find('[') {
+        Some(start) => {
+            let index = segment[start + 1..].strip_suffix(']')?.parse().ok()?;
+            Some((&segment[..start], Some(index)))
+        }
+        None => Some((segment, None)),
+    }
+}
+
+/// Whether the field holds one of the `google.protobuf` wrapper types,
+/// which the generated code maps to an optional scalar
+fn is_wrapper(field: &FieldDescriptorProto) -> bool {
+    field.r#type() == Type::Message && WRAPPERS.contains(&field.type_name())
+}
+
+/// The well known wrapper types, holding a single scalar
+const WRAPPERS: [&str; 9] = [
+    ".google.protobuf.DoubleValue",
+    ".google.protobuf.FloatValue",
+    ".google.protobuf.Int64Value",
+    ".google.protobuf.UInt64Value",
+    ".google.protobuf.Int32Value",
+    ".google.protobuf.UInt32Value",
+    ".google.protobuf.BoolValue",
+    ".google.protobuf.StringValue",
+    ".google.protobuf.BytesValue",
+];
+
+/// The field type a scalar value is decoded from, the varint one for types
+/// sharing a variant
+fn scalar_type(value: &Value) -> Option<Type> {
+    match value {
+        Value::Bool(_) => Some(Type::Bool),
+        Value::I32(_) => Some(Type::Int32),
+        Value::I64(_) => Some(Type::Int64),
+        Value::U32(_) => Some(Type::Uint32),
+        Value::U64(_) => Some(Type::Uint64),
+        Value::F32(_) => Some(Type::Float),
+        Value::F64(_) => Some(Type::Double),
+        Value::String(_) => Some(Type::String),
+        Value::Bytes(_) => Some(Type::Bytes),
+        Value::Enum(_) | Value::Message(_) | Value::List(_) => None,
+    }
+}
+
+/// The fixed and zigzag field types sharing a variant with a varint one
+fn scalar_alias(ty: Type, value: &Value) -> bool {
+    matches!(
+        (ty, value),
+        (Type::Sfixed32, Value::I32(_))
+            | (Type::Sint32, Value::I32(_))
+            | (Type::Sfixed64, Value::I64(_))
+            | (Type::Sint64, Value::I64(_))
+            | (Type::Fixed32, Value::U32(_))
+            | (Type::Fixed64, Value::U64(_))
+    )
+}
+
+impl PartialEq for DynamicMessage {
+    fn eq(&self, other: &DynamicMessage) -> bool {
+        self.type_name == other.type_name && self.fields == other.fields
+    }
+}
+
+/// Shows the type and fields, the pool is left out
+impl fmt::Debug for DynamicMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynamicMessage")
+            .field("type_name", &self.type_name)
+            .field("fields", &self.fields)
+            .finish()
+    }
+}
+
+/// The message in the protobuf text format, preceded by its type
+impl fmt::Display for DynamicMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {{", self.type_name)?;
+        self.fmt_fields(f, 1)?;
+        if f.alternate() {
+            f.write_str("\n}")
+        } else {
+            f.write_str(" }")
+        }
+    }
+}
+
+/// Merges a scalar field, repeated ones in packed or unpacked form
+macro_rules! merge_scalar {
+    ($field:expr, $module:ident, $variant:ident, $wire_type:expr, $buf:expr, $ctx:expr, $existing:expr) => {{
+        if $field.label() == Label::Repeated {
+            let mut values = Vec::new();
+            encoding::$module::merge_repeated($wire_type, &mut values, $buf, $ctx)?;
+            let mut list = match $existing {
+                Some(Value::List(list)) => list,
+                _ => Vec::new(),
+            };
+            list.extend(values.into_iter().map(Value::$variant));
+            Value::List(list)
+        } else {
+            let mut value = Default::default();
+            encoding::$module::merge($wire_type, &mut value, $buf, $ctx)?;
+            Value::$variant(value)
+        }
+    }};
+}
+
+/// Encodes a scalar field, or with `len` its encoded length, repeated
+/// numbers are packed as proto3 does by default
+macro_rules! encode_scalar {
+    ($module:ident, $variant:ident, $tag:expr, $value:expr, $buf:expr) => {
+        match $value {
+            Value::$variant(value) => encoding::$module::encode($tag, value, $buf),
+            Value::List(values) => {
+                let values: Vec<_> = values
+                    .iter()
+                    .filter_map(|value| match value {
+                        Value::$variant(value) => Some(value.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                encoding::$module::encode_packed($tag, &values, $buf)
+            }
+            _ => {}
+        }
+    };
+    (len $module:ident, $variant:ident, $tag:expr, $value:expr) => {
+        match $value {
+            Value::$variant(value) => encoding::$module::encoded_len($tag, value),
+            Value::List(values) => {
+                let values: Vec<_> = values
+                    .iter()
+                    .filter_map(|value| match value {
+                        Value::$variant(value) => Some(value.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                encoding::$module::encoded_len_packed($tag, &values)
+            }
+            _ => 0,
+        }
+    };
+}
+
+/// Strings and bytes are never packed
+macro_rules! encode_length_delimited {
+    ($module:ident, $variant:ident, $tag:expr, $value:expr, $buf:expr) => {
+        match $value {
+            Value::$variant(value) => encoding::$module::encode($tag, value, $buf),
+            Value::List(values) => {
+                for value in values {
+                    if let Value::$variant(value) = value {
+                        encoding::$module::encode($tag, value, $buf);
+                    }
+                }
+            }
+            _ => {}
+        }
+    };
+    (len $module:ident, $variant:ident, $tag:expr, $value:expr) => {
+        match $value {
+            Value::$variant(value) => encoding::$module::encoded_len($tag, value),
+            Value::List(values) => values
+                .iter()
+                .map(|value| match value {
+                    Value::$variant(value) => encoding::$module::encoded_len($tag, value),
+                    _ => 0,
+                })
+                .sum(),
+            _ => 0,
+        }
+    };
+}
+
+impl Message for DynamicMessage {
+    fn encode_raw<B: BufMut>(&self, buf: &mut B) {
+        for field in self.descriptor.field.iter() {
+            let value = match self.fields.get(&field.number()) {
+                Some(value) => value,
+                None => continue,
+            };
+            let tag = field.number() as u32;
+            match field.r#type() {
+                Type::Double => encode_scalar!(double, F64, tag, value, buf),
+                Type::Float => encode_scalar!(float, F32, tag, value, buf),
+                Type::Int64 => encode_scalar!(int64, I64, tag, value, buf),
+                Type::Uint64 => encode_scalar!(uint64, U64, tag, value, buf),
+                Type::Int32 => encode_scalar!(int32, I32, tag, value, buf),
+                Type::Fixed64 => encode_scalar!(fixed64, U64, tag, value, buf),
+                Type::Fixed32 => encode_scalar!(fixed32, U32, tag, value, buf),
+                Type::Bool => encode_scalar!(bool, Bool, tag, value, buf),
+                Type::Uint32 => encode_scalar!(uint32, U32, tag, value, buf),
+                Type::Enum => encode_scalar!(int32, Enum, tag, value, buf),
+                Type::Sfixed32 => encode_scalar!(sfixed32, I32, tag, value, buf),
+                Type::Sfixed64 => encode_scalar!(sfixed64, I64, tag, value, buf),
+                Type::Sint32 => encode_scalar!(sint32, I32, tag, value, buf),
+                Type::Sint64 => encode_scalar!(sint64, I64, tag, value, buf),
+                Type::String => encode_length_delimited!(string, String, tag, value, buf),
+                Type::Bytes => encode_length_delimited!(bytes, Bytes, tag, value, buf),
+                Type::Message => encode_length_delimited!(message, Message, tag, value, buf),
+                Type::Group => {}
+            }
+        }
+    }
+
+    fn merge_field<B: Buf>(
+        &mut self,
+        tag: u32,
+        wire_type: WireType,
+        buf: &mut B,
+        ctx: DecodeContext,
+    ) -> Result<(), DecodeError> {
+        let descriptor = self.descriptor.clone();
+        let field = match descriptor.field.iter().find(|f| f.number() as u32 == tag) {
+            Some(field) => field,
+            None => return encoding::skip_field(wire_type, tag, buf, ctx),
+        };
+        let existing = self.fields.remove(&field.number());
+        let value = match field.r#type() {
+            Type::Double => merge_scalar!(field, double, F64, wire_type, buf, ctx, existing),
+            Type::Float => merge_scalar!(field, float, F32, wire_type, buf, ctx, existing),
+            Type::Int64 => merge_scalar!(field, int64, I64, wire_type, buf, ctx, existing),
+            Type::Uint64 => merge_scalar!(field, uint64, U64, wire_type, buf, ctx, existing),
+            Type::Int32 => merge_scalar!(field, int32, I32, wire_type, buf, ctx, existing),
+            Type::Fixed64 => merge_scalar!(field, fixed64, U64, wire_type, buf, ctx, existing),
+            Type::Fixed32 => merge_scalar!(field, fixed32, U32, wire_type, buf, ctx, existing),
+            Type::Bool => merge_scalar!(field, bool, Bool, wire_type, buf, ctx, existing),
+            Type::Uint32 => merge_scalar!(field, uint32, U32, wire_type, buf, ctx, existing),
+            Type::Enum => merge_scalar!(field, int32, Enum, wire_type, buf, ctx, existing),
+            Type::Sfixed32 => merge_scalar!(field, sfixed32, I32, wire_type, buf, ctx, existing),
+            Type::Sfixed64 => merge_scalar!(field, sfixed64, I64, wire_type, buf, ctx, existing),
+            Type::Sint32 => merge_scalar!(field, sint32, I32, wire_type, buf, ctx, existing),
+            Type::Sint64 => merge_scalar!(field, sint64, I64, wire_type, buf, ctx, existing),
+            Type::String => merge_scalar!(field, string, String, wire_type, buf, ctx, existing),
+            Type::Bytes => merge_scalar!(field, bytes, Bytes, wire_type, buf, ctx, existing),
+            Type::Message => {
+                let mut msg = DynamicMessage::new(self.pool.clone(), field.type_name())
+                    .ok_or_else(|| {
+                        DecodeError::new(format!("unknown message type {}", field.type_name()))
+                    })?;
+                match existing {
+                    Some(Value::List(mut list)) => {
+                        encoding::message::merge(wire_type, &mut msg, buf, ctx)?;
+                        list.push(Value::Message(msg));
+                        Value::List(list)
+                    }
+                    // singular messages repeated on the wire are merged
+                    Some(Value::Message(mut existing)) => {
+                        encoding::message::merge(wire_type, &mut existing, buf, ctx)?;
+                        Value::Message(existing)
+                    }
+                    _ => {
+                        encoding::message::merge(wire_type, &mut msg, buf, ctx)?;
+                        if field.label() == Label::Repeated {
+                            Value::List(vec![Value::Message(msg)])
+                        } else {
+                            Value::Message(msg)
+                        }
+                    }
+                }
+            }
+            Type::Group => return Err(DecodeError::new("groups are not supported")),
+        };
+        self.fields.insert(field.number(), value);
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.descriptor
+            .field
+            .iter()
+            .filter_map(|field| {
+                let value = self.fields.get(&field.number())?;
+                let tag = field.number() as u32;
+                Some(match field.r#type() {
+                    Type::Double => encode_scalar!(len double, F64, tag, value),
+                    Type::Float => encode_scalar!(len float, F32, tag, value),
+                    Type::Int64 => encode_scalar!(len int64, I64, tag, value),
+                    Type::Uint64 => encode_scalar!(len uint64, U64, tag, value),
+                    Type::Int32 => encode_scalar!(len int32, I32, tag, value),
+                    Type::Fixed64 => encode_scalar!(len fixed64, U64, tag, value),
+                    Type::Fixed32 => encode_scalar!(len fixed32, U32, tag, value),
+                    Type::Bool => encode_scalar!(len bool, Bool, tag, value),
+                    Type::Uint32 => encode_scalar!(len uint32, U32, tag, value),
+                    Type::Enum => encode_scalar!(len int32, Enum, tag, value),
+                    Type::Sfixed32 => encode_scalar!(len sfixed32, I32, tag, value),
+                    Type::Sfixed64 => encode_scalar!(len sfixed64, I64, tag, value),
+                    Type::Sint32 => encode_scalar!(len sint32, I32, tag, value),
+                    Type::Sint64 => encode_scalar!(len sint64, I64, tag, value),
+                    Type::String => encode_length_delimited!(len string, String, tag, value),
+                    Type::Bytes => encode_length_delimited!(len bytes, Bytes, tag, value),
+                    Type::Message => encode_length_delimited!(len message, Message, tag, value),
+                    Type::Group => 0,
+                })
+            })
+            .sum()
+    }
+
+    fn clear(&mut self) {
+        self.fields.clear();
+    }
+}
@@ -6,6 +6,8 @@ use lazy_static::lazy_static;
 use prost::Message;
 use proto_types::FileDescriptorSet;
 
+pub use proto_types;
+
 pub static OPENFMB_DESCRIPTORS_BUF: &'static [u8] =
     include_bytes!(concat!(env!("OUT_DIR"), "/openfmb_descriptors.pb"));
 
@@ -13,3 +15,5 @@ lazy_static! {
     pub static ref OPENFMB_DESCRIPTORS: FileDescriptorSet =
         FileDescriptorSet::decode(OPENFMB_DESCRIPTORS_BUF).unwrap();
 }
+
+pub mod dynamic;
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use openfmb_messages::{breakermodule::*, commonmodule::*};
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::{str::FromStr, time::SystemTime};
 use uuid::Uuid;
 
@@ -12,21 +12,17 @@ use crate::{error::*, ControlProfileExt, OpenFMBExt, Phase};
 impl OpenFMBExt for BreakerDiscreteControlProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
         let state = if self
-            .breaker_discrete_control
-            .as_ref()
-            .context(NoBreakerDiscreteControl)?
-            .breaker_discrete_control_xcbr
-            .as_ref()
-            .context(NoBreakerDiscreteControlXcbr)?
-            .discrete_control_xcbr
-            .as_ref()
-            .context(NoDiscreteControlXcbr)?
-            .pos
-            .as_ref()
-            .context(NoPos)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)?
+            .try_breaker_discrete_control_and_then(|breaker_discrete_control| {
+                breaker_discrete_control.try_breaker_discrete_control_xcbr_and_then(
+                    |breaker_discrete_control_xcbr| {
+                        breaker_discrete_control_xcbr.try_discrete_control_xcbr_and_then(
+                            |discrete_control_xcbr| {
+                                discrete_control_xcbr.try_pos_and_then(|pos| pos.try_phs3())
+                            },
+                        )
+                    },
+                )
+            })?
             .ctl_val
         {
             "Request Closed"
@@ -37,13 +33,11 @@ impl OpenFMBExt for BreakerDiscreteControlProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .control_message_info
-            .as_ref()
-            .context(NoControlMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_control_message_info_and_then(|control_message_info| {
+                control_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -51,33 +45,23 @@ impl OpenFMBExt for BreakerDiscreteControlProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .breaker
-                .as_ref()
-                .context(NoBreaker)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_breaker_and_then(|breaker| breaker.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .breaker
-            .as_ref()
-            .context(NoBreaker)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_breaker_and_then(|breaker| {
+                breaker.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
@@ -22,18 +22,9 @@ impl OpenFMBExt for BreakerReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoBreakerReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag
                 .to_string());
         }
@@ -41,13 +32,11 @@ impl OpenFMBExt for BreakerReadingProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoReadingMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_reading_message_info_and_then(|reading_message_info| {
+                reading_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -55,42 +44,29 @@ impl OpenFMBExt for BreakerReadingProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .breaker
-                .as_ref()
-                .context(NoBreaker)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_breaker_and_then(|breaker| breaker.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .breaker
-            .as_ref()
-            .context(NoBreaker)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_breaker_and_then(|breaker| {
+                breaker.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtReading for BreakerReadingProfile {
     fn reading_message_info(&self) -> OpenFMBResult<&ReadingMessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_reading_message_info()?)
     }
 }
 
@@ -102,18 +78,9 @@ impl ReadingProfileExt for BreakerReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoBreakerReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoBreakerReading)
@@ -126,18 +93,9 @@ impl ReadingProfileExt for BreakerReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoBreakerReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoBreakerReading)
@@ -150,18 +108,10 @@ impl ReadingProfileExt for BreakerReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoBreakerReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .v_ar
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoBreakerReading)
@@ -174,18 +124,10 @@ impl ReadingProfileExt for BreakerReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoBreakerReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .v_ar
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoBreakerReading)
@@ -198,18 +140,10 @@ impl ReadingProfileExt for BreakerReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoBreakerReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .ph_v
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoBreakerReading)
@@ -222,18 +156,10 @@ impl ReadingProfileExt for BreakerReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoBreakerReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .ph_v
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoBreakerReading)
@@ -246,18 +172,9 @@ impl ReadingProfileExt for BreakerReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoBreakerReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .a
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoBreakerReading)
@@ -270,18 +187,9 @@ impl ReadingProfileExt for BreakerReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoBreakerReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .a
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoBreakerReading)
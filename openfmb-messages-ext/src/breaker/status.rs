@@ -9,56 +9,40 @@ use openfmb_messages::{
     commonmodule::{DbPosKind, MessageInfo, StatusMessageInfo},
     *,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 use crate::{error::*, OpenFMBExt, OpenFMBExtStatus, Phase, Position};
 
 impl OpenFMBExtStatus for BreakerStatusProfile {
     fn status_message_info(&self) -> OpenFMBResult<&StatusMessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_status_message_info()?)
     }
 }
 
 impl OpenFMBExt for BreakerStatusProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .breaker_status
-            .as_ref()
-            .context(NoBreakerStatus)?
-            .status_and_event_xcbr
-            .as_ref()
-            .context(NoStatusAndEventXcbr)?
-            .pos
-            .as_ref()
-            .context(NoPos)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)
-        {
-            Ok(v) => match v.st_val {
-                0 => Ok("Undefined".into()),
-                1 => Ok("Transient".into()),
-                2 => Ok("Closed".into()),
-                3 => Ok("Open".into()),
-                4 => Ok("Invalid".into()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let v = self.try_breaker_status_and_then(|breaker_status| {
+            breaker_status.try_status_and_event_xcbr_and_then(|status_and_event_xcbr| {
+                status_and_event_xcbr.try_pos_and_then(|pos| pos.try_phs3())
+            })
+        })?;
+        match v.st_val {
+            0 => Ok("Undefined".into()),
+            1 => Ok("Transient".into()),
+            2 => Ok("Closed".into()),
+            3 => Ok("Open".into()),
+            4 => Ok("Invalid".into()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_status_message_info_and_then(|status_message_info| {
+                status_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -66,33 +50,23 @@ impl OpenFMBExt for BreakerStatusProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .breaker
-                .as_ref()
-                .context(NoBreaker)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_breaker_and_then(|breaker| breaker.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .breaker
-            .as_ref()
-            .context(NoBreaker)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_breaker_and_then(|breaker| {
+                breaker.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
@@ -104,64 +78,36 @@ impl Position for BreakerStatusProfile {
     fn pos_per_phase(&self, phase: Phase) -> OpenFMBResult<DbPosKind> {
         let st_val = match phase {
             Phase::Phs3 => {
-                self.breaker_status
-                    .as_ref()
-                    .context(NoBreakerStatus)?
-                    .status_and_event_xcbr
-                    .as_ref()
-                    .context(NoStatusAndEventXcbr)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs3
-                    .as_ref()
-                    .context(NoPhs3)?
-                    .st_val
+                self.try_breaker_status_and_then(|breaker_status| {
+                    breaker_status.try_status_and_event_xcbr_and_then(|status_and_event_xcbr| {
+                        status_and_event_xcbr.try_pos_and_then(|pos| pos.try_phs3())
+                    })
+                })?
+                .st_val
             }
             Phase::PhsA => {
-                self.breaker_status
-                    .as_ref()
-                    .context(NoBreakerStatus)?
-                    .status_and_event_xcbr
-                    .as_ref()
-                    .context(NoStatusAndEventXcbr)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs_a
-                    .as_ref()
-                    .context(NoPhsA)?
-                    .st_val
+                self.try_breaker_status_and_then(|breaker_status| {
+                    breaker_status.try_status_and_event_xcbr_and_then(|status_and_event_xcbr| {
+                        status_and_event_xcbr.try_pos_and_then(|pos| pos.try_phs_a())
+                    })
+                })?
+                .st_val
             }
             Phase::PhsB => {
-                self.breaker_status
-                    .as_ref()
-                    .context(NoBreakerStatus)?
-                    .status_and_event_xcbr
-                    .as_ref()
-                    .context(NoStatusAndEventXcbr)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs_b
-                    .as_ref()
-                    .context(NoPhsB)?
-                    .st_val
+                self.try_breaker_status_and_then(|breaker_status| {
+                    breaker_status.try_status_and_event_xcbr_and_then(|status_and_event_xcbr| {
+                        status_and_event_xcbr.try_pos_and_then(|pos| pos.try_phs_b())
+                    })
+                })?
+                .st_val
             }
             Phase::PhsC => {
-                self.breaker_status
-                    .as_ref()
-                    .context(NoBreakerStatus)?
-                    .status_and_event_xcbr
-                    .as_ref()
-                    .context(NoStatusAndEventXcbr)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs_c
-                    .as_ref()
-                    .context(NoPhsC)?
-                    .st_val
+                self.try_breaker_status_and_then(|breaker_status| {
+                    breaker_status.try_status_and_event_xcbr_and_then(|status_and_event_xcbr| {
+                        status_and_event_xcbr.try_pos_and_then(|pos| pos.try_phs_c())
+                    })
+                })?
+                .st_val
             }
         };
 
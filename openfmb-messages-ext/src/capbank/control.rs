@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::{str::FromStr, time::SystemTime};
 use uuid::Uuid;
 
@@ -17,13 +17,11 @@ impl OpenFMBExt for CapBankControlProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .control_message_info
-            .as_ref()
-            .context(NoControlMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_control_message_info_and_then(|control_message_info| {
+                control_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -31,33 +29,25 @@ impl OpenFMBExt for CapBankControlProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .cap_bank_system
-                .as_ref()
-                .context(NoCapBankSystem)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_cap_bank_system_and_then(|cap_bank_system| {
+                    cap_bank_system.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .cap_bank_system
-            .as_ref()
-            .context(NoCapBankSystem)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_cap_bank_system_and_then(|cap_bank_system| {
+                cap_bank_system.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
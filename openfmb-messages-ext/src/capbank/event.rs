@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,10 +13,7 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtEvent};
 
 impl OpenFMBExtEvent for CapBankEventProfile {
     fn event_message_info(&self) -> OpenFMBResult<&EventMessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?)
+        Ok(self.try_event_message_info()?)
     }
 }
 
@@ -26,13 +23,9 @@ impl OpenFMBExt for CapBankEventProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(self.try_event_message_info_and_then(|event_message_info| {
+            event_message_info.try_message_info()
+        })?)
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -40,32 +33,24 @@ impl OpenFMBExt for CapBankEventProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .cap_bank_system
-                .as_ref()
-                .context(NoCapBankSystem)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_cap_bank_system_and_then(|cap_bank_system| {
+                    cap_bank_system.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .cap_bank_system
-            .as_ref()
-            .context(NoCapBankSystem)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_cap_bank_system_and_then(|cap_bank_system| {
+                cap_bank_system.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
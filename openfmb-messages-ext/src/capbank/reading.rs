@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,10 +13,7 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtReading, ReadingProfileExt};
 
 impl OpenFMBExtReading for CapBankReadingProfile {
     fn reading_message_info(&self) -> OpenFMBResult<&ReadingMessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoReadingMessageInfo)?)
+        Ok(self.try_reading_message_info()?)
     }
 }
 
@@ -26,13 +23,11 @@ impl OpenFMBExt for CapBankReadingProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoReadingMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_reading_message_info_and_then(|reading_message_info| {
+                reading_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -40,114 +35,68 @@ impl OpenFMBExt for CapBankReadingProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .cap_bank_system
-                .as_ref()
-                .context(NoCapBankSystem)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_cap_bank_system_and_then(|cap_bank_system| {
+                    cap_bank_system.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .cap_bank_system
-            .as_ref()
-            .context(NoCapBankSystem)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_cap_bank_system_and_then(|cap_bank_system| {
+                cap_bank_system.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl ReadingProfileExt for CapBankReadingProfile {
     fn w_net(&self) -> OpenFMBResult<f64> {
         return Ok(self
-            .cap_bank_reading
-            .as_ref()
-            .context(NoCapBankReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .w
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_cap_bank_reading_and_then(|cap_bank_reading| {
+                cap_bank_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag);
     }
 
     fn q_net(&self) -> OpenFMBResult<f64> {
         return Ok(self
-            .cap_bank_reading
-            .as_ref()
-            .context(NoCapBankReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .v_ar
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_cap_bank_reading_and_then(|cap_bank_reading| {
+                cap_bank_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag);
     }
 
     fn v_net(&self) -> OpenFMBResult<f64> {
         return Ok(self
-            .cap_bank_reading
-            .as_ref()
-            .context(NoCapBankReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .ph_v
-            .as_ref()
-            .context(NoValue)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_cap_bank_reading_and_then(|cap_bank_reading| {
+                cap_bank_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag);
     }
 
     fn a_net(&self) -> OpenFMBResult<f64> {
         return Ok(self
-            .cap_bank_reading
-            .as_ref()
-            .context(NoCapBankReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .a
-            .as_ref()
-            .context(NoValue)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_cap_bank_reading_and_then(|cap_bank_reading| {
+                cap_bank_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag);
     }
 }
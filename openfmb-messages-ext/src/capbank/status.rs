@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,10 +13,7 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtStatus, Phase, Position, StatusProfi
 
 impl OpenFMBExtStatus for CapBankStatusProfile {
     fn status_message_info(&self) -> OpenFMBResult<&StatusMessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_status_message_info()?)
     }
 }
 
@@ -26,13 +23,11 @@ impl OpenFMBExt for CapBankStatusProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_status_message_info_and_then(|status_message_info| {
+                status_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -40,33 +35,25 @@ impl OpenFMBExt for CapBankStatusProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .cap_bank_system
-                .as_ref()
-                .context(NoCapBankSystem)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_cap_bank_system_and_then(|cap_bank_system| {
+                    cap_bank_system.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .cap_bank_system
-            .as_ref()
-            .context(NoCapBankSystem)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_cap_bank_system_and_then(|cap_bank_system| {
+                cap_bank_system.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
@@ -78,64 +65,44 @@ impl Position for CapBankStatusProfile {
     fn pos_per_phase(&self, phase: Phase) -> OpenFMBResult<DbPosKind> {
         let st_val = match phase {
             Phase::Phs3 => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs3
-                    .as_ref()
-                    .context(NoPhs3)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh.try_pos_and_then(|pos| pos.try_phs3())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsA => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs_a
-                    .as_ref()
-                    .context(NoPhsA)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh.try_pos_and_then(|pos| pos.try_phs_a())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsB => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs_b
-                    .as_ref()
-                    .context(NoPhsB)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh.try_pos_and_then(|pos| pos.try_phs_b())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsC => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs_c
-                    .as_ref()
-                    .context(NoPhsC)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh.try_pos_and_then(|pos| pos.try_phs_c())
+                        },
+                    )
+                })?
+                .st_val
             }
         };
 
@@ -163,64 +130,48 @@ impl CapBankStatusExt for CapBankStatusProfile {
     fn temp_lmt(&self, phase: Phase) -> OpenFMBResult<bool> {
         Ok(match phase {
             Phase::Phs3 => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .temp_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs3
-                    .as_ref()
-                    .context(NoPhs3)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_temp_lmt_and_then(|temp_lmt| temp_lmt.try_phs3())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsA => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .temp_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_a
-                    .as_ref()
-                    .context(NoPhsA)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_temp_lmt_and_then(|temp_lmt| temp_lmt.try_phs_a())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsB => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .temp_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_b
-                    .as_ref()
-                    .context(NoPhsB)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_temp_lmt_and_then(|temp_lmt| temp_lmt.try_phs_b())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsC => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .temp_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_c
-                    .as_ref()
-                    .context(NoPhsC)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_temp_lmt_and_then(|temp_lmt| temp_lmt.try_phs_c())
+                        },
+                    )
+                })?
+                .st_val
             }
         })
     }
@@ -228,64 +179,48 @@ impl CapBankStatusExt for CapBankStatusProfile {
     fn var_lmt(&self, phase: Phase) -> OpenFMBResult<bool> {
         Ok(match phase {
             Phase::Phs3 => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .v_ar_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs3
-                    .as_ref()
-                    .context(NoPhs3)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_v_ar_lmt_and_then(|v_ar_lmt| v_ar_lmt.try_phs3())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsA => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .v_ar_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_a
-                    .as_ref()
-                    .context(NoPhsA)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_v_ar_lmt_and_then(|v_ar_lmt| v_ar_lmt.try_phs_a())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsB => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .v_ar_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_b
-                    .as_ref()
-                    .context(NoPhsB)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_v_ar_lmt_and_then(|v_ar_lmt| v_ar_lmt.try_phs_b())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsC => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .v_ar_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_c
-                    .as_ref()
-                    .context(NoPhsC)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_v_ar_lmt_and_then(|v_ar_lmt| v_ar_lmt.try_phs_c())
+                        },
+                    )
+                })?
+                .st_val
             }
         })
     }
@@ -293,64 +228,48 @@ impl CapBankStatusExt for CapBankStatusProfile {
     fn vol_lmt(&self, phase: Phase) -> OpenFMBResult<bool> {
         Ok(match phase {
             Phase::Phs3 => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .vol_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs3
-                    .as_ref()
-                    .context(NoPhs3)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_vol_lmt_and_then(|vol_lmt| vol_lmt.try_phs3())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsA => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .vol_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_a
-                    .as_ref()
-                    .context(NoPhsA)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_vol_lmt_and_then(|vol_lmt| vol_lmt.try_phs_a())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsB => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .vol_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_b
-                    .as_ref()
-                    .context(NoPhsB)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_vol_lmt_and_then(|vol_lmt| vol_lmt.try_phs_b())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsC => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .vol_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_c
-                    .as_ref()
-                    .context(NoPhsC)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_vol_lmt_and_then(|vol_lmt| vol_lmt.try_phs_c())
+                        },
+                    )
+                })?
+                .st_val
             }
         })
     }
@@ -358,64 +277,48 @@ impl CapBankStatusExt for CapBankStatusProfile {
     fn amp_lmt(&self, phase: Phase) -> OpenFMBResult<bool> {
         Ok(match phase {
             Phase::Phs3 => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .amp_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs3
-                    .as_ref()
-                    .context(NoPhs3)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_amp_lmt_and_then(|amp_lmt| amp_lmt.try_phs3())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsA => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .amp_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_a
-                    .as_ref()
-                    .context(NoPhsA)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_amp_lmt_and_then(|amp_lmt| amp_lmt.try_phs_a())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsB => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .amp_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_b
-                    .as_ref()
-                    .context(NoPhsB)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_amp_lmt_and_then(|amp_lmt| amp_lmt.try_phs_b())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsC => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .amp_lmt
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_c
-                    .as_ref()
-                    .context(NoPhsC)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_amp_lmt_and_then(|amp_lmt| amp_lmt.try_phs_c())
+                        },
+                    )
+                })?
+                .st_val
             }
         })
     }
@@ -423,64 +326,48 @@ impl CapBankStatusExt for CapBankStatusProfile {
     fn dir_rev(&self, phase: Phase) -> OpenFMBResult<bool> {
         Ok(match phase {
             Phase::Phs3 => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .dir_rev
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs3
-                    .as_ref()
-                    .context(NoPhs3)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_dir_rev_and_then(|dir_rev| dir_rev.try_phs3())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsA => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .dir_rev
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_a
-                    .as_ref()
-                    .context(NoPhsA)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_dir_rev_and_then(|dir_rev| dir_rev.try_phs_a())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsB => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .dir_rev
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_b
-                    .as_ref()
-                    .context(NoPhsB)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_dir_rev_and_then(|dir_rev| dir_rev.try_phs_b())
+                        },
+                    )
+                })?
+                .st_val
             }
             Phase::PhsC => {
-                self.cap_bank_status
-                    .as_ref()
-                    .context(NoCapBankStatus)?
-                    .cap_bank_event_and_status_ypsh
-                    .as_ref()
-                    .context(NoCapBankEventAndStatusYpsh)?
-                    .dir_rev
-                    .as_ref()
-                    .context(NoValue)?
-                    .phs_c
-                    .as_ref()
-                    .context(NoPhsC)?
-                    .st_val
+                self.try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh
+                                .try_dir_rev_and_then(|dir_rev| dir_rev.try_phs_c())
+                        },
+                    )
+                })?
+                .st_val
             }
         })
     }
@@ -488,15 +375,13 @@ impl CapBankStatusExt for CapBankStatusProfile {
     fn ctl_mode(&self) -> OpenFMBResult<ControlModeKind> {
         Ok(
             match self
-                .cap_bank_status
-                .as_ref()
-                .context(NoCapBankStatus)?
-                .cap_bank_event_and_status_ypsh
-                .as_ref()
-                .context(NoCapBankEventAndStatusYpsh)?
-                .ctl_mode
-                .as_ref()
-                .context(NoValue)?
+                .try_cap_bank_status_and_then(|cap_bank_status| {
+                    cap_bank_status.try_cap_bank_event_and_status_ypsh_and_then(
+                        |cap_bank_event_and_status_ypsh| {
+                            cap_bank_event_and_status_ypsh.try_ctl_mode()
+                        },
+                    )
+                })?
                 .value
             {
                 1 => ControlModeKind::Auto,
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,10 +13,7 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtEvent};
 
 impl OpenFMBExtEvent for CircuitSegmentEventProfile {
     fn event_message_info(&self) -> OpenFMBResult<&EventMessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?)
+        Ok(self.try_event_message_info()?)
     }
 }
 
@@ -26,13 +23,9 @@ impl OpenFMBExt for CircuitSegmentEventProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(self.try_event_message_info_and_then(|event_message_info| {
+            event_message_info.try_message_info()
+        })?)
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -40,26 +33,14 @@ impl OpenFMBExt for CircuitSegmentEventProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
-            &self
-                .application_system
-                .as_ref()
-                .context(NoApplicationSystem)?
-                .m_rid,
-        )
-        .context(UuidError)?)
+        Uuid::from_str(&self.try_application_system()?.m_rid).context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .application_system
-            .as_ref()
-            .context(NoApplicationSystem)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_application_system_and_then(|application_system| {
+                application_system.try_named_object_and_then(|named_object| named_object.try_name())
+            })
+            .cloned()?)
     }
 }
@@ -18,31 +18,17 @@ pub trait HasIdentifiedObject {
 
     /// Get the mrid of the identified object
     fn get_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::parse_str(
-            self.get_identified_object()?
-                .m_rid
-                .as_ref()
-                .context(NoMRID)?,
-        )
-        .context(UuidError)?)
+        Ok(Uuid::parse_str(self.get_identified_object()?.try_m_rid()?).context(UuidError)?)
     }
 
     /// Get the name of the identified object
     fn get_name(&self) -> OpenFMBResult<&str> {
-        Ok(self
-            .get_identified_object()?
-            .name
-            .as_ref()
-            .context(NoName)?)
+        Ok(self.get_identified_object()?.try_name()?)
     }
 
     /// Get the description of the identifed object
     fn get_description(&self) -> OpenFMBResult<&str> {
-        Ok(self
-            .get_identified_object()?
-            .description
-            .as_ref()
-            .context(NoName)?)
+        Ok(self.get_identified_object()?.try_description()?)
     }
 }
 
@@ -113,33 +99,21 @@ pub trait HasMessageInfo {
 /// Default implementation for types that implement HasStatusMessageInfo
 impl HasMessageInfo for dyn HasStatusMessageInfo {
     fn get_message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .get_status_message_info()?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(self.get_status_message_info()?.try_message_info()?)
     }
 }
 
 /// Default implementation for types that implement HasReadingMessageInfo
 impl HasMessageInfo for dyn HasReadingMessageInfo {
     fn get_message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .get_reading_message_info()?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(self.get_reading_message_info()?.try_message_info()?)
     }
 }
 
 /// Default implementation for types that implement HasConductingEquipment
 impl<T: HasConductingEquipment> HasNamedObject for T {
     fn get_named_object(&self) -> OpenFMBResult<&NamedObject> {
-        Ok(self
-            .get_conducting_equipment()?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?)
+        Ok(self.get_conducting_equipment()?.try_named_object()?)
     }
 }
 
@@ -153,7 +127,7 @@ impl<T: HasConductingEquipment> HasDeviceMRID for T {
 /// Default implementation for types that implement HasNamedObject
 impl<T: HasNamedObject> HasDeviceName for T {
     fn get_device_name(&self) -> OpenFMBResult<&str> {
-        Ok(self.get_named_object()?.name.as_ref().context(NoName)?)
+        Ok(self.get_named_object()?.try_name()?)
     }
 }
 
@@ -162,9 +136,7 @@ impl<T: HasLogicalNodeForEventAndStatus> HasHealthKind for T {
     fn get_health_kind(&self) -> OpenFMBResult<HealthKind> {
         Ok(self
             .get_logical_node_for_event_and_status()?
-            .ee_health
-            .as_ref()
-            .context(NoEeHealth)?
+            .try_ee_health()?
             .st_val())
     }
 }
@@ -173,9 +145,7 @@ impl<T: HasLogicalNodeForEventAndStatus> HasBehaviourModeKind for T {
     fn get_behaviour_mode_kind(&self) -> OpenFMBResult<BehaviourModeKind> {
         Ok(self
             .get_logical_node_for_event_and_status()?
-            .beh
-            .as_ref()
-            .context(NoBeh)?
+            .try_beh()?
             .st_val())
     }
 }
@@ -184,65 +154,48 @@ impl<T: HasLogicalNodeForEventAndStatus> HasBehaviourModeKind for T {
 impl<T: HasLogicalNodeForEventAndStatus> HasLogicalNode for T {
     fn get_logical_node(&self) -> OpenFMBResult<&LogicalNode> {
         self.get_logical_node_for_event_and_status()?
-            .logical_node
-            .as_ref()
-            .context(NoLogicalNode)
+            .try_logical_node()
     }
 }
 
 /// Default implementation of HasIdentifiedObject for types that implements HasLogicalNode
 impl<T: HasLogicalNode> HasIdentifiedObject for T {
     fn get_identified_object(&self) -> OpenFMBResult<&IdentifiedObject> {
-        self.get_logical_node()?
-            .identified_object
-            .as_ref()
-            .context(NoIdentifiedObject)
+        self.get_logical_node()?.try_identified_object()
     }
 }
 
 /// MessageInfo has IdentifiedObject
 impl HasIdentifiedObject for MessageInfo {
     fn get_identified_object(&self) -> OpenFMBResult<&IdentifiedObject> {
-        self.identified_object.as_ref().context(NoIdentifiedObject)
+        self.try_identified_object()
     }
 }
 
 /// Meter has ConductingEquipment
 impl HasConductingEquipment for Meter {
     fn get_conducting_equipment(&self) -> OpenFMBResult<&ConductingEquipment> {
-        Ok(self
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?)
+        Ok(self.try_conducting_equipment()?)
     }
 }
 
 /// EnergyConsumer has ConductingEquipment
 impl HasConductingEquipment for EnergyConsumer {
     fn get_conducting_equipment(&self) -> OpenFMBResult<&ConductingEquipment> {
-        Ok(self
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?)
+        Ok(self.try_conducting_equipment()?)
     }
 }
 
 /// Ess has ConductingEquipment
 impl HasConductingEquipment for Ess {
     fn get_conducting_equipment(&self) -> OpenFMBResult<&ConductingEquipment> {
-        Ok(self
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?)
+        Ok(self.try_conducting_equipment()?)
     }
 }
 
 /// Ied has IdentifiedObject
 impl HasIdentifiedObject for Ied {
     fn get_identified_object(&self) -> OpenFMBResult<&IdentifiedObject> {
-        Ok(self
-            .identified_object
-            .as_ref()
-            .context(NoIdentifiedObject)?)
+        Ok(self.try_identified_object()?)
     }
 }
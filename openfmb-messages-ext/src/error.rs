@@ -4,11 +4,15 @@
 
 use snafu::Snafu;
 
+pub use openfmb_messages::MissingField;
+
 /// OpenFMBError type provides a very specific cause for a failure
 ///
 /// This is useful over the typical Option<T> in many scenarios as a deeply
 /// nested Option<T> may fail at one of many levels of Option<T> unwrapping,
 /// instead we provide a Result<T, E> with an error that self describes what was missing.
+/// Fields missing from a message are reported by the generated `try_<field>()`
+/// accessors as a MissingFieldError naming the field by its path from the profile.
 #[derive(Debug, Snafu)]
 #[snafu(visibility = "pub(crate)")]
 pub enum OpenFMBError {
@@ -20,136 +24,17 @@ pub enum OpenFMBError {
     UnsupportedOpenFMBTypeError {
         fmb_type: String,
     },
-    NoProtectedSwitch,
-    NoDiscreteBreaker,
-    NoConductingEquipment,
-    NoReadingMessageInfo,
-    NoControlMessageInfo,
-    NoMessageInfo,
-    NoIdentifiedObject,
-    NoLogicalNode,
-    NoLogicalControlNode,
-    NoIED,
     InvalidOpenFMBMessage,
-    NoMRID,
-    NoStatusMessageInfo,
-    NoMessageTimestamp,
-    NoMeter,
-    NoMeterReading,
-    NoSolarInverter,
-    NoEnergyConsumer,
-    NoRegulatorSystem,
-    NoRegulatorReading,
-    NoRegulatorStatus,
-    NoRegulatorEvent,
-    NoRegulatorControl,
-    NoRegulatorEventAndStatusAncr,
-    NoTapPos,
-    NoBndWidHi,
-    NoBndWidLo,
-    NoVolLmtHi,
-    NoVolLmtLo,
-    NoVoltageSetPointEnabled,
-    NoEss,
-    NoGenerationStatus,
-    NoGenerationEvent,
-    NoGenerationStatusZGen,
-    NoGenerationEventZGen,
-    NoGenerationEventAndStatusZGen,
-    NoGenerationControl,
-    NoGenerationReading,
-    NoRecloser,
     NoRecloserReading,
-    NoRecloserStatus,
-    NoRecloserEvent,
-    NoControlValue,
-    NoShuntSystem,
-    NoBreaker,
-    NoName,
-    NoPos,
-    NoLoadReading,
-    NoLoadControl,
-    NoLoadEvent,
-    NoLoadEventZGld,
-    NoLoadStatus,
-    NoLoadStatusZGld,
-    NoLoadEventAndStatusZGld,
-    NoNamedObject,
-    NoGeneratingUnit,
-    NoSolarStatus,
-    NoSolarEvent,
-    NoSolarReading,
-    NoSolarStatusZGen,
-    NoSolarEventZGen,
-    NoSolarEventAndStatusZGen,
-    NoPointStatus,
     NoState,
-    NoBreakerDiscreteControl,
-    NoBreakerDiscreteControlXcbr,
-    NoDiscreteControlXcbr,
-    NoBreakerStatus,
-    NoBreakerEvent,
     NoBreakerReading,
-    NoPhs3,
-    NoPhsA,
-    NoPhsB,
-    NoPhsC,
-    NoTapOpErr,
-    NoHotLineTag,
-    NoStatusAndEventXcbr,
-    NoEssStatus,
-    NoEssStatusZBat,
-    NoEssStatusZGen,
-    NoEssEventAndStatusZGen,
-    NoEssEvent,
-    NoEssEventZGen,
-    NoEssGnSyncSt,
-    NoEssReading,
-    NoEssCapability,
-    NoMode,
-    NoSoc,
-    NoReadingMmxu,
-    NoW,
-    NoNet,
-    NoCVal,
-    NoMag,
-    NoF,
-    NoLogicalNodeForEventAndStatus,
-    NoEeHealth,
-    NoBeh,
-    NoEventMessageInfo,
-    NoCapBankSystem,
-    NoCapBankEventAndStatusYpsh,
-    NoApplicationSystem,
-    NoSwitchStatus,
-    NoSwitchStatusXswi,
-    NoSwitchEvent,
-    NoSwitchEventXswi,
     NoSwitchReading,
     InvalidValue,
-    NoResourceStatus,
-    NoResourceDiscreteControl,
-    NoVss,
-    NoVsc,
-    NoControlApc,
-    NoMv,
-    NoStatusIns,
-    NoControlInc,
-    NoStatusSps,
-    NoControlSpc,
     NoValue,
-    NoCapBankReading,
-    NoCapBankStatus,
-    NoCapBankControl,
-    NoSocLimit,
-    NoSocHigh,
-    NoSocLow,
-    NoBatSt,
-    NoStandby,
-    NoRealPowerSet,
-    NoReactivePowerSet,
-    NoFunction,
-    NoSoh,
+    #[snafu(display("Missing field {}", source.field))]
+    MissingFieldError {
+        source: MissingField,
+    },
     #[snafu(display("Actor System Error"))]
     IOError {
         source: std::io::Error,
@@ -160,5 +45,11 @@ pub enum OpenFMBError {
     NotImplented,
 }
 
+impl From<MissingField> for OpenFMBError {
+    fn from(source: MissingField) -> OpenFMBError {
+        OpenFMBError::MissingFieldError { source }
+    }
+}
+
 /// OpenFMBExt Result Type Alias
 pub type OpenFMBResult<T> = std::result::Result<T, OpenFMBError>;
@@ -7,7 +7,7 @@ use std::str::FromStr;
 use essmodule::EssCapabilityOverrideProfile;
 use openfmb_messages::{commonmodule::MessageInfo, *};
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 use crate::{error::*, OpenFMBExt};
@@ -18,13 +18,11 @@ impl OpenFMBExt for EssCapabilityOverrideProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .capability_message_info
-            .as_ref()
-            .context(NoMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_capability_message_info_and_then(|capability_message_info| {
+                capability_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -32,32 +30,22 @@ impl OpenFMBExt for EssCapabilityOverrideProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .ess
-                .as_ref()
-                .context(NoEss)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_ess_and_then(|ess| ess.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .ess
-            .as_ref()
-            .context(NoEss)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_ess_and_then(|ess| {
+                ess.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
@@ -14,7 +14,7 @@ use openfmb_messages::{
         EssFunction, EssPoint, Esscsg, SocLimit, SocManagement,
     },
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::{str::FromStr, time::SystemTime};
 use uuid::Uuid;
 
@@ -44,13 +44,11 @@ impl OpenFMBExt for EssControlProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .control_message_info
-            .as_ref()
-            .context(NoControlMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_control_message_info_and_then(|control_message_info| {
+                control_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -58,33 +56,23 @@ impl OpenFMBExt for EssControlProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .ess
-                .as_ref()
-                .context(NoEss)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_ess_and_then(|ess| ess.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .ess
-            .as_ref()
-            .context(NoEss)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_ess_and_then(|ess| {
+                ess.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 use openfmb_messages::commonmodule::GridConnectModeKind;
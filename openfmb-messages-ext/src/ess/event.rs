@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,51 +13,35 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtEvent};
 
 impl OpenFMBExtEvent for EssEventProfile {
     fn event_message_info(&self) -> OpenFMBResult<&EventMessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?)
+        Ok(self.try_event_message_info()?)
     }
 }
 
 impl OpenFMBExt for EssEventProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .ess_event
-            .as_ref()
-            .context(NoEssEvent)?
-            .ess_event_zgen
-            .as_ref()
-            .context(NoEssEventZGen)?
-            .e_ss_event_and_status_zgen
-            .as_ref()
-            .context(NoEssEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)
-        {
-            Ok(state) => match state.value {
-                0 => Ok("Undefined".into()),
-                1 => Ok("Off".into()),
-                2 => Ok("On".into()),
-                3 => Ok("StandBy".into()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let state = self.try_ess_event_and_then(|ess_event| {
+            ess_event.try_ess_event_zgen_and_then(|ess_event_zgen| {
+                ess_event_zgen.try_e_ss_event_and_status_zgen_and_then(
+                    |e_ss_event_and_status_zgen| {
+                        e_ss_event_and_status_zgen
+                            .try_point_status_and_then(|point_status| point_status.try_state())
+                    },
+                )
+            })
+        })?;
+        match state.value {
+            0 => Ok("Undefined".into()),
+            1 => Ok("Off".into()),
+            2 => Ok("On".into()),
+            3 => Ok("StandBy".into()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(self.try_event_message_info_and_then(|event_message_info| {
+            event_message_info.try_message_info()
+        })?)
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -65,17 +49,12 @@ impl OpenFMBExt for EssEventProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .ess
-                .as_ref()
-                .context(NoEss)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_ess_and_then(|ess| ess.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
@@ -8,7 +8,7 @@ use openfmb_messages::{
     commonmodule::{MessageInfo, ReadingMessageInfo},
     essmodule::EssReadingProfile,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 use crate::{error::*, OpenFMBExt, OpenFMBExtReading, OpenFMBReading, ReadingProfileExt};
@@ -20,13 +20,11 @@ impl OpenFMBExt for EssReadingProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoReadingMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_reading_message_info_and_then(|reading_message_info| {
+                reading_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -34,42 +32,29 @@ impl OpenFMBExt for EssReadingProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .ess
-                .as_ref()
-                .context(NoEss)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_ess_and_then(|ess| ess.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .ess
-            .as_ref()
-            .context(NoEss)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_ess_and_then(|ess| {
+                ess.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtReading for EssReadingProfile {
     fn reading_message_info(&self) -> OpenFMBResult<&ReadingMessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_reading_message_info()?)
     }
 }
 
@@ -81,81 +66,43 @@ pub trait EssReadingExt: ReadingProfileExt {
 impl ReadingProfileExt for EssReadingProfile {
     fn w_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .ess_reading
-            .as_ref()
-            .context(NoEssReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .w
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_ess_reading_and_then(|ess_reading| {
+                ess_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 
     fn q_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .ess_reading
-            .as_ref()
-            .context(NoEssReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .v_ar
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_ess_reading_and_then(|ess_reading| {
+                ess_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 
     fn v_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .ess_reading
-            .as_ref()
-            .context(NoEssReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .ph_v
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_ess_reading_and_then(|ess_reading| {
+                ess_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 
     fn a_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .ess_reading
-            .as_ref()
-            .context(NoEssReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .a
-            .as_ref()
-            .context(NoValue)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_ess_reading_and_then(|ess_reading| {
+                ess_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 }
@@ -163,41 +110,21 @@ impl ReadingProfileExt for EssReadingProfile {
 impl EssReadingExt for EssReadingProfile {
     fn pf_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .ess_reading
-            .as_ref()
-            .context(NoEssReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .pf
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_ess_reading_and_then(|ess_reading| {
+                ess_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_pf_and_then(|pf| pf.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 
     fn va_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .ess_reading
-            .as_ref()
-            .context(NoEssReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .va
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_ess_reading_and_then(|ess_reading| {
+                ess_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_va_and_then(|va| va.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 }
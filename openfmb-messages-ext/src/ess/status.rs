@@ -12,49 +12,38 @@ use openfmb_messages::{
 };
 
 use crate::StatusProfileExt;
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 use crate::{error::*, OpenFMBExt, OpenFMBExtStatus};
 
 impl OpenFMBExt for EssStatusProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zgen
-            .as_ref()
-            .context(NoEssStatus)?
-            .e_ss_event_and_status_zgen
-            .as_ref()
-            .context(NoEssEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)
-        {
-            Ok(state) => match state.value {
-                0 => Ok("Undefined".into()),
-                1 => Ok("Off".into()),
-                2 => Ok("On".into()),
-                3 => Ok("StandBy".into()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let state = self.try_ess_status_and_then(|ess_status| {
+            ess_status.try_ess_status_zgen_and_then(|ess_status_zgen| {
+                ess_status_zgen.try_e_ss_event_and_status_zgen_and_then(
+                    |e_ss_event_and_status_zgen| {
+                        e_ss_event_and_status_zgen
+                            .try_point_status_and_then(|point_status| point_status.try_state())
+                    },
+                )
+            })
+        })?;
+        match state.value {
+            0 => Ok("Undefined".into()),
+            1 => Ok("Off".into()),
+            2 => Ok("On".into()),
+            3 => Ok("StandBy".into()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_status_message_info_and_then(|status_message_info| {
+                status_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -62,42 +51,29 @@ impl OpenFMBExt for EssStatusProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .ess
-                .as_ref()
-                .context(NoEss)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_ess_and_then(|ess| ess.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .ess
-            .as_ref()
-            .context(NoEss)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_ess_and_then(|ess| {
+                ess.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtStatus for EssStatusProfile {
     fn status_message_info(&self) -> OpenFMBResult<&StatusMessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_status_message_info()?)
     }
 }
 
@@ -119,207 +95,152 @@ pub trait EssStatusExt: StatusProfileExt {
 impl EssStatusExt for EssStatusProfile {
     fn ess_soc(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zbat
-            .as_ref()
-            .context(NoEssStatusZBat)?
-            .soc
-            .as_ref()
-            .context(NoSoc)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status.try_ess_status_zbat_and_then(|ess_status_zbat| ess_status_zbat.try_soc())
+            })?
             .mag
             / 100.0)
     }
 
     fn ess_mode(&self) -> OpenFMBResult<EngGridConnectModeKind> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zgen
-            .as_ref()
-            .context(NoEssStatusZGen)?
-            .e_ss_event_and_status_zgen
-            .as_ref()
-            .context(NoEssEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .mode
-            .clone()
-            .context(NoMode)?)
+            .try_ess_status_and_then(|ess_status| {
+                ess_status.try_ess_status_zgen_and_then(|ess_status_zgen| {
+                    ess_status_zgen.try_e_ss_event_and_status_zgen_and_then(
+                        |e_ss_event_and_status_zgen| {
+                            e_ss_event_and_status_zgen
+                                .try_point_status_and_then(|point_status| point_status.try_mode())
+                        },
+                    )
+                })
+            })
+            .cloned()?)
     }
 
     fn ess_state(&self) -> OpenFMBResult<StateKind> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zgen
-            .as_ref()
-            .context(NoEssStatusZGen)?
-            .e_ss_event_and_status_zgen
-            .as_ref()
-            .context(NoEssEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status.try_ess_status_zgen_and_then(|ess_status_zgen| {
+                    ess_status_zgen.try_e_ss_event_and_status_zgen_and_then(
+                        |e_ss_event_and_status_zgen| {
+                            e_ss_event_and_status_zgen
+                                .try_point_status_and_then(|point_status| point_status.try_state())
+                        },
+                    )
+                })
+            })?
             .value())
     }
 
     fn ess_gn_sync_st(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zgen
-            .as_ref()
-            .context(NoEssStatusZGen)?
-            .e_ss_event_and_status_zgen
-            .as_ref()
-            .context(NoEssEventAndStatusZGen)?
-            .gn_syn_st
-            .as_ref()
-            .context(NoEssGnSyncSt)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status.try_ess_status_zgen_and_then(|ess_status_zgen| {
+                    ess_status_zgen.try_e_ss_event_and_status_zgen_and_then(
+                        |e_ss_event_and_status_zgen| e_ss_event_and_status_zgen.try_gn_syn_st(),
+                    )
+                })
+            })?
             .st_val)
     }
 
     fn ess_soh(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zbat
-            .as_ref()
-            .context(NoEssStatusZBat)?
-            .so_h
-            .as_ref()
-            .context(NoSoh)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status
+                    .try_ess_status_zbat_and_then(|ess_status_zbat| ess_status_zbat.try_so_h())
+            })?
             .mag
             / 100.0)
     }
 
     fn soc_min(&self) -> OpenFMBResult<f32> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zgen
-            .as_ref()
-            .context(NoEssStatusZGen)?
-            .e_ss_event_and_status_zgen
-            .as_ref()
-            .context(NoEssEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .function
-            .as_ref()
-            .context(NoFunction)?
-            .soc_limit
-            .as_ref()
-            .context(NoSocLimit)?
-            .soc_low_limit
-            .as_ref()
-            .context(NoSocLow)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status.try_ess_status_zgen_and_then(|ess_status_zgen| {
+                    ess_status_zgen.try_e_ss_event_and_status_zgen_and_then(
+                        |e_ss_event_and_status_zgen| {
+                            e_ss_event_and_status_zgen.try_point_status_and_then(|point_status| {
+                                point_status.try_function_and_then(|function| {
+                                    function.try_soc_limit_and_then(|soc_limit| {
+                                        soc_limit.try_soc_low_limit()
+                                    })
+                                })
+                            })
+                        },
+                    )
+                })
+            })?
             .clone())
     }
 
     fn soc_max(&self) -> OpenFMBResult<f32> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zgen
-            .as_ref()
-            .context(NoEssStatusZGen)?
-            .e_ss_event_and_status_zgen
-            .as_ref()
-            .context(NoEssEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .function
-            .as_ref()
-            .context(NoFunction)?
-            .soc_limit
-            .as_ref()
-            .context(NoSocLimit)?
-            .soc_high_limit
-            .as_ref()
-            .context(NoSocHigh)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status.try_ess_status_zgen_and_then(|ess_status_zgen| {
+                    ess_status_zgen.try_e_ss_event_and_status_zgen_and_then(
+                        |e_ss_event_and_status_zgen| {
+                            e_ss_event_and_status_zgen.try_point_status_and_then(|point_status| {
+                                point_status.try_function_and_then(|function| {
+                                    function.try_soc_limit_and_then(|soc_limit| {
+                                        soc_limit.try_soc_high_limit()
+                                    })
+                                })
+                            })
+                        },
+                    )
+                })
+            })?
             .clone())
     }
 
     fn online_status(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zbat
-            .as_ref()
-            .context(NoEssStatusZBat)?
-            .bat_st
-            .as_ref()
-            .context(NoBatSt)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status
+                    .try_ess_status_zbat_and_then(|ess_status_zbat| ess_status_zbat.try_bat_st())
+            })?
             .st_val)
     }
 
     fn supervisory_control(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zbat
-            .as_ref()
-            .context(NoEssStatusZBat)?
-            .stdby
-            .as_ref()
-            .context(NoStandby)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status
+                    .try_ess_status_zbat_and_then(|ess_status_zbat| ess_status_zbat.try_stdby())
+            })?
             .st_val)
     }
 
     fn reactive_power_enabled(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zgen
-            .as_ref()
-            .context(NoEssEventZGen)?
-            .e_ss_event_and_status_zgen
-            .as_ref()
-            .context(NoEssEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .reactive_pwr_set_point_enabled
-            .as_ref()
-            .context(NoReactivePowerSet)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status.try_ess_status_zgen_and_then(|ess_status_zgen| {
+                    ess_status_zgen.try_e_ss_event_and_status_zgen_and_then(
+                        |e_ss_event_and_status_zgen| {
+                            e_ss_event_and_status_zgen.try_point_status_and_then(|point_status| {
+                                point_status.try_reactive_pwr_set_point_enabled()
+                            })
+                        },
+                    )
+                })
+            })?
             .st_val)
     }
 
     fn active_power_enabled(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .ess_status
-            .as_ref()
-            .context(NoEssStatus)?
-            .ess_status_zgen
-            .as_ref()
-            .context(NoEssEventZGen)?
-            .e_ss_event_and_status_zgen
-            .as_ref()
-            .context(NoEssEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .real_pwr_set_point_enabled
-            .as_ref()
-            .context(NoRealPowerSet)?
+            .try_ess_status_and_then(|ess_status| {
+                ess_status.try_ess_status_zgen_and_then(|ess_status_zgen| {
+                    ess_status_zgen.try_e_ss_event_and_status_zgen_and_then(
+                        |e_ss_event_and_status_zgen| {
+                            e_ss_event_and_status_zgen.try_point_status_and_then(|point_status| {
+                                point_status.try_real_pwr_set_point_enabled()
+                            })
+                        },
+                    )
+                })
+            })?
             .st_val)
     }
 }
@@ -14,7 +14,7 @@ use openfmb_messages::{
         GenerationControlScheduleFsch, GenerationCsg, GenerationPoint,
     },
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::{str::FromStr, time::SystemTime};
 use uuid::Uuid;
 
@@ -32,33 +32,25 @@ impl OpenFMBExt for GenerationControlProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .generating_unit
-                .as_ref()
-                .context(NoGeneratingUnit)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_generating_unit_and_then(|generating_unit| {
+                    generating_unit.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .generating_unit
-            .as_ref()
-            .context(NoGeneratingUnit)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_generating_unit_and_then(|generating_unit| {
+                generating_unit.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
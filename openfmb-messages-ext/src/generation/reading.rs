@@ -8,7 +8,7 @@ use openfmb_messages::{
     commonmodule::{MessageInfo, ReadingMessageInfo},
     generationmodule::GenerationReadingProfile,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 use crate::{error::*, OpenFMBExt, OpenFMBExtReading, ReadingProfileExt};
@@ -16,33 +16,21 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtReading, ReadingProfileExt};
 impl OpenFMBExt for GenerationReadingProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
         Ok(self
-            .generation_reading
-            .as_ref()
-            .context(NoGenerationReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .w
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_generation_reading_and_then(|generation_reading| {
+                generation_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag
             .to_string())
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_reading_message_info_and_then(|reading_message_info| {
+                reading_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -50,123 +38,74 @@ impl OpenFMBExt for GenerationReadingProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .generating_unit
-                .as_ref()
-                .context(NoGeneratingUnit)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_generating_unit_and_then(|generating_unit| {
+                    generating_unit.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .generating_unit
-            .as_ref()
-            .context(NoGeneratingUnit)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_generating_unit_and_then(|generating_unit| {
+                generating_unit.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtReading for GenerationReadingProfile {
     fn reading_message_info(&self) -> OpenFMBResult<&ReadingMessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_reading_message_info()?)
     }
 }
 
 impl ReadingProfileExt for GenerationReadingProfile {
     fn w_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .generation_reading
-            .as_ref()
-            .context(NoGenerationReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .w
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_generation_reading_and_then(|generation_reading| {
+                generation_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 
     fn q_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .generation_reading
-            .as_ref()
-            .context(NoGenerationReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .v_ar
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_generation_reading_and_then(|generation_reading| {
+                generation_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 
     fn v_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .generation_reading
-            .as_ref()
-            .context(NoGenerationReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .ph_v
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_generation_reading_and_then(|generation_reading| {
+                generation_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 
     fn a_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .generation_reading
-            .as_ref()
-            .context(NoGenerationReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .a
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_generation_reading_and_then(|generation_reading| {
+                generation_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 }
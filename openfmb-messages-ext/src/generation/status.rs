@@ -10,7 +10,7 @@ use openfmb_messages::{
     commonmodule::{MessageInfo, StatusMessageInfo},
     *,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 use crate::{error::*, OpenFMBExt, OpenFMBExtStatus};
@@ -18,42 +18,31 @@ use openfmb_messages::commonmodule::StateKind;
 
 impl OpenFMBExt for GenerationStatusProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .generation_status
-            .as_ref()
-            .context(NoGenerationStatus)?
-            .generation_status_zgen
-            .as_ref()
-            .context(NoGenerationStatusZGen)?
-            .generation_event_and_status_zgen
-            .as_ref()
-            .context(NoGenerationEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)
-        {
-            Ok(state) => match state.value {
-                0 => Ok("Undefined".into()),
-                1 => Ok("Off".into()),
-                2 => Ok("On".into()),
-                3 => Ok("StandBy".into()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let state = self.try_generation_status_and_then(|generation_status| {
+            generation_status.try_generation_status_zgen_and_then(|generation_status_zgen| {
+                generation_status_zgen.try_generation_event_and_status_zgen_and_then(
+                    |generation_event_and_status_zgen| {
+                        generation_event_and_status_zgen
+                            .try_point_status_and_then(|point_status| point_status.try_state())
+                    },
+                )
+            })
+        })?;
+        match state.value {
+            0 => Ok("Undefined".into()),
+            1 => Ok("Off".into()),
+            2 => Ok("On".into()),
+            3 => Ok("StandBy".into()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_status_message_info_and_then(|status_message_info| {
+                status_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -61,42 +50,31 @@ impl OpenFMBExt for GenerationStatusProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .generating_unit
-                .as_ref()
-                .context(NoGeneratingUnit)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_generating_unit_and_then(|generating_unit| {
+                    generating_unit.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .generating_unit
-            .as_ref()
-            .context(NoGeneratingUnit)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_generating_unit_and_then(|generating_unit| {
+                generating_unit.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtStatus for GenerationStatusProfile {
     fn status_message_info(&self) -> OpenFMBResult<&StatusMessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_status_message_info()?)
     }
 }
 
@@ -108,17 +86,19 @@ impl GenerationStatusExt for GenerationStatusProfile {
     fn generation_state(&self) -> OpenFMBResult<StateKind> {
         {
             Ok(self
-                .generation_status
-                .clone()
-                .context(NoGenerationStatus)?
-                .generation_status_zgen
-                .context(NoGenerationStatusZGen)?
-                .generation_event_and_status_zgen
-                .unwrap()
-                .point_status
-                .unwrap()
-                .state
-                .unwrap()
+                .try_generation_status_and_then(|generation_status| {
+                    generation_status.try_generation_status_zgen_and_then(
+                        |generation_status_zgen| {
+                            generation_status_zgen.try_generation_event_and_status_zgen_and_then(
+                                |generation_event_and_status_zgen| {
+                                    generation_event_and_status_zgen.try_point_status_and_then(
+                                        |point_status| point_status.try_state(),
+                                    )
+                                },
+                            )
+                        },
+                    )
+                })?
                 .value())
         }
     }
@@ -12,7 +12,7 @@ use openfmb_messages::commonmodule::{
     ControlMessageInfo, DbPosKind, EventMessageInfo, IdentifiedObject, MessageInfo,
     ReadingMessageInfo, StatusMessageInfo, Timestamp,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 pub mod breaker;
@@ -106,12 +106,9 @@ pub trait OpenFMBExt {
         Ok(Uuid::from_str(
             &self
                 .message_info()?
-                .identified_object
-                .as_ref()
-                .context(error::NoIdentifiedObject)?
-                .m_rid
-                .as_ref()
-                .context(error::NoMRID)?,
+                .try_identified_object_and_then(|identified_object| {
+                    identified_object.try_m_rid()
+                })?,
         )
         .context(error::UuidError)?)
     }
@@ -18,7 +18,7 @@ use openfmb_messages::{
         LoadPoint,
     },
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -46,7 +46,7 @@ impl OpenFMBExt for LoadControlProfile {
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
         unimplemented!()
-        //        Ok(self.solar_control.clone().context(NoStatusMessageInfo)?..unwrap())
+        //        Ok(self.try_solar_control().cloned()?..unwrap())
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -54,33 +54,25 @@ impl OpenFMBExt for LoadControlProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .energy_consumer
-                .as_ref()
-                .context(NoEnergyConsumer)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_energy_consumer_and_then(|energy_consumer| {
+                    energy_consumer.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .energy_consumer
-            .as_ref()
-            .context(NoEnergyConsumer)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_energy_consumer_and_then(|energy_consumer| {
+                energy_consumer.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,51 +13,35 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtEvent};
 
 impl OpenFMBExtEvent for LoadEventProfile {
     fn event_message_info(&self) -> OpenFMBResult<&EventMessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?)
+        Ok(self.try_event_message_info()?)
     }
 }
 
 impl OpenFMBExt for LoadEventProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .load_event
-            .as_ref()
-            .context(NoLoadEvent)?
-            .load_event_zgld
-            .as_ref()
-            .context(NoLoadEventZGld)?
-            .load_event_and_status_zgld
-            .as_ref()
-            .context(NoLoadEventAndStatusZGld)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)
-        {
-            Ok(state) => match state.value {
-                0 => Ok("Undefined".into()),
-                1 => Ok("Off".into()),
-                2 => Ok("On".into()),
-                3 => Ok("StandBy".into()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let state = self.try_load_event_and_then(|load_event| {
+            load_event.try_load_event_zgld_and_then(|load_event_zgld| {
+                load_event_zgld.try_load_event_and_status_zgld_and_then(
+                    |load_event_and_status_zgld| {
+                        load_event_and_status_zgld
+                            .try_point_status_and_then(|point_status| point_status.try_state())
+                    },
+                )
+            })
+        })?;
+        match state.value {
+            0 => Ok("Undefined".into()),
+            1 => Ok("Off".into()),
+            2 => Ok("On".into()),
+            3 => Ok("StandBy".into()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(self.try_event_message_info_and_then(|event_message_info| {
+            event_message_info.try_message_info()
+        })?)
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -65,32 +49,24 @@ impl OpenFMBExt for LoadEventProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .energy_consumer
-                .as_ref()
-                .context(NoEnergyConsumer)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_energy_consumer_and_then(|energy_consumer| {
+                    energy_consumer.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .energy_consumer
-            .as_ref()
-            .context(NoEnergyConsumer)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_energy_consumer_and_then(|energy_consumer| {
+                energy_consumer.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
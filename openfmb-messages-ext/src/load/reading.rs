@@ -8,7 +8,7 @@ use openfmb_messages::{
     commonmodule::{MessageInfo, ReadingMessageInfo},
     loadmodule::LoadReadingProfile,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 use crate::{error::*, OpenFMBExt, OpenFMBExtReading, ReadingProfileExt};
@@ -20,13 +20,11 @@ impl OpenFMBExt for LoadReadingProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_reading_message_info_and_then(|reading_message_info| {
+                reading_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -34,42 +32,31 @@ impl OpenFMBExt for LoadReadingProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .energy_consumer
-                .as_ref()
-                .context(NoEnergyConsumer)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_energy_consumer_and_then(|energy_consumer| {
+                    energy_consumer.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .energy_consumer
-            .as_ref()
-            .context(NoEnergyConsumer)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_energy_consumer_and_then(|energy_consumer| {
+                energy_consumer.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtReading for LoadReadingProfile {
     fn reading_message_info(&self) -> OpenFMBResult<&ReadingMessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_reading_message_info()?)
     }
 }
 
@@ -82,81 +69,43 @@ pub trait LoadReadingExt: ReadingProfileExt {
 impl ReadingProfileExt for LoadReadingProfile {
     fn w_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .load_reading
-            .as_ref()
-            .context(NoLoadReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .w
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_load_reading_and_then(|load_reading| {
+                load_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 
     fn q_net(&self) -> OpenFMBResult<f64> {
         Ok(self
-            .load_reading
-            .as_ref()
-            .context(NoLoadReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .v_ar
-            .as_ref()
-            .context(NoW)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_load_reading_and_then(|load_reading| {
+                load_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag)
     }
 
     fn v_net(&self) -> OpenFMBResult<f64> {
         return Ok(self
-            .load_reading
-            .as_ref()
-            .context(NoLoadReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .ph_v
-            .as_ref()
-            .context(NoValue)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_load_reading_and_then(|load_reading| {
+                load_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag);
     }
 
     fn a_net(&self) -> OpenFMBResult<f64> {
         return Ok(self
-            .load_reading
-            .as_ref()
-            .context(NoLoadReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .a
-            .as_ref()
-            .context(NoValue)?
-            .net
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_load_reading_and_then(|load_reading| {
+                load_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })
+            })?
             .mag);
     }
 }
@@ -164,61 +113,37 @@ impl ReadingProfileExt for LoadReadingProfile {
 impl LoadReadingExt for LoadReadingProfile {
     fn v_phs_a(&self) -> OpenFMBResult<f64> {
         return Ok(self
-            .load_reading
-            .as_ref()
-            .context(NoLoadReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .ph_v
-            .as_ref()
-            .context(NoValue)?
-            .phs_a
-            .as_ref()
-            .context(NoNet)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_load_reading_and_then(|load_reading| {
+                load_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_ph_v_and_then(|ph_v| {
+                        ph_v.try_phs_a_and_then(|phs_a| phs_a.try_c_val())
+                    })
+                })
+            })?
             .mag);
     }
 
     fn v_phs_b(&self) -> OpenFMBResult<f64> {
         return Ok(self
-            .load_reading
-            .as_ref()
-            .context(NoLoadReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .ph_v
-            .as_ref()
-            .context(NoValue)?
-            .phs_b
-            .as_ref()
-            .context(NoPhsB)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_load_reading_and_then(|load_reading| {
+                load_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_ph_v_and_then(|ph_v| {
+                        ph_v.try_phs_b_and_then(|phs_b| phs_b.try_c_val())
+                    })
+                })
+            })?
             .mag);
     }
 
     fn v_phs_c(&self) -> OpenFMBResult<f64> {
         return Ok(self
-            .load_reading
-            .as_ref()
-            .context(NoLoadReading)?
-            .reading_mmxu
-            .as_ref()
-            .context(NoReadingMmxu)?
-            .ph_v
-            .as_ref()
-            .context(NoValue)?
-            .phs_c
-            .as_ref()
-            .context(NoPhsC)?
-            .c_val
-            .as_ref()
-            .context(NoCVal)?
+            .try_load_reading_and_then(|load_reading| {
+                load_reading.try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_ph_v_and_then(|ph_v| {
+                        ph_v.try_phs_c_and_then(|phs_c| phs_c.try_c_val())
+                    })
+                })
+            })?
             .mag);
     }
 }
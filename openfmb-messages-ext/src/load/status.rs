@@ -9,49 +9,38 @@ use openfmb_messages::{
     commonmodule::{MessageInfo, StateKind, StatusMessageInfo},
     loadmodule::LoadStatusProfile,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 use crate::{error::*, OpenFMBExt, OpenFMBExtStatus};
 
 impl OpenFMBExt for LoadStatusProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .load_status
-            .as_ref()
-            .context(NoLoadStatus)?
-            .load_status_zgld
-            .as_ref()
-            .context(NoLoadStatusZGld)?
-            .load_event_and_status_zgld
-            .as_ref()
-            .context(NoLoadEventAndStatusZGld)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)
-        {
-            Ok(state) => match state.value {
-                0 => Ok("Undefined".into()),
-                1 => Ok("Off".into()),
-                2 => Ok("On".into()),
-                3 => Ok("StandBy".into()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let state = self.try_load_status_and_then(|load_status| {
+            load_status.try_load_status_zgld_and_then(|load_status_zgld| {
+                load_status_zgld.try_load_event_and_status_zgld_and_then(
+                    |load_event_and_status_zgld| {
+                        load_event_and_status_zgld
+                            .try_point_status_and_then(|point_status| point_status.try_state())
+                    },
+                )
+            })
+        })?;
+        match state.value {
+            0 => Ok("Undefined".into()),
+            1 => Ok("Off".into()),
+            2 => Ok("On".into()),
+            3 => Ok("StandBy".into()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_status_message_info_and_then(|status_message_info| {
+                status_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -59,42 +48,31 @@ impl OpenFMBExt for LoadStatusProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .energy_consumer
-                .as_ref()
-                .context(NoEnergyConsumer)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_energy_consumer_and_then(|energy_consumer| {
+                    energy_consumer.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .energy_consumer
-            .as_ref()
-            .context(NoEnergyConsumer)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_energy_consumer_and_then(|energy_consumer| {
+                energy_consumer.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtStatus for LoadStatusProfile {
     fn status_message_info(&self) -> OpenFMBResult<&StatusMessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_status_message_info()?)
     }
 }
 
@@ -105,21 +83,16 @@ pub trait LoadStatusExt: StatusProfileExt {
 impl LoadStatusExt for LoadStatusProfile {
     fn load_state(&self) -> OpenFMBResult<StateKind> {
         Ok(self
-            .load_status
-            .as_ref()
-            .context(NoLoadStatus)?
-            .load_status_zgld
-            .as_ref()
-            .context(NoLoadStatusZGld)?
-            .load_event_and_status_zgld
-            .as_ref()
-            .context(NoLoadEventAndStatusZGld)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)?
+            .try_load_status_and_then(|load_status| {
+                load_status.try_load_status_zgld_and_then(|load_status_zgld| {
+                    load_status_zgld.try_load_event_and_status_zgld_and_then(
+                        |load_event_and_status_zgld| {
+                            load_event_and_status_zgld
+                                .try_point_status_and_then(|point_status| point_status.try_state())
+                        },
+                    )
+                })
+            })?
             .value())
     }
 }
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use openfmb_messages::{commonmodule::*, reclosermodule::*};
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::{str::FromStr, time::SystemTime};
 use uuid::Uuid;
 
@@ -12,21 +12,17 @@ use crate::{error::*, ControlProfileExt, OpenFMBExt, Phase};
 impl OpenFMBExt for RecloserDiscreteControlProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
         let state = if self
-            .recloser_discrete_control
-            .as_ref()
-            .context(NoBreakerDiscreteControl)?
-            .recloser_discrete_control_xcbr
-            .as_ref()
-            .context(NoBreakerDiscreteControlXcbr)?
-            .discrete_control_xcbr
-            .as_ref()
-            .context(NoDiscreteControlXcbr)?
-            .pos
-            .as_ref()
-            .context(NoPos)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)?
+            .try_recloser_discrete_control_and_then(|recloser_discrete_control| {
+                recloser_discrete_control.try_recloser_discrete_control_xcbr_and_then(
+                    |recloser_discrete_control_xcbr| {
+                        recloser_discrete_control_xcbr.try_discrete_control_xcbr_and_then(
+                            |discrete_control_xcbr| {
+                                discrete_control_xcbr.try_pos_and_then(|pos| pos.try_phs3())
+                            },
+                        )
+                    },
+                )
+            })?
             .ctl_val
         {
             "Request Closed"
@@ -45,33 +41,23 @@ impl OpenFMBExt for RecloserDiscreteControlProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .recloser
-                .as_ref()
-                .context(NoRecloser)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_recloser_and_then(|recloser| recloser.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .recloser
-            .as_ref()
-            .context(NoRecloser)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_recloser_and_then(|recloser| {
+                recloser.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,49 +13,31 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtEvent};
 
 impl OpenFMBExtEvent for RecloserEventProfile {
     fn event_message_info(&self) -> OpenFMBResult<&EventMessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?)
+        Ok(self.try_event_message_info()?)
     }
 }
 
 impl OpenFMBExt for RecloserEventProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .recloser_event
-            .as_ref()
-            .context(NoRecloserEvent)?
-            .status_and_event_xcbr
-            .as_ref()
-            .context(NoStatusAndEventXcbr)?
-            .pos
-            .as_ref()
-            .context(NoPos)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)
-        {
-            Ok(phs3) => match phs3.st_val {
-                0 => Ok("Undefined".into()),
-                1 => Ok("Transient".into()),
-                2 => Ok("Closed".into()),
-                3 => Ok("Open".into()),
-                4 => Ok("Invalid".into()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let phs3 = self.try_recloser_event_and_then(|recloser_event| {
+            recloser_event.try_status_and_event_xcbr_and_then(|status_and_event_xcbr| {
+                status_and_event_xcbr.try_pos_and_then(|pos| pos.try_phs3())
+            })
+        })?;
+        match phs3.st_val {
+            0 => Ok("Undefined".into()),
+            1 => Ok("Transient".into()),
+            2 => Ok("Closed".into()),
+            3 => Ok("Open".into()),
+            4 => Ok("Invalid".into()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(self.try_event_message_info_and_then(|event_message_info| {
+            event_message_info.try_message_info()
+        })?)
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -63,32 +45,22 @@ impl OpenFMBExt for RecloserEventProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .recloser
-                .as_ref()
-                .context(NoRecloser)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_recloser_and_then(|recloser| recloser.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .recloser
-            .as_ref()
-            .context(NoRecloser)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_recloser_and_then(|recloser| {
+                recloser.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
@@ -17,13 +17,11 @@ impl OpenFMBExt for RecloserReadingProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_reading_message_info_and_then(|reading_message_info| {
+                reading_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -31,42 +29,29 @@ impl OpenFMBExt for RecloserReadingProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .recloser
-                .as_ref()
-                .context(NoRecloser)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_recloser_and_then(|recloser| recloser.try_conducting_equipment())?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .recloser
-            .as_ref()
-            .context(NoRecloser)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_recloser_and_then(|recloser| {
+                recloser.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtReading for RecloserReadingProfile {
     fn reading_message_info(&self) -> OpenFMBResult<&ReadingMessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_reading_message_info()?)
     }
 }
 
@@ -78,18 +63,9 @@ impl ReadingProfileExt for RecloserReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoRecloserReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoRecloserReading)
@@ -102,18 +78,10 @@ impl ReadingProfileExt for RecloserReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoRecloserReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .v_ar
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoRecloserReading)
@@ -126,18 +94,9 @@ impl ReadingProfileExt for RecloserReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoRecloserReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoRecloserReading)
@@ -150,18 +109,10 @@ impl ReadingProfileExt for RecloserReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoRecloserReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .v_ar
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoRecloserReading)
@@ -174,18 +125,10 @@ impl ReadingProfileExt for RecloserReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoRecloserReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .ph_v
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoRecloserReading)
@@ -198,18 +141,10 @@ impl ReadingProfileExt for RecloserReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoRecloserReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .ph_v
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoRecloserReading)
@@ -222,18 +157,9 @@ impl ReadingProfileExt for RecloserReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoRecloserReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .a
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoRecloserReading)
@@ -246,18 +172,9 @@ impl ReadingProfileExt for RecloserReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoRecloserReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .a
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoRecloserReading)
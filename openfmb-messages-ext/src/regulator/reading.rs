@@ -13,10 +13,7 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtReading, ReadingProfileExt};
 
 impl OpenFMBExtReading for RegulatorReadingProfile {
     fn reading_message_info(&self) -> OpenFMBResult<&ReadingMessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoReadingMessageInfo)?)
+        Ok(self.try_reading_message_info()?)
     }
 }
 
@@ -26,13 +23,11 @@ impl OpenFMBExt for RegulatorReadingProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoReadingMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_reading_message_info_and_then(|reading_message_info| {
+                reading_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -40,33 +35,25 @@ impl OpenFMBExt for RegulatorReadingProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .regulator_system
-                .as_ref()
-                .context(NoRegulatorSystem)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_regulator_system_and_then(|regulator_system| {
+                    regulator_system.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .regulator_system
-            .as_ref()
-            .context(NoRegulatorSystem)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_regulator_system_and_then(|regulator_system| {
+                regulator_system.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
@@ -78,18 +65,9 @@ impl ReadingProfileExt for RegulatorReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -102,18 +80,10 @@ impl ReadingProfileExt for RegulatorReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .v_ar
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -126,18 +96,9 @@ impl ReadingProfileExt for RegulatorReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -150,18 +111,10 @@ impl ReadingProfileExt for RegulatorReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .v_ar
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -174,18 +127,10 @@ impl ReadingProfileExt for RegulatorReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .ph_v
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -198,18 +143,10 @@ impl ReadingProfileExt for RegulatorReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .ph_v
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -222,18 +159,9 @@ impl ReadingProfileExt for RegulatorReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .a
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -246,18 +174,9 @@ impl ReadingProfileExt for RegulatorReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .a
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,48 +13,35 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtStatus, StatusProfileExt};
 
 impl OpenFMBExtStatus for RegulatorStatusProfile {
     fn status_message_info(&self) -> OpenFMBResult<&StatusMessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_status_message_info()?)
     }
 }
 
 impl OpenFMBExt for RegulatorStatusProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)
-        {
-            Ok(state) => match state.value {
-                0 => Ok("Undefined".into()),
-                1 => Ok("Off".into()),
-                2 => Ok("On".into()),
-                3 => Ok("StandBy".into()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let state = self.try_regulator_status_and_then(|regulator_status| {
+            regulator_status.try_regulator_event_and_status_ancr_and_then(
+                |regulator_event_and_status_ancr| {
+                    regulator_event_and_status_ancr
+                        .try_point_status_and_then(|point_status| point_status.try_state())
+                },
+            )
+        })?;
+        match state.value {
+            0 => Ok("Undefined".into()),
+            1 => Ok("Off".into()),
+            2 => Ok("On".into()),
+            3 => Ok("StandBy".into()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_status_message_info_and_then(|status_message_info| {
+                status_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -62,33 +49,25 @@ impl OpenFMBExt for RegulatorStatusProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .regulator_system
-                .as_ref()
-                .context(NoRegulatorSystem)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_regulator_system_and_then(|regulator_system| {
+                    regulator_system.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .regulator_system
-            .as_ref()
-            .context(NoRegulatorSystem)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_regulator_system_and_then(|regulator_system| {
+                regulator_system.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
@@ -117,230 +96,171 @@ impl StatusProfileExt for RegulatorStatusProfile {}
 impl RegulatorStatusExt for RegulatorStatusProfile {
     fn tap_pos_phs3(&self) -> OpenFMBResult<i32> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .tap_pos
-            .as_ref()
-            .context(NoTapPos)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr.try_point_status_and_then(|point_status| {
+                            point_status.try_tap_pos_and_then(|tap_pos| tap_pos.try_phs3())
+                        })
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn tap_pos_phs_a(&self) -> OpenFMBResult<i32> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .tap_pos
-            .as_ref()
-            .context(NoTapPos)?
-            .phs_a
-            .as_ref()
-            .context(NoPhsA)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr.try_point_status_and_then(|point_status| {
+                            point_status.try_tap_pos_and_then(|tap_pos| tap_pos.try_phs_a())
+                        })
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn tap_pos_phs_b(&self) -> OpenFMBResult<i32> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .tap_pos
-            .as_ref()
-            .context(NoTapPos)?
-            .phs_b
-            .as_ref()
-            .context(NoPhsB)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr.try_point_status_and_then(|point_status| {
+                            point_status.try_tap_pos_and_then(|tap_pos| tap_pos.try_phs_b())
+                        })
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn tap_pos_phs_c(&self) -> OpenFMBResult<i32> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .tap_pos
-            .as_ref()
-            .context(NoTapPos)?
-            .phs_c
-            .as_ref()
-            .context(NoPhsC)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr.try_point_status_and_then(|point_status| {
+                            point_status.try_tap_pos_and_then(|tap_pos| tap_pos.try_phs_c())
+                        })
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn vol_lmt_hi_phs3(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .vol_lmt_hi
-            .as_ref()
-            .context(NoVolLmtHi)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr.try_point_status_and_then(|point_status| {
+                            point_status.try_vol_lmt_hi_and_then(|vol_lmt_hi| vol_lmt_hi.try_phs3())
+                        })
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn vol_lmt_lo_phs3(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .vol_lmt_lo
-            .as_ref()
-            .context(NoVolLmtLo)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr.try_point_status_and_then(|point_status| {
+                            point_status.try_vol_lmt_lo_and_then(|vol_lmt_lo| vol_lmt_lo.try_phs3())
+                        })
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn bnd_wid_hi_phs3(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .bnd_wid_hi
-            .as_ref()
-            .context(NoBndWidHi)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr.try_point_status_and_then(|point_status| {
+                            point_status.try_bnd_wid_hi_and_then(|bnd_wid_hi| bnd_wid_hi.try_phs3())
+                        })
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn bnd_wid_lo_phs3(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .bnd_wid_lo
-            .as_ref()
-            .context(NoBndWidLo)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr.try_point_status_and_then(|point_status| {
+                            point_status.try_bnd_wid_lo_and_then(|bnd_wid_lo| bnd_wid_lo.try_phs3())
+                        })
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn tap_op_err(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .tap_op_err
-            .as_ref()
-            .context(NoTapOpErr)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr
+                            .try_point_status_and_then(|point_status| point_status.try_tap_op_err())
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn hot_line_tag(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .logical_node_for_event_and_status
-            .as_ref()
-            .context(NoLogicalNodeForEventAndStatus)?
-            .hot_line_tag
-            .as_ref()
-            .context(NoHotLineTag)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr
+                            .try_logical_node_for_event_and_status_and_then(
+                                |logical_node_for_event_and_status| {
+                                    logical_node_for_event_and_status.try_hot_line_tag()
+                                },
+                            )
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn voltage_setpoint_enabled(&self) -> OpenFMBResult<bool> {
         Ok(self
-            .regulator_status
-            .as_ref()
-            .context(NoRegulatorStatus)?
-            .regulator_event_and_status_ancr
-            .as_ref()
-            .context(NoRegulatorEventAndStatusAncr)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .voltage_set_point_enabled
-            .as_ref()
-            .context(NoVoltageSetPointEnabled)?
+            .try_regulator_status_and_then(|regulator_status| {
+                regulator_status.try_regulator_event_and_status_ancr_and_then(
+                    |regulator_event_and_status_ancr| {
+                        regulator_event_and_status_ancr.try_point_status_and_then(|point_status| {
+                            point_status.try_voltage_set_point_enabled()
+                        })
+                    },
+                )
+            })?
             .st_val)
     }
 
     fn state(&self) -> OpenFMBResult<StateKind> {
         Ok(
             match self
-                .regulator_status
-                .as_ref()
-                .context(NoRegulatorStatus)?
-                .regulator_event_and_status_ancr
-                .as_ref()
-                .context(NoRegulatorEventAndStatusAncr)?
-                .point_status
-                .as_ref()
-                .context(NoPointStatus)?
-                .state
-                .as_ref()
-                .context(NoState)?
+                .try_regulator_status_and_then(|regulator_status| {
+                    regulator_status.try_regulator_event_and_status_ancr_and_then(
+                        |regulator_event_and_status_ancr| {
+                            regulator_event_and_status_ancr
+                                .try_point_status_and_then(|point_status| point_status.try_state())
+                        },
+                    )
+                })?
                 .value
             {
                 1 => StateKind::Off,
@@ -14,7 +14,7 @@ use openfmb_messages::{
         StringControlGgio,
     },
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::{str::FromStr, time::SystemTime};
 use uuid::Uuid;
 
@@ -32,27 +32,16 @@ impl OpenFMBExt for ResourceDiscreteControlProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
-            &self
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
-                .m_rid,
-        )
-        .context(UuidError)?)
+        Uuid::from_str(&self.try_conducting_equipment()?.m_rid).context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_conducting_equipment_and_then(|conducting_equipment| {
+                conducting_equipment
+                    .try_named_object_and_then(|named_object| named_object.try_name())
+            })
+            .cloned()?)
     }
 }
 
@@ -348,15 +337,10 @@ impl ResourceControlExt for ResourceDiscreteControlProfile {
 
     fn message_identified_object_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .control_message_info
-            .as_ref()
-            .context(NoControlMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?
-            .identified_object
-            .as_ref()
-            .context(NoIdentifiedObject)?
+            .try_control_message_info_and_then(|control_message_info| {
+                control_message_info
+                    .try_message_info_and_then(|message_info| message_info.try_identified_object())
+            })?
             .name
             .clone()
             .unwrap_or("".to_string()))
@@ -364,15 +348,10 @@ impl ResourceControlExt for ResourceDiscreteControlProfile {
 
     fn message_identified_description(&self) -> OpenFMBResult<String> {
         Ok(self
-            .control_message_info
-            .as_ref()
-            .context(NoControlMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?
-            .identified_object
-            .as_ref()
-            .context(NoIdentifiedObject)?
+            .try_control_message_info_and_then(|control_message_info| {
+                control_message_info
+                    .try_message_info_and_then(|message_info| message_info.try_identified_object())
+            })?
             .description
             .clone()
             .unwrap_or("".to_string()))
@@ -380,36 +359,28 @@ impl ResourceControlExt for ResourceDiscreteControlProfile {
 
     fn string_ggio(&self) -> OpenFMBResult<Vec<StringControlGgio>> {
         Ok(self
-            .resource_discrete_control
-            .as_ref()
-            .context(NoResourceDiscreteControl)?
+            .try_resource_discrete_control()?
             .string_control_ggio
             .clone())
     }
 
     fn analog_ggio(&self) -> OpenFMBResult<Vec<AnalogControlGgio>> {
         Ok(self
-            .resource_discrete_control
-            .as_ref()
-            .context(NoResourceDiscreteControl)?
+            .try_resource_discrete_control()?
             .analog_control_ggio
             .clone())
     }
 
     fn integer_ggio(&self) -> OpenFMBResult<Vec<IntegerControlGgio>> {
         Ok(self
-            .resource_discrete_control
-            .as_ref()
-            .context(NoResourceDiscreteControl)?
+            .try_resource_discrete_control()?
             .integer_control_ggio
             .clone())
     }
 
     fn boolean_ggio(&self) -> OpenFMBResult<Vec<BooleanControlGgio>> {
         Ok(self
-            .resource_discrete_control
-            .as_ref()
-            .context(NoResourceDiscreteControl)?
+            .try_resource_discrete_control()?
             .boolean_control_ggio
             .clone())
     }
@@ -418,19 +389,13 @@ impl ResourceControlExt for ResourceDiscreteControlProfile {
         let into_iter = self.string_ggio()?.into_iter();
 
         for item in into_iter {
-            if let Ok(name) = item
-                .logical_node
-                .as_ref()
-                .context(NoLogicalNode)?
-                .identified_object
-                .as_ref()
-                .context(NoIdentifiedObject)?
-                .name
-                .as_ref()
-                .context(NoName)
-            {
+            if let Ok(name) = item.try_logical_node_and_then(|logical_node| {
+                logical_node.try_identified_object_and_then(|identified_object| {
+                    identified_object.try_name()
+                })
+            }) {
                 if key == name.to_string() {
-                    return Ok(item.str_out.as_ref().context(NoVsc)?.ctl_val.clone());
+                    return Ok(item.try_str_out()?.ctl_val.clone());
                 }
             }
         }
@@ -442,19 +407,13 @@ impl ResourceControlExt for ResourceDiscreteControlProfile {
         let into_iter = self.analog_ggio()?.into_iter();
 
         for item in into_iter {
-            if let Ok(name) = item
-                .logical_node
-                .as_ref()
-                .context(NoLogicalNode)?
-                .identified_object
-                .as_ref()
-                .context(NoIdentifiedObject)?
-                .name
-                .as_ref()
-                .context(NoName)
-            {
+            if let Ok(name) = item.try_logical_node_and_then(|logical_node| {
+                logical_node.try_identified_object_and_then(|identified_object| {
+                    identified_object.try_name()
+                })
+            }) {
                 if key == name.to_string() {
-                    return Ok(item.an_out.as_ref().context(NoControlApc)?.ctl_val);
+                    return Ok(item.try_an_out()?.ctl_val);
                 }
             }
         }
@@ -466,19 +425,13 @@ impl ResourceControlExt for ResourceDiscreteControlProfile {
         let into_iter = self.integer_ggio()?.into_iter();
 
         for item in into_iter {
-            if let Ok(name) = item
-                .logical_node
-                .as_ref()
-                .context(NoLogicalNode)?
-                .identified_object
-                .as_ref()
-                .context(NoIdentifiedObject)?
-                .name
-                .as_ref()
-                .context(NoName)
-            {
+            if let Ok(name) = item.try_logical_node_and_then(|logical_node| {
+                logical_node.try_identified_object_and_then(|identified_object| {
+                    identified_object.try_name()
+                })
+            }) {
                 if key == name.to_string() {
-                    return Ok(item.iscso.as_ref().context(NoControlInc)?.ctl_val);
+                    return Ok(item.try_iscso()?.ctl_val);
                 }
             }
         }
@@ -490,19 +443,13 @@ impl ResourceControlExt for ResourceDiscreteControlProfile {
         let into_iter = self.boolean_ggio()?.into_iter();
 
         for item in into_iter {
-            if let Ok(name) = item
-                .logical_node
-                .as_ref()
-                .context(NoLogicalNode)?
-                .identified_object
-                .as_ref()
-                .context(NoIdentifiedObject)?
-                .name
-                .as_ref()
-                .context(NoName)
-            {
+            if let Ok(name) = item.try_logical_node_and_then(|logical_node| {
+                logical_node.try_identified_object_and_then(|identified_object| {
+                    identified_object.try_name()
+                })
+            }) {
                 if key == name.to_string() {
-                    return Ok(item.spcso.as_ref().context(NoControlSpc)?.ctl_val);
+                    return Ok(item.try_spcso()?.ctl_val);
                 }
             }
         }
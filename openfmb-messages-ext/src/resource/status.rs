@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,10 +13,7 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtStatus};
 
 impl OpenFMBExtStatus for ResourceStatusProfile {
     fn status_message_info(&self) -> OpenFMBResult<&StatusMessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_status_message_info()?)
     }
 }
 
@@ -26,13 +23,11 @@ impl OpenFMBExt for ResourceStatusProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_status_message_info_and_then(|status_message_info| {
+                status_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -40,27 +35,16 @@ impl OpenFMBExt for ResourceStatusProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
-            &self
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
-                .m_rid,
-        )
-        .context(UuidError)?)
+        Uuid::from_str(&self.try_conducting_equipment()?.m_rid).context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_conducting_equipment_and_then(|conducting_equipment| {
+                conducting_equipment
+                    .try_named_object_and_then(|named_object| named_object.try_name())
+            })
+            .cloned()?)
     }
 }
 
@@ -81,15 +65,10 @@ pub trait ResourceStatusExt {
 impl ResourceStatusExt for ResourceStatusProfile {
     fn message_identified_object_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?
-            .identified_object
-            .as_ref()
-            .context(NoIdentifiedObject)?
+            .try_status_message_info_and_then(|status_message_info| {
+                status_message_info
+                    .try_message_info_and_then(|message_info| message_info.try_identified_object())
+            })?
             .name
             .clone()
             .unwrap_or("".to_string()))
@@ -97,15 +76,10 @@ impl ResourceStatusExt for ResourceStatusProfile {
 
     fn message_identified_description(&self) -> OpenFMBResult<String> {
         Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?
-            .identified_object
-            .as_ref()
-            .context(NoIdentifiedObject)?
+            .try_status_message_info_and_then(|status_message_info| {
+                status_message_info
+                    .try_message_info_and_then(|message_info| message_info.try_identified_object())
+            })?
             .description
             .clone()
             .unwrap_or("".to_string()))
@@ -113,36 +87,28 @@ impl ResourceStatusExt for ResourceStatusProfile {
 
     fn string_ggio(&self) -> OpenFMBResult<Vec<StringEventAndStatusGgio>> {
         Ok(self
-            .resource_status
-            .as_ref()
-            .context(NoResourceStatus)?
+            .try_resource_status()?
             .string_event_and_status_ggio
             .clone())
     }
 
     fn analog_ggio(&self) -> OpenFMBResult<Vec<AnalogEventAndStatusGgio>> {
         Ok(self
-            .resource_status
-            .as_ref()
-            .context(NoResourceStatus)?
+            .try_resource_status()?
             .analog_event_and_status_ggio
             .clone())
     }
 
     fn integer_ggio(&self) -> OpenFMBResult<Vec<IntegerEventAndStatusGgio>> {
         Ok(self
-            .resource_status
-            .as_ref()
-            .context(NoResourceStatus)?
+            .try_resource_status()?
             .integer_event_and_status_ggio
             .clone())
     }
 
     fn boolean_ggio(&self) -> OpenFMBResult<Vec<BooleanEventAndStatusGgio>> {
         Ok(self
-            .resource_status
-            .as_ref()
-            .context(NoResourceStatus)?
+            .try_resource_status()?
             .boolean_event_and_status_ggio
             .clone())
     }
@@ -151,19 +117,13 @@ impl ResourceStatusExt for ResourceStatusProfile {
         let into_iter = self.string_ggio()?.into_iter();
 
         for item in into_iter {
-            if let Ok(name) = item
-                .logical_node
-                .as_ref()
-                .context(NoLogicalNode)?
-                .identified_object
-                .as_ref()
-                .context(NoIdentifiedObject)?
-                .name
-                .as_ref()
-                .context(NoName)
-            {
+            if let Ok(name) = item.try_logical_node_and_then(|logical_node| {
+                logical_node.try_identified_object_and_then(|identified_object| {
+                    identified_object.try_name()
+                })
+            }) {
                 if key == name.to_string() {
-                    return Ok(item.str_in.as_ref().context(NoVss)?.st_val.clone());
+                    return Ok(item.try_str_in()?.st_val.clone());
                 }
             }
         }
@@ -175,19 +135,13 @@ impl ResourceStatusExt for ResourceStatusProfile {
         let into_iter = self.analog_ggio()?.into_iter();
 
         for item in into_iter {
-            if let Ok(name) = item
-                .logical_node
-                .as_ref()
-                .context(NoLogicalNode)?
-                .identified_object
-                .as_ref()
-                .context(NoIdentifiedObject)?
-                .name
-                .as_ref()
-                .context(NoName)
-            {
+            if let Ok(name) = item.try_logical_node_and_then(|logical_node| {
+                logical_node.try_identified_object_and_then(|identified_object| {
+                    identified_object.try_name()
+                })
+            }) {
                 if key == name.to_string() {
-                    return Ok(item.an_in.as_ref().context(NoMv)?.mag);
+                    return Ok(item.try_an_in()?.mag);
                 }
             }
         }
@@ -199,19 +153,13 @@ impl ResourceStatusExt for ResourceStatusProfile {
         let into_iter = self.integer_ggio()?.into_iter();
 
         for item in into_iter {
-            if let Ok(name) = item
-                .logical_node
-                .as_ref()
-                .context(NoLogicalNode)?
-                .identified_object
-                .as_ref()
-                .context(NoIdentifiedObject)?
-                .name
-                .as_ref()
-                .context(NoName)
-            {
+            if let Ok(name) = item.try_logical_node_and_then(|logical_node| {
+                logical_node.try_identified_object_and_then(|identified_object| {
+                    identified_object.try_name()
+                })
+            }) {
                 if key == name.to_string() {
-                    return Ok(item.int_in.as_ref().context(NoStatusIns)?.st_val);
+                    return Ok(item.try_int_in()?.st_val);
                 }
             }
         }
@@ -223,19 +171,13 @@ impl ResourceStatusExt for ResourceStatusProfile {
         let into_iter = self.boolean_ggio()?.into_iter();
 
         for item in into_iter {
-            if let Ok(name) = item
-                .logical_node
-                .as_ref()
-                .context(NoLogicalNode)?
-                .identified_object
-                .as_ref()
-                .context(NoIdentifiedObject)?
-                .name
-                .as_ref()
-                .context(NoName)
-            {
+            if let Ok(name) = item.try_logical_node_and_then(|logical_node| {
+                logical_node.try_identified_object_and_then(|identified_object| {
+                    identified_object.try_name()
+                })
+            }) {
                 if key == name.to_string() {
-                    return Ok(item.ind.as_ref().context(NoMv)?.st_val);
+                    return Ok(item.try_ind()?.st_val);
                 }
             }
         }
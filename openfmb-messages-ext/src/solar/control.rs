@@ -14,7 +14,7 @@ use openfmb_messages::{
         SolarCurvePoint, SolarInverter, SolarPoint,
     },
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::{str::FromStr, time::SystemTime};
 use uuid::Uuid;
 
@@ -47,7 +47,7 @@ impl OpenFMBExt for SolarControlProfile {
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
         unimplemented!()
-        //        Ok(self.solar_control.clone().context(NoStatusMessageInfo)?..unwrap())
+        //        Ok(self.try_solar_control().cloned()?..unwrap())
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -55,33 +55,25 @@ impl OpenFMBExt for SolarControlProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .solar_inverter
-                .as_ref()
-                .context(NoSolarInverter)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_solar_inverter_and_then(|solar_inverter| {
+                    solar_inverter.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .solar_inverter
-            .as_ref()
-            .context(NoSolarInverter)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_solar_inverter_and_then(|solar_inverter| {
+                solar_inverter.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -13,51 +13,35 @@ use crate::{error::*, OpenFMBExt, OpenFMBExtEvent};
 
 impl OpenFMBExtEvent for SolarEventProfile {
     fn event_message_info(&self) -> OpenFMBResult<&EventMessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?)
+        Ok(self.try_event_message_info()?)
     }
 }
 
 impl OpenFMBExt for SolarEventProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .solar_event
-            .as_ref()
-            .context(NoSolarEvent)?
-            .solar_event_zgen
-            .as_ref()
-            .context(NoSolarEventZGen)?
-            .solar_event_and_status_zgen
-            .as_ref()
-            .context(NoSolarEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)
-        {
-            Ok(state) => match state.value {
-                0 => Ok("Undefined".to_string()),
-                1 => Ok("Off".to_string()),
-                2 => Ok("On".to_string()),
-                3 => Ok("StandBy".to_string()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let state = self.try_solar_event_and_then(|solar_event| {
+            solar_event.try_solar_event_zgen_and_then(|solar_event_zgen| {
+                solar_event_zgen.try_solar_event_and_status_zgen_and_then(
+                    |solar_event_and_status_zgen| {
+                        solar_event_and_status_zgen
+                            .try_point_status_and_then(|point_status| point_status.try_state())
+                    },
+                )
+            })
+        })?;
+        match state.value {
+            0 => Ok("Undefined".to_string()),
+            1 => Ok("Off".to_string()),
+            2 => Ok("On".to_string()),
+            3 => Ok("StandBy".to_string()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .event_message_info
-            .as_ref()
-            .context(NoEventMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(self.try_event_message_info_and_then(|event_message_info| {
+            event_message_info.try_message_info()
+        })?)
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -65,32 +49,24 @@ impl OpenFMBExt for SolarEventProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .solar_inverter
-                .as_ref()
-                .context(NoSolarInverter)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_solar_inverter_and_then(|solar_inverter| {
+                    solar_inverter.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .solar_inverter
-            .as_ref()
-            .context(NoSolarInverter)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_solar_inverter_and_then(|solar_inverter| {
+                solar_inverter.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
@@ -10,47 +10,36 @@ use openfmb_messages::{
     commonmodule::{MessageInfo, StatusMessageInfo},
     solarmodule::SolarStatusProfile,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use uuid::Uuid;
 
 impl OpenFMBExt for SolarStatusProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .solar_status
-            .as_ref()
-            .context(NoSolarStatus)?
-            .solar_status_zgen
-            .as_ref()
-            .context(NoSolarStatusZGen)?
-            .solar_event_and_status_zgen
-            .as_ref()
-            .context(NoSolarEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)
-        {
-            Ok(state) => match state.value {
-                0 => Ok("Undefined".to_string()),
-                1 => Ok("Off".to_string()),
-                2 => Ok("On".to_string()),
-                3 => Ok("StandBy".to_string()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let state = self.try_solar_status_and_then(|solar_status| {
+            solar_status.try_solar_status_zgen_and_then(|solar_status_zgen| {
+                solar_status_zgen.try_solar_event_and_status_zgen_and_then(
+                    |solar_event_and_status_zgen| {
+                        solar_event_and_status_zgen
+                            .try_point_status_and_then(|point_status| point_status.try_state())
+                    },
+                )
+            })
+        })?;
+        match state.value {
+            0 => Ok("Undefined".to_string()),
+            1 => Ok("Off".to_string()),
+            2 => Ok("On".to_string()),
+            3 => Ok("StandBy".to_string()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_status_message_info_and_then(|status_message_info| {
+                status_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -58,42 +47,31 @@ impl OpenFMBExt for SolarStatusProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .solar_inverter
-                .as_ref()
-                .context(NoSolarInverter)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_solar_inverter_and_then(|solar_inverter| {
+                    solar_inverter.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .solar_inverter
-            .as_ref()
-            .context(NoSolarInverter)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_solar_inverter_and_then(|solar_inverter| {
+                solar_inverter.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtStatus for SolarStatusProfile {
     fn status_message_info(&self) -> OpenFMBResult<&StatusMessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_status_message_info()?)
     }
 }
 
@@ -105,38 +83,30 @@ pub trait SolarStatusExt: StatusProfileExt {
 impl SolarStatusExt for SolarStatusProfile {
     fn solar_state(&self) -> OpenFMBResult<StateKind> {
         Ok(self
-            .solar_status
-            .as_ref()
-            .context(NoSolarStatus)?
-            .solar_status_zgen
-            .as_ref()
-            .context(NoSolarStatusZGen)?
-            .solar_event_and_status_zgen
-            .as_ref()
-            .context(NoSolarEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
-            .state
-            .as_ref()
-            .context(NoState)?
+            .try_solar_status_and_then(|solar_status| {
+                solar_status.try_solar_status_zgen_and_then(|solar_status_zgen| {
+                    solar_status_zgen.try_solar_event_and_status_zgen_and_then(
+                        |solar_event_and_status_zgen| {
+                            solar_event_and_status_zgen
+                                .try_point_status_and_then(|point_status| point_status.try_state())
+                        },
+                    )
+                })
+            })?
             .value())
     }
 
     fn pct_v_droop(&self) -> OpenFMBResult<Option<f32>> {
         Ok(self
-            .solar_status
-            .as_ref()
-            .context(NoSolarStatus)?
-            .solar_status_zgen
-            .as_ref()
-            .context(NoSolarStatusZGen)?
-            .solar_event_and_status_zgen
-            .as_ref()
-            .context(NoSolarEventAndStatusZGen)?
-            .point_status
-            .as_ref()
-            .context(NoPointStatus)?
+            .try_solar_status_and_then(|solar_status| {
+                solar_status.try_solar_status_zgen_and_then(|solar_status_zgen| {
+                    solar_status_zgen.try_solar_event_and_status_zgen_and_then(
+                        |solar_event_and_status_zgen| {
+                            solar_event_and_status_zgen.try_point_status()
+                        },
+                    )
+                })
+            })?
             .pct_v_droop)
     }
 }
@@ -19,18 +19,9 @@ impl OpenFMBExt for SwitchReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag
                 .to_string());
         }
@@ -38,13 +29,11 @@ impl OpenFMBExt for SwitchReadingProfile {
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_reading_message_info_and_then(|reading_message_info| {
+                reading_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -52,42 +41,31 @@ impl OpenFMBExt for SwitchReadingProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .protected_switch
-                .as_ref()
-                .context(NoProtectedSwitch)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_protected_switch_and_then(|protected_switch| {
+                    protected_switch.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .protected_switch
-            .as_ref()
-            .context(NoProtectedSwitch)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_protected_switch_and_then(|protected_switch| {
+                protected_switch.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtReading for SwitchReadingProfile {
     fn reading_message_info(&self) -> OpenFMBResult<&ReadingMessageInfo> {
-        Ok(self
-            .reading_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_reading_message_info()?)
     }
 }
 
@@ -99,18 +77,9 @@ impl ReadingProfileExt for SwitchReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -123,18 +92,10 @@ impl ReadingProfileExt for SwitchReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .v_ar
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -147,18 +108,9 @@ impl ReadingProfileExt for SwitchReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .w
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_w_and_then(|w| w.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -171,18 +123,10 @@ impl ReadingProfileExt for SwitchReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .v_ar
-                .as_ref()
-                .context(NoW)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_v_ar_and_then(|v_ar| v_ar.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -195,18 +139,10 @@ impl ReadingProfileExt for SwitchReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .ph_v
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -219,18 +155,10 @@ impl ReadingProfileExt for SwitchReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .ph_v
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu
+                        .try_ph_v_and_then(|ph_v| ph_v.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -243,18 +171,9 @@ impl ReadingProfileExt for SwitchReadingProfile {
                 .first()
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .a
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
@@ -267,18 +186,9 @@ impl ReadingProfileExt for SwitchReadingProfile {
                 .get(1)
                 .as_ref()
                 .context(NoSwitchReading)?
-                .reading_mmxu
-                .as_ref()
-                .context(NoReadingMmxu)?
-                .a
-                .as_ref()
-                .context(NoValue)?
-                .net
-                .as_ref()
-                .context(NoNet)?
-                .c_val
-                .as_ref()
-                .context(NoCVal)?
+                .try_reading_mmxu_and_then(|reading_mmxu| {
+                    reading_mmxu.try_a_and_then(|a| a.try_net_and_then(|net| net.try_c_val()))
+                })?
                 .mag);
         }
         Err(OpenFMBError::NoSwitchReading)
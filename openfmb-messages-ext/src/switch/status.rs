@@ -7,46 +7,33 @@ use openfmb_messages::{
     commonmodule::{DbPosKind, MessageInfo, StatusMessageInfo},
     switchmodule::SwitchStatusProfile,
 };
-use snafu::{OptionExt, ResultExt};
+use snafu::ResultExt;
 use std::str::FromStr;
 use uuid::Uuid;
 
 impl OpenFMBExt for SwitchStatusProfile {
     fn device_state(&self) -> OpenFMBResult<String> {
-        match self
-            .switch_status
-            .as_ref()
-            .context(NoSwitchStatus)?
-            .switch_status_xswi
-            .as_ref()
-            .context(NoSwitchStatusXswi)?
-            .pos
-            .as_ref()
-            .context(NoPos)?
-            .phs3
-            .as_ref()
-            .context(NoPhs3)
-        {
-            Ok(phs3) => match phs3.st_val {
-                0 => Ok("Undefined".into()),
-                1 => Ok("Transient".into()),
-                2 => Ok("Closed".into()),
-                3 => Ok("Open".into()),
-                4 => Ok("Invalid".into()),
-                _ => Err(OpenFMBError::InvalidValue),
-            },
-            Err(_) => Err(OpenFMBError::InvalidOpenFMBMessage),
+        let phs3 = self.try_switch_status_and_then(|switch_status| {
+            switch_status.try_switch_status_xswi_and_then(|switch_status_xswi| {
+                switch_status_xswi.try_pos_and_then(|pos| pos.try_phs3())
+            })
+        })?;
+        match phs3.st_val {
+            0 => Ok("Undefined".into()),
+            1 => Ok("Transient".into()),
+            2 => Ok("Closed".into()),
+            3 => Ok("Open".into()),
+            4 => Ok("Invalid".into()),
+            _ => Err(OpenFMBError::InvalidValue),
         }
     }
 
     fn message_info(&self) -> OpenFMBResult<&MessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?
-            .message_info
-            .as_ref()
-            .context(NoMessageInfo)?)
+        Ok(
+            self.try_status_message_info_and_then(|status_message_info| {
+                status_message_info.try_message_info()
+            })?,
+        )
     }
 
     fn message_type(&self) -> OpenFMBResult<String> {
@@ -54,42 +41,31 @@ impl OpenFMBExt for SwitchStatusProfile {
     }
 
     fn device_mrid(&self) -> OpenFMBResult<Uuid> {
-        Ok(Uuid::from_str(
+        Uuid::from_str(
             &self
-                .protected_switch
-                .as_ref()
-                .context(NoProtectedSwitch)?
-                .conducting_equipment
-                .as_ref()
-                .context(NoConductingEquipment)?
+                .try_protected_switch_and_then(|protected_switch| {
+                    protected_switch.try_conducting_equipment()
+                })?
                 .m_rid,
         )
-        .context(UuidError)?)
+        .context(UuidError)
     }
 
     fn device_name(&self) -> OpenFMBResult<String> {
         Ok(self
-            .protected_switch
-            .as_ref()
-            .context(NoProtectedSwitch)?
-            .conducting_equipment
-            .as_ref()
-            .context(NoConductingEquipment)?
-            .named_object
-            .as_ref()
-            .context(NoNamedObject)?
-            .name
-            .clone()
-            .context(NoName)?)
+            .try_protected_switch_and_then(|protected_switch| {
+                protected_switch.try_conducting_equipment_and_then(|conducting_equipment| {
+                    conducting_equipment
+                        .try_named_object_and_then(|named_object| named_object.try_name())
+                })
+            })
+            .cloned()?)
     }
 }
 
 impl OpenFMBExtStatus for SwitchStatusProfile {
     fn status_message_info(&self) -> OpenFMBResult<&StatusMessageInfo> {
-        Ok(self
-            .status_message_info
-            .as_ref()
-            .context(NoStatusMessageInfo)?)
+        Ok(self.try_status_message_info()?)
     }
 }
 
@@ -101,64 +77,36 @@ impl Position for SwitchStatusProfile {
     fn pos_per_phase(&self, phase: Phase) -> OpenFMBResult<DbPosKind> {
         let st_val = match phase {
             Phase::Phs3 => {
-                self.switch_status
-                    .as_ref()
-                    .context(NoSwitchStatus)?
-                    .switch_status_xswi
-                    .as_ref()
-                    .context(NoSwitchStatusXswi)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs3
-                    .as_ref()
-                    .context(NoPhs3)?
-                    .st_val
+                self.try_switch_status_and_then(|switch_status| {
+                    switch_status.try_switch_status_xswi_and_then(|switch_status_xswi| {
+                        switch_status_xswi.try_pos_and_then(|pos| pos.try_phs3())
+                    })
+                })?
+                .st_val
             }
             Phase::PhsA => {
-                self.switch_status
-                    .as_ref()
-                    .context(NoSwitchStatus)?
-                    .switch_status_xswi
-                    .as_ref()
-                    .context(NoSwitchStatusXswi)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs_a
-                    .as_ref()
-                    .context(NoPhsA)?
-                    .st_val
+                self.try_switch_status_and_then(|switch_status| {
+                    switch_status.try_switch_status_xswi_and_then(|switch_status_xswi| {
+                        switch_status_xswi.try_pos_and_then(|pos| pos.try_phs_a())
+                    })
+                })?
+                .st_val
             }
             Phase::PhsB => {
-                self.switch_status
-                    .as_ref()
-                    .context(NoSwitchStatus)?
-                    .switch_status_xswi
-                    .as_ref()
-                    .context(NoSwitchStatusXswi)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs_b
-                    .as_ref()
-                    .context(NoPhsB)?
-                    .st_val
+                self.try_switch_status_and_then(|switch_status| {
+                    switch_status.try_switch_status_xswi_and_then(|switch_status_xswi| {
+                        switch_status_xswi.try_pos_and_then(|pos| pos.try_phs_b())
+                    })
+                })?
+                .st_val
             }
             Phase::PhsC => {
-                self.switch_status
-                    .as_ref()
-                    .context(NoSwitchStatus)?
-                    .switch_status_xswi
-                    .as_ref()
-                    .context(NoSwitchStatusXswi)?
-                    .pos
-                    .as_ref()
-                    .context(NoPos)?
-                    .phs_c
-                    .as_ref()
-                    .context(NoPhsC)?
-                    .st_val
+                self.try_switch_status_and_then(|switch_status| {
+                    switch_status.try_switch_status_xswi_and_then(|switch_status_xswi| {
+                        switch_status_xswi.try_pos_and_then(|pos| pos.try_phs_c())
+                    })
+                })?
+                .st_val
             }
         };
 
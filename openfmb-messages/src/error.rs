@@ -0,0 +1,47 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+/// A field that is not set, named by its fully qualified proto name, e.g.
+/// `switchmodule.SwitchStatusXSWI.Pos`
+///
+/// Returned by the generated `try_<field>()` accessors and by builders
+/// missing a field the UML model requires. The name is that of the field
+/// in the message declaring it, as an accessor does not know where its
+/// message sits in the profile. Going through the generated
+/// `try_<field>_and_then()` helpers names it by its path from the message
+/// the chain started at instead, e.g.
+/// `switchmodule.SwitchStatusProfile.switchStatus.switchStatusXSWI.Pos`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingField {
+    pub field: String,
+}
+
+impl MissingField {
+    pub fn new<F: Into<String>>(field: F) -> MissingField {
+        MissingField {
+            field: field.into(),
+        }
+    }
+
+    /// The missing field named from the parent field holding the message
+    /// it was missing from, e.g. `switchmodule.SwitchStatusXSWI.Pos` within
+    /// `switchmodule.SwitchStatus.switchStatusXSWI` becomes
+    /// `switchmodule.SwitchStatus.switchStatusXSWI.Pos`
+    pub fn within(self, parent: &str) -> MissingField {
+        // drop the package and the message of the field, which is the type
+        // of the parent field; the OpenFMB packages are a single level
+        match self.field.splitn(3, '.').nth(2) {
+            Some(path) => MissingField::new(format!("{}.{}", parent, path)),
+            None => self,
+        }
+    }
+}
+
+impl std::fmt::Display for MissingField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for MissingField {}
@@ -0,0 +1,12 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+/// The fully qualified proto name of a message, e.g.
+/// `switchmodule.SwitchStatusProfile`, generated for every message
+///
+/// Names the message in a descriptor pool, so that any message can be
+/// handled as a dynamic one.
+pub trait FullName {
+    const FULL_NAME: &'static str;
+}
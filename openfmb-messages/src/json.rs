@@ -0,0 +1,193 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! serde helpers the generated messages use for the proto3 JSON mapping
+//!
+//! Enums are written as their value names, 64-bit integers as strings and
+//! non-finite floats as `"NaN"`, `"Infinity"` and `"-Infinity"`. Reading
+//! accepts either form of each as well as `null` for the default value.
+//!
+//! Formats that are not human readable, such as CBOR and MessagePack, keep
+//! the compact form and get enums as their numbers and 64-bit integers and
+//! floats as is.
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Generated enums, which messages hold as their i32 values
+pub trait ProtoEnum: Sized {
+    fn from_i32(value: i32) -> Option<Self>;
+    fn as_i32(self) -> i32;
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EnumValue<E> {
+    Name(E),
+    Number(i32),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberValue<T> {
+    Number(T),
+    String(String),
+}
+
+impl<T: FromStr> NumberValue<T>
+where
+    T::Err: Display,
+{
+    fn parse<E: de::Error>(self) -> Result<T, E> {
+        match self {
+            NumberValue::Number(value) => Ok(value),
+            NumberValue::String(value) => value.parse().map_err(E::custom),
+        }
+    }
+}
+
+pub mod nullable {
+    use super::*;
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Default + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Option::<T>::deserialize(deserializer).map(Option::unwrap_or_default)
+    }
+}
+
+pub mod enumeration {
+    use super::*;
+
+    /// Values without a name in the enum are written as numbers
+    pub fn serialize<E, S>(value: &i32, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        E: ProtoEnum + Serialize,
+        S: Serializer,
+    {
+        match E::from_i32(*value) {
+            Some(named) if serializer.is_human_readable() => named.serialize(serializer),
+            _ => serializer.serialize_i32(*value),
+        }
+    }
+
+    pub fn deserialize<'de, E, D>(deserializer: D) -> Result<i32, D::Error>
+    where
+        E: ProtoEnum + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(match Option::<EnumValue<E>>::deserialize(deserializer)? {
+            Some(EnumValue::Name(named)) => named.as_i32(),
+            Some(EnumValue::Number(value)) => value,
+            None => 0,
+        })
+    }
+}
+
+pub mod int64 {
+    use super::*;
+
+    pub fn serialize<T: Display + Serialize, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(value)
+        } else {
+            value.serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Default + FromStr + Deserialize<'de>,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        super::optional_int64::deserialize(deserializer).map(Option::unwrap_or_default)
+    }
+}
+
+pub mod optional_int64 {
+    use super::*;
+
+    pub fn serialize<T: Display + Serialize, S: Serializer>(
+        value: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::int64::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromStr + Deserialize<'de>,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        Option::<NumberValue<T>>::deserialize(deserializer)?
+            .map(NumberValue::parse)
+            .transpose()
+    }
+}
+
+pub mod float {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Copy + Into<f64> + Serialize,
+        S: Serializer,
+    {
+        let float: f64 = (*value).into();
+        if !serializer.is_human_readable() || float.is_finite() {
+            value.serialize(serializer)
+        } else if float.is_nan() {
+            serializer.serialize_str("NaN")
+        } else if float == f64::INFINITY {
+            serializer.serialize_str("Infinity")
+        } else {
+            serializer.serialize_str("-Infinity")
+        }
+    }
+
+    /// Float parsing accepts the non-finite names
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Default + FromStr + Deserialize<'de>,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        super::optional_float::deserialize(deserializer).map(Option::unwrap_or_default)
+    }
+}
+
+pub mod optional_float {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Copy + Into<f64> + Serialize,
+        S: Serializer,
+    {
+        match value {
+            Some(value) => super::float::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromStr + Deserialize<'de>,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        super::optional_int64::deserialize(deserializer)
+    }
+}
@@ -86,7 +86,16 @@ impl std::str::FromStr for Module {
     }
 }
 
+mod error;
+pub use error::MissingField;
+mod full_name;
+pub use full_name::FullName;
+mod json;
 mod profiles;
 pub use profiles::Profile;
+mod validate;
+pub use validate::{is_uuid, Validate, Violation, ViolationKind};
 mod variant;
-pub use variant::ProfileMessage;
+pub use variant::{ProfileDecoder, ProfileMessage};
+mod visit;
+pub use visit::{Visit, VisitMut, Visitor, VisitorMut};
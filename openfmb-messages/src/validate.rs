@@ -0,0 +1,219 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validation of messages against the options given to their fields in
+//! the UML model, generated for every message
+//!
+//! ```ignore
+//! if let Err(violations) = control.validate() {
+//!     for violation in violations {
+//!         log::warn!("rejecting control, {}", violation);
+//!     }
+//! }
+//! ```
+
+/// Checks a message against the required fields, multiplicities and mRID
+/// formats of the UML model
+pub trait Validate {
+    /// Every violation found in the message, with the paths of the fields
+    /// relative to it
+    fn validate(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        self.validate_at("", &mut violations);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Adds the violations found in the message, with field paths starting
+    /// at the given path of the message itself
+    fn validate_at(&self, path: &str, violations: &mut Vec<Violation>);
+}
+
+/// A field breaking a rule of the UML model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Path of the field using the proto field names, for example
+    /// `statusMessageInfo.messageInfo.identifiedObject.mRID` or
+    /// `breakerReading[2]`
+    pub path: String,
+    pub kind: ViolationKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// A required field is not set, or empty for a string
+    MissingRequired,
+    /// A repeated field has fewer elements than its multiplicity allows
+    TooFew { min: usize, len: usize },
+    /// A repeated field has more elements than its multiplicity allows
+    TooMany { max: usize, len: usize },
+    /// A field holding an mRID is not a UUID
+    NotUuid(String),
+}
+
+impl Violation {
+    pub fn new<P: Into<String>>(path: P, kind: ViolationKind) -> Violation {
+        Violation {
+            path: path.into(),
+            kind,
+        }
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {:?}", self.path, self.kind)
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// True for the hyphenated hex form of a UUID,
+/// e.g. `b3a2c5f4-1d2e-4f60-8a7b-9c0d1e2f3a4b`
+pub fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.bytes().enumerate().all(|(idx, b)| match idx {
+            8 | 13 | 18 | 23 => b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+pub(crate) fn field_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+pub(crate) fn index_path(path: &str, name: &str, idx: usize) -> String {
+    format!("{}[{}]", field_path(path, name), idx)
+}
+
+pub(crate) fn check_required(
+    path: &str,
+    name: &str,
+    present: bool,
+    violations: &mut Vec<Violation>,
+) {
+    if !present {
+        violations.push(Violation::new(
+            field_path(path, name),
+            ViolationKind::MissingRequired,
+        ));
+    }
+}
+
+pub(crate) fn check_multiplicity(
+    path: &str,
+    name: &str,
+    len: usize,
+    min: usize,
+    max: Option<usize>,
+    violations: &mut Vec<Violation>,
+) {
+    if len < min {
+        violations.push(Violation::new(
+            field_path(path, name),
+            ViolationKind::TooFew { min, len },
+        ));
+    }
+    match max {
+        Some(max) if len > max => violations.push(Violation::new(
+            field_path(path, name),
+            ViolationKind::TooMany { max, len },
+        )),
+        _ => (),
+    }
+}
+
+/// An empty mRID is left to the required check
+pub(crate) fn check_uuid(path: &str, value: &str, violations: &mut Vec<Violation>) {
+    if !value.is_empty() && !is_uuid(value) {
+        violations.push(Violation::new(
+            path,
+            ViolationKind::NotUuid(value.to_string()),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::breakermodule::{
+        BreakerDiscreteControlProfile, BreakerReading, BreakerReadingProfile,
+    };
+    use crate::commonmodule::{
+        ConductingEquipmentTerminalReading, ControlMessageInfo, IdentifiedObject, MessageInfo,
+    };
+
+    const MRID: &str = "b3a2c5f4-1d2e-4f60-8a7b-9c0d1e2f3a4b";
+
+    #[test]
+    fn test_is_uuid() {
+        assert!(is_uuid(MRID));
+        assert!(is_uuid(&MRID.to_uppercase()));
+        assert!(!is_uuid("b3a2c5f41d2e4f608a7b9c0d1e2f3a4b"));
+        assert!(!is_uuid("breaker-1"));
+        assert!(!is_uuid("g3a2c5f4-1d2e-4f60-8a7b-9c0d1e2f3a4b"));
+    }
+
+    #[test]
+    fn test_validate() {
+        let msg = BreakerDiscreteControlProfile {
+            control_message_info: Some(ControlMessageInfo {
+                message_info: Some(MessageInfo {
+                    identified_object: Some(IdentifiedObject {
+                        m_rid: Some("not-a-uuid".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            }),
+            ..Default::default()
+        };
+        let violations = msg.validate().unwrap_err();
+        assert!(violations.contains(&Violation::new(
+            "controlMessageInfo.messageInfo.identifiedObject.mRID",
+            ViolationKind::NotUuid("not-a-uuid".to_string()),
+        )));
+        assert!(violations.contains(&Violation::new("breaker", ViolationKind::MissingRequired)));
+        assert!(violations.contains(&Violation::new(
+            "breakerDiscreteControl",
+            ViolationKind::MissingRequired
+        )));
+
+        let mut reading = BreakerReading::default();
+        reading.conducting_equipment_terminal_reading =
+            Some(ConductingEquipmentTerminalReading::default());
+        let msg = BreakerReadingProfile {
+            breaker_reading: vec![
+                BreakerReading::default(),
+                BreakerReading::default(),
+                reading,
+            ],
+            ..Default::default()
+        };
+        let violations = msg.validate().unwrap_err();
+        assert!(violations.contains(&Violation::new(
+            "breakerReading",
+            ViolationKind::TooMany { max: 2, len: 3 }
+        )));
+        // each element is checked as well
+        assert!(violations.contains(&Violation::new(
+            "breakerReading[2].conductingEquipmentTerminalReading.terminal",
+            ViolationKind::MissingRequired
+        )));
+
+        let msg = BreakerReadingProfile::default();
+        let violations = msg.validate().unwrap_err();
+        assert!(violations.contains(&Violation::new(
+            "breakerReading",
+            ViolationKind::TooFew { min: 1, len: 0 }
+        )));
+    }
+}
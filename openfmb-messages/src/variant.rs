@@ -2,4 +2,13 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+/// Decodes a message of any single type, for the serde formats whose
+/// Deserializer is not public and so cannot be given to
+/// ProfileMessage::deserialize_profile, see ProfileMessage::decode_profile
+pub trait ProfileDecoder {
+    type Error;
+
+    fn decode<M: serde::de::DeserializeOwned>(self) -> Result<M, Self::Error>;
+}
+
 include!(concat!(env!("OUT_DIR"), "/variant.rs"));
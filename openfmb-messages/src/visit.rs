@@ -0,0 +1,224 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Walking every field of a message, generated for every message, with
+//! callbacks for the leaves a generic function is usually after
+//!
+//! ```ignore
+//! /// Every mRID in a message replaced, e.g. before logging it
+//! struct Redact;
+//!
+//! impl VisitorMut for Redact {
+//!     fn visit_mrid_mut(&mut self, _path: &str, mrid: &mut String) {
+//!         *mrid = "redacted".to_string();
+//!     }
+//! }
+//!
+//! profile.visit_mut(&mut Redact);
+//! ```
+//!
+//! Fields are walked in the order they are declared in the proto, so the
+//! quality `q` of a measured value is visited just after its value, e.g.
+//! `readingMMXU.W.net.cVal.mag` then `readingMMXU.W.net.q`.
+
+use crate::commonmodule::{Quality, Timestamp};
+
+/// Callbacks for the leaves of a message, each given the path of the field
+/// using the proto field names, e.g. `switchStatus.switchStatusXSWI.Pos.phs3.stVal`
+/// or `breakerReading[1].readingMMXU.W.net.cVal.mag`
+///
+/// Every callback does nothing by default. Message fields and wrapper types
+/// that are not set are not visited, plain scalars and enums always are,
+/// at their default value when not set.
+pub trait Visitor {
+    /// A double or float field, or their wrapper types
+    fn visit_analog(&mut self, _path: &str, _value: f64) {}
+
+    /// A Timestamp, which is not walked any further
+    fn visit_timestamp(&mut self, _path: &str, _value: &Timestamp) {}
+
+    /// A Quality, which is not walked any further
+    fn visit_quality(&mut self, _path: &str, _value: &Quality) {}
+
+    /// An enum field, with the fully qualified proto name of the enum, e.g.
+    /// `commonmodule.DbPosKind`
+    fn visit_enum(&mut self, _path: &str, _enum_name: &'static str, _value: i32) {}
+
+    /// A field holding an mRID
+    fn visit_mrid(&mut self, _path: &str, _value: &str) {}
+}
+
+/// Callbacks for the leaves of a message that may change them, see Visitor
+pub trait VisitorMut {
+    /// A double or float field, or their wrapper types, floats are written
+    /// back narrowed from the double
+    fn visit_analog_mut(&mut self, _path: &str, _value: &mut f64) {}
+
+    fn visit_timestamp_mut(&mut self, _path: &str, _value: &mut Timestamp) {}
+
+    fn visit_quality_mut(&mut self, _path: &str, _value: &mut Quality) {}
+
+    fn visit_enum_mut(&mut self, _path: &str, _enum_name: &'static str, _value: &mut i32) {}
+
+    fn visit_mrid_mut(&mut self, _path: &str, _value: &mut String) {}
+}
+
+/// Walks every field of a message, calling the Visitor for its leaves
+pub trait Visit {
+    fn visit<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        self.visit_at("", visitor)
+    }
+
+    /// Walks the message with field paths starting at the given path of the
+    /// message itself
+    fn visit_at<V: Visitor + ?Sized>(&self, path: &str, visitor: &mut V);
+}
+
+/// Walks every field of a message, calling the VisitorMut for its leaves
+pub trait VisitMut {
+    fn visit_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        self.visit_mut_at("", visitor)
+    }
+
+    /// Walks the message with field paths starting at the given path of the
+    /// message itself
+    fn visit_mut_at<V: VisitorMut + ?Sized>(&mut self, path: &str, visitor: &mut V);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commonmodule::{
+        CalcMethodKind, Cmv, ConductingEquipment, EngCalcMethodKind, IdentifiedObject, MessageInfo,
+        Meter, ReadingMessageInfo, ReadingMmxu, ValidityKind, Vector, Wye,
+    };
+    use crate::metermodule::{MeterReading, MeterReadingProfile};
+
+    #[derive(Default)]
+    struct Collect {
+        analogs: Vec<(String, f64)>,
+        qualities: Vec<String>,
+        enums: Vec<(String, &'static str, i32)>,
+        mrids: Vec<String>,
+        timestamps: usize,
+    }
+
+    impl Visitor for Collect {
+        fn visit_analog(&mut self, path: &str, value: f64) {
+            self.analogs.push((path.to_string(), value));
+        }
+
+        fn visit_timestamp(&mut self, _path: &str, _value: &Timestamp) {
+            self.timestamps += 1;
+        }
+
+        fn visit_quality(&mut self, path: &str, _value: &Quality) {
+            self.qualities.push(path.to_string());
+        }
+
+        fn visit_enum(&mut self, path: &str, enum_name: &'static str, value: i32) {
+            self.enums.push((path.to_string(), enum_name, value));
+        }
+
+        fn visit_mrid(&mut self, path: &str, _value: &str) {
+            self.mrids.push(path.to_string());
+        }
+    }
+
+    struct Stamp;
+
+    impl VisitorMut for Stamp {
+        fn visit_analog_mut(&mut self, _path: &str, value: &mut f64) {
+            *value *= 2.0;
+        }
+
+        fn visit_timestamp_mut(&mut self, _path: &str, value: &mut Timestamp) {
+            value.seconds = 1_600_000_000;
+        }
+
+        fn visit_mrid_mut(&mut self, _path: &str, value: &mut String) {
+            *value = "redacted".to_string();
+        }
+    }
+
+    #[test]
+    fn test_visit() {
+        let mut msg = MeterReadingProfile {
+            meter: Some(Meter {
+                conducting_equipment: Some(ConductingEquipment {
+                    m_rid: "b3a2c5f4-1d2e-4f60-8a7b-9c0d1e2f3a4b".to_string(),
+                    ..Default::default()
+                }),
+            }),
+            meter_reading: Some(MeterReading {
+                reading_mmxu: Some(ReadingMmxu {
+                    clc_mth: Some(EngCalcMethodKind {
+                        set_val: CalcMethodKind::PClass as i32,
+                    }),
+                    w: Some(Wye {
+                        net: Some(Cmv {
+                            c_val: Some(Vector {
+                                ang: Some(90.0),
+                                mag: 12.5,
+                            }),
+                            q: Some(Quality {
+                                validity: ValidityKind::Good as i32,
+                                ..Default::default()
+                            }),
+                            t: Some(Timestamp::default()),
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            reading_message_info: Some(ReadingMessageInfo {
+                message_info: Some(MessageInfo {
+                    identified_object: Some(IdentifiedObject::default()),
+                    message_time_stamp: Some(Timestamp::default()),
+                }),
+            }),
+        };
+
+        let mut collect = Collect::default();
+        msg.visit(&mut collect);
+        assert_eq!(
+            collect.analogs,
+            vec![
+                ("meterReading.readingMMXU.W.net.cVal.ang".to_string(), 90.0),
+                ("meterReading.readingMMXU.W.net.cVal.mag".to_string(), 12.5),
+            ]
+        );
+        assert_eq!(collect.qualities, vec!["meterReading.readingMMXU.W.net.q"]);
+        // quality is a leaf, its enums are not visited
+        assert_eq!(
+            collect.enums,
+            vec![(
+                "meterReading.readingMMXU.ClcMth.setVal".to_string(),
+                "commonmodule.CalcMethodKind",
+                CalcMethodKind::PClass as i32
+            )]
+        );
+        // the unset mRID of the identified object is not visited
+        assert_eq!(collect.mrids, vec!["meter.conductingEquipment.mRID"]);
+        assert_eq!(collect.timestamps, 2);
+
+        msg.visit_mut(&mut Stamp);
+        let net = msg
+            .meter_reading
+            .as_ref()
+            .and_then(|reading| reading.reading_mmxu.as_ref())
+            .and_then(|mmxu| mmxu.w.as_ref())
+            .and_then(|w| w.net.as_ref())
+            .unwrap();
+        assert_eq!(net.c_val.as_ref().unwrap().mag, 25.0);
+        assert_eq!(net.c_val.as_ref().unwrap().ang, Some(180.0));
+        assert_eq!(net.t.as_ref().unwrap().seconds, 1_600_000_000);
+        assert_eq!(
+            msg.meter.unwrap().conducting_equipment.unwrap().m_rid,
+            "redacted"
+        );
+    }
+}
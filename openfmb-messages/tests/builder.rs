@@ -0,0 +1,58 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use openfmb_messages::commonmodule::*;
+use openfmb_messages::switchmodule::*;
+use openfmb_messages::MissingField;
+
+#[test]
+fn test_builder() {
+    let mrid = "b3a2c5f4-1d2e-4f60-8a7b-9c0d1e2f3a4b";
+    let switch_status_xswi = SwitchStatusXswi::builder()
+        .pos(
+            PhaseDps::builder()
+                .phs3(
+                    StatusDps::builder()
+                        .st_val(DbPosKind::Closed)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+        .protection_pickup(PhaseSps::default())
+        .build()
+        .unwrap();
+    assert_eq!(
+        switch_status_xswi.pos().phs3().st_val,
+        DbPosKind::Closed as i32
+    );
+
+    let builder = SwitchStatusProfile::builder().switch_status(
+        SwitchStatus::builder()
+            .switch_status_xswi(switch_status_xswi)
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(
+        builder.clone().build(),
+        Err(MissingField::new(
+            "switchmodule.SwitchStatusProfile.protectedSwitch"
+        ))
+    );
+    assert_eq!(
+        ConductingEquipment::builder().build(),
+        Err(MissingField::new("commonmodule.ConductingEquipment.mRID"))
+    );
+    let status = builder
+        .protected_switch(
+            ProtectedSwitch::builder()
+                .conducting_equipment(ConductingEquipment::builder().m_rid(mrid).build().unwrap())
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(status.protected_switch().conducting_equipment().m_rid, mrid);
+}
@@ -9,3 +9,51 @@ fn ensure_optional_or_default() {
     let msg_info = MessageInfo::default();
     msg_info.identified_object();
 }
+
+#[test]
+fn test_try_accessor() {
+    let mut msg_info = MessageInfo::default();
+    assert_eq!(
+        msg_info.try_identified_object(),
+        Err(openfmb_messages::MissingField::new(
+            "commonmodule.MessageInfo.identifiedObject"
+        ))
+    );
+    msg_info.identified_object_mut().m_rid = Some("mrid".to_string());
+    let identified_object = msg_info.try_identified_object().unwrap();
+    assert_eq!(identified_object.try_m_rid().unwrap(), "mrid");
+    assert!(identified_object.try_name().is_err());
+}
+
+#[test]
+fn test_try_and_then_accessor() {
+    let mut msg_info = MessageInfo::default();
+    assert_eq!(
+        msg_info.try_identified_object_and_then(|identified_object| identified_object.try_m_rid()),
+        Err(openfmb_messages::MissingField::new(
+            "commonmodule.MessageInfo.identifiedObject"
+        ))
+    );
+    msg_info.identified_object_mut().m_rid = Some("mrid".to_string());
+    assert_eq!(
+        msg_info.try_identified_object_and_then(|identified_object| identified_object.try_m_rid()),
+        Ok(&"mrid".to_string())
+    );
+    assert_eq!(
+        msg_info.try_identified_object_and_then(|identified_object| identified_object.try_name()),
+        Err(openfmb_messages::MissingField::new(
+            "commonmodule.MessageInfo.identifiedObject.name"
+        ))
+    );
+}
+
+#[test]
+fn test_missing_field_within() {
+    let missing = openfmb_messages::MissingField::new("switchmodule.SwitchStatusXSWI.Pos")
+        .within("switchmodule.SwitchStatus.switchStatusXSWI")
+        .within("switchmodule.SwitchStatusProfile.switchStatus");
+    assert_eq!(
+        missing.field,
+        "switchmodule.SwitchStatusProfile.switchStatus.switchStatusXSWI.Pos"
+    );
+}
@@ -0,0 +1,363 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bridging of two message buses, e.g. NATS in a substation and zenoh on the
+//! edge devices
+//!
+//! Messages are forwarded as RawMessage so every profile crosses the bridge,
+//! and decodes as a ProfileMessage on the other side, without wiring up each
+//! message type. Each bus maps topics to its own subjects, `.` separated for
+//! NATS and `/` separated paths for zenoh, so the bridge only has to drop the
+//! empty level a leading `/` leaves behind.
+//!
+//! ```ignore
+//! let bridge = Bridge::new(nats_bus, zenoh_bus)
+//!     .prefix("site-a")
+//!     .module(Module::SwitchModule)
+//!     .profile(Profile::SwitchStatusProfile);
+//! bridge.run().await?;
+//! ```
+
+use crate::prelude::*;
+use crate::topic::{prefix_levels, ROOT};
+use futures::{stream, StreamExt};
+use log::{debug, warn};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// Number of forwarded messages remembered per side while waiting for
+/// their echo
+const ECHO_CAPACITY: usize = 4096;
+
+/// How long a forwarded message waits for its echo, after which a bus is
+/// taken not to echo it and the same message coming from it is forwarded
+const ECHO_WINDOW: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Remembers the messages forwarded onto each bus so the copy the bus hands
+/// back to the bridge's own subscription is not forwarded again
+///
+/// Not every bus echoes, so a message is only remembered for ECHO_WINDOW
+/// rather than until its echo arrives.
+#[derive(Debug, Default)]
+struct Echoes {
+    left: VecDeque<(Instant, u64)>,
+    right: VecDeque<(Instant, u64)>,
+}
+
+impl Echoes {
+    /// The messages still waiting for their echo from a side
+    fn side(&mut self, side: Side, now: Instant) -> &mut VecDeque<(Instant, u64)> {
+        let echoes = match side {
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+        };
+        while let Some((published, _)) = echoes.front() {
+            if now.saturating_duration_since(*published) < ECHO_WINDOW {
+                break;
+            }
+            echoes.pop_front();
+        }
+        echoes
+    }
+
+    /// Note a message about to be published onto a side
+    fn expect(&mut self, side: Side, fingerprint: u64, now: Instant) {
+        let echoes = self.side(side, now);
+        if echoes.len() == ECHO_CAPACITY {
+            echoes.pop_front();
+        }
+        echoes.push_back((now, fingerprint));
+    }
+
+    /// True if a message received from a side is one the bridge published
+    fn is_echo(&mut self, side: Side, fingerprint: u64, now: Instant) -> bool {
+        let echoes = self.side(side, now);
+        match echoes.iter().position(|(_, fp)| *fp == fingerprint) {
+            Some(idx) => {
+                echoes.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn fingerprint(msg: &RawMessage) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    msg.topic.canonical_subject().hash(&mut hasher);
+    msg.payload.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Forwards messages in both directions between two buses
+///
+/// Only openfmb topics are bridged, those namespaced with the prefix if one
+/// is given, optionally limited to some modules and profiles. Messages
+/// echoed back by a bus shortly after the bridge published them are
+/// recognized by their topic and payload and not sent back across.
+#[derive(Debug, Clone)]
+pub struct Bridge<L, R> {
+    left: L,
+    right: R,
+    prefix: Vec<String>,
+    modules: Vec<Module>,
+    profiles: Vec<Profile>,
+}
+
+impl<L, R> Bridge<L, R>
+where
+    L: Publisher<RawMessage> + Subscriber<RawMessage>,
+    R: Publisher<RawMessage> + Subscriber<RawMessage>,
+{
+    pub fn new(left: L, right: R) -> Bridge<L, R> {
+        Bridge {
+            left,
+            right,
+            prefix: Vec::new(),
+            modules: Vec::new(),
+            profiles: Vec::new(),
+        }
+    }
+
+    /// Bridge the topics namespaced with these `.` separated prefix levels,
+    /// e.g. `site-a` for `site-a.openfmb.switchmodule...`, rather than those
+    /// without a prefix
+    pub fn prefix(mut self, prefix: &str) -> Bridge<L, R> {
+        self.prefix = prefix_levels(prefix);
+        self
+    }
+
+    /// Bridge messages of this module, may be given more than once
+    pub fn module(mut self, module: Module) -> Bridge<L, R> {
+        self.modules.push(module);
+        self
+    }
+
+    /// Bridge messages of this profile, may be given more than once
+    pub fn profile(mut self, profile: Profile) -> Bridge<L, R> {
+        self.profiles.push(profile);
+        self
+    }
+
+    /// The topic subscribed to on both buses, narrowed to the module when
+    /// only one is bridged
+    fn topic(&self) -> TopicBuf {
+        let mut levels: Vec<_> = self
+            .prefix
+            .iter()
+            .map(|lvl| TopicLevel::Exact(lvl.clone()))
+            .collect();
+        levels.push(TopicLevel::Exact(ROOT.to_string()));
+        if let [module] = self.modules.as_slice() {
+            levels.push(TopicLevel::Exact(module.as_str().to_string()));
+        }
+        TopicBuf::new(levels, true)
+    }
+
+    /// True if the module and profile levels of the topic pass the filters,
+    /// the prefix is already matched by the subscription
+    fn matches(&self, topic: &TopicBuf) -> bool {
+        let levels = match topic.root_levels() {
+            Some(levels) => levels,
+            None => return false,
+        };
+        let level = |idx: usize| match levels.get(idx) {
+            Some(TopicLevel::Exact(val)) => Some(val.as_str()),
+            _ => None,
+        };
+        let module_ok =
+            self.modules.is_empty() || self.modules.iter().any(|m| level(1) == Some(m.as_str()));
+        let profile_ok =
+            self.profiles.is_empty() || self.profiles.iter().any(|p| level(2) == Some(p.as_str()));
+        module_ok && profile_ok
+    }
+
+    /// Forward messages until both subscriptions end
+    pub async fn run(mut self) -> Result<(), SubscribeError> {
+        let topic = self.topic();
+        let left = self.left.subscribe(topic.iter()).await?;
+        let right = self.right.subscribe(topic.iter()).await?;
+        let mut received = stream::select(
+            left.map(|item| (Side::Left, item)),
+            right.map(|item| (Side::Right, item)),
+        );
+        let mut echoes = Echoes::default();
+        while let Some((from, item)) = received.next().await {
+            let mut msg = match item {
+                Ok(msg) => msg,
+                Err(err) => {
+                    warn!("bridge subscription on {:?} failed {:?}", from, err);
+                    continue;
+                }
+            };
+            msg.topic
+                .levels
+                .retain(|lvl| !matches!(lvl, TopicLevel::Exact(val) if val.is_empty()));
+            if !self.matches(&msg.topic) {
+                continue;
+            }
+            let fingerprint = fingerprint(&msg);
+            let now = Instant::now();
+            if echoes.is_echo(from, fingerprint, now) {
+                continue;
+            }
+            let (to, result) = match from {
+                Side::Left => {
+                    echoes.expect(Side::Right, fingerprint, now);
+                    (
+                        Side::Right,
+                        self.right.publish(msg.topic.clone().iter(), msg).await,
+                    )
+                }
+                Side::Right => {
+                    echoes.expect(Side::Left, fingerprint, now);
+                    (
+                        Side::Left,
+                        self.left.publish(msg.topic.clone().iter(), msg).await,
+                    )
+                }
+            };
+            match result {
+                Ok(()) => debug!("bridged message from {:?} to {:?}", from, to),
+                Err(err) => warn!("bridge publish to {:?} failed {:?}", to, err),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bridge, Echoes, Side, ECHO_WINDOW};
+    use crate::bus::LocalBus;
+    use crate::encoding::ProtobufEncoding;
+    use crate::prelude::*;
+    use futures::StreamExt;
+    use openfmb_messages::{switchmodule::SwitchStatusProfile, ProfileMessage};
+    use std::time::{Duration, Instant};
+    use uuid::Uuid;
+
+    fn topic(module: Module, profile: Profile) -> ProfileTopic {
+        let mrid = Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+        ProfileTopic::new(module, profile, mrid)
+    }
+
+    #[tokio::test]
+    async fn test_bridge() {
+        let mut left = LocalBus::<ProtobufEncoding>::new();
+        let mut right = LocalBus::<ProtobufEncoding>::new();
+        let everything = TopicBuf::from_subject("openfmb.>");
+        let mut left_sub: Subscription<ProfileMessage> =
+            left.subscribe(everything.iter()).await.unwrap();
+        let mut right_sub: Subscription<ProfileMessage> =
+            right.subscribe(everything.iter()).await.unwrap();
+
+        let bridge = Bridge::new(left.clone(), right.clone()).module(Module::SwitchModule);
+        let bridging = tokio::spawn(async move {
+            let _ = bridge.run().await;
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let status = ProfileMessage::SwitchStatusProfile(SwitchStatusProfile::default());
+        let status_topic = topic(Module::SwitchModule, Profile::SwitchStatusProfile);
+        left.publish(status_topic.iter(), status.clone())
+            .await
+            .unwrap();
+        assert_eq!(left_sub.next().await.unwrap().unwrap(), status);
+        assert_eq!(right_sub.next().await.unwrap().unwrap(), status);
+
+        // filtered out by module
+        let ess = topic(Module::EssModule, Profile::ESSStatusProfile);
+        right
+            .publish(
+                ess.iter(),
+                RawMessage::new(TopicBuf::from_topic(ess.iter()), vec![].into()),
+            )
+            .await
+            .unwrap();
+        let _ = right_sub.next().await.unwrap().unwrap();
+
+        right
+            .publish(status_topic.iter(), status.clone())
+            .await
+            .unwrap();
+        assert_eq!(right_sub.next().await.unwrap().unwrap(), status);
+        assert_eq!(left_sub.next().await.unwrap().unwrap(), status);
+
+        // nothing bounced back and forth
+        let bounced = tokio::time::timeout(Duration::from_millis(100), async {
+            futures::future::select(left_sub.next(), right_sub.next()).await
+        })
+        .await;
+        assert!(bounced.is_err());
+        bridging.abort();
+    }
+
+    #[tokio::test]
+    async fn test_bridge_prefixed() {
+        let mut left = LocalBus::<ProtobufEncoding>::new();
+        let mut right = LocalBus::<ProtobufEncoding>::new();
+        let everything = TopicBuf::from_subject("site-a.openfmb.>");
+        let mut right_sub: Subscription<ProfileMessage> =
+            right.subscribe(everything.iter()).await.unwrap();
+        let mut unprefixed_sub: Subscription<ProfileMessage> = right
+            .subscribe(TopicBuf::from_subject("openfmb.>").iter())
+            .await
+            .unwrap();
+
+        let bridge = Bridge::new(left.clone(), right.clone()).prefix("site-a");
+        let bridging = tokio::spawn(async move {
+            let _ = bridge.run().await;
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let status = ProfileMessage::SwitchStatusProfile(SwitchStatusProfile::default());
+        let status_topic = topic(Module::SwitchModule, Profile::SwitchStatusProfile);
+        // another site, not bridged
+        left.publish(
+            status_topic.clone().with_prefix("site-b").iter(),
+            status.clone(),
+        )
+        .await
+        .unwrap();
+        left.publish(status_topic.iter(), status.clone())
+            .await
+            .unwrap();
+        let prefixed = status_topic.with_prefix("site-a");
+        left.publish(prefixed.iter(), status.clone()).await.unwrap();
+        assert_eq!(right_sub.next().await.unwrap().unwrap(), status);
+
+        let bounced = tokio::time::timeout(Duration::from_millis(100), async {
+            futures::future::select(right_sub.next(), unprefixed_sub.next()).await
+        })
+        .await;
+        assert!(bounced.is_err());
+        bridging.abort();
+    }
+
+    #[test]
+    fn test_echoes() {
+        let mut echoes = Echoes::default();
+        let published = Instant::now();
+        echoes.expect(Side::Right, 1, published);
+        echoes.expect(Side::Right, 1, published);
+        assert!(!echoes.is_echo(Side::Left, 1, published));
+        assert!(echoes.is_echo(Side::Right, 1, published));
+
+        // the bus did not echo the second copy, the same message coming
+        // from it later is a message of its own
+        let later = published + ECHO_WINDOW;
+        assert!(!echoes.is_echo(Side::Right, 1, later));
+        echoes.expect(Side::Right, 1, later);
+        assert!(!echoes.is_echo(Side::Right, 1, later + ECHO_WINDOW));
+    }
+}
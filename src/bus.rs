@@ -2,9 +2,15 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::error::{PublishResult, SubscribeResult};
+use crate::error::{
+    PublishResult, RequestError, RequestResult, SubscribeError, SubscribeResult, SubscriptionError,
+};
 use crate::topic::Topic;
 use async_trait::async_trait;
+use futures::future::{self, Either};
+use futures::stream::BoxStream;
+use futures::Future;
+use std::time::Duration;
 
 /// A publisher provides the functionality needed to publish encodeable
 /// messages.
@@ -20,6 +26,84 @@ pub trait Publisher<M> {
 pub trait Subscriber<M> {
     /// Subscribe to a topic and return a stream of Messages
     async fn subscribe<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T) -> SubscribeResult<M>;
+
+    /// Subscribe to a topic as a member of a queue group, each message is
+    /// delivered to only one member of the group, e.g. to share the load
+    /// between redundant workers
+    ///
+    /// Buses without queue groups return `SubscribeError::Unsupported`
+    async fn subscribe_queue<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        _topic: T,
+        _group: &str,
+    ) -> SubscribeResult<M> {
+        Err(SubscribeError::Unsupported(
+            "queue subscriptions are not supported by this bus".to_string(),
+        ))
+    }
+}
+
+/// Requester provides the functionality to send a request to a topic and
+/// wait for a single reply, e.g. to ask a device for its current status
+/// rather than waiting for its next periodic publish.
+#[async_trait]
+pub trait Requester<Req, Resp> {
+    /// Send a request of type Req to a topic of type T and wait up to timeout
+    /// for the reply of type Resp
+    async fn request<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        req: Req,
+        timeout: Duration,
+    ) -> RequestResult<Resp>;
+}
+
+/// Responder provides the functionality to receive requests sent with a
+/// Requester on a topic and reply to them.
+#[async_trait]
+pub trait Responder<Req, Resp> {
+    /// Serve requests on a topic and return a stream of them
+    async fn serve<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+    ) -> Result<Requests<Req, Resp>, SubscribeError>;
+}
+
+/// Sends the reply to a single received request
+#[async_trait]
+pub trait Replier<M>: Send {
+    async fn reply(self: Box<Self>, msg: M) -> PublishResult<()>;
+}
+
+/// A request received by a Responder
+pub struct Request<Req, Resp> {
+    pub msg: Req,
+    replier: Box<dyn Replier<Resp>>,
+}
+
+impl<Req, Resp> Request<Req, Resp> {
+    pub fn new(msg: Req, replier: Box<dyn Replier<Resp>>) -> Request<Req, Resp> {
+        Request { msg, replier }
+    }
+
+    /// Reply to the requester, a request may only be replied to once
+    pub async fn reply(self, msg: Resp) -> PublishResult<()> {
+        self.replier.reply(msg).await
+    }
+}
+
+/// Type alias for the stream of requests given by a Responder
+pub type Requests<Req, Resp> = BoxStream<'static, Result<Request<Req, Resp>, SubscriptionError>>;
+
+/// Wait for a future for at most the given time
+pub(crate) async fn with_timeout<F: Future + Unpin>(
+    fut: F,
+    timeout: Duration,
+) -> RequestResult<F::Output> {
+    match future::select(fut, futures_timer::Delay::new(timeout)).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(_) => Err(RequestError::Timeout(timeout)),
+    }
 }
 
 /// A message bus provides functionality to publish messages to a topic
@@ -32,6 +116,12 @@ pub trait Closable<M> {
     fn close(&self);
 }
 
+mod layer;
+pub use self::layer::{Layer, LayeredBus, LoggingLayer, PrefixLayer};
+
+mod local;
+pub use self::local::LocalBus;
+
 #[cfg(feature = "nats-sync")]
 mod nats;
 #[cfg(feature = "nats-sync")]
@@ -40,9 +130,34 @@ pub use self::nats::NatsBus;
 #[cfg(feature = "nats-async")]
 mod nats_async;
 #[cfg(feature = "nats-async")]
-pub use self::nats_async::NatsAsyncBus;
+pub use self::nats_async::{DeliverFrom, NatsAsyncBus};
+
+#[cfg(feature = "mqtt")]
+mod mqtt;
+#[cfg(feature = "mqtt")]
+pub use self::mqtt::{MqttBus, MqttPublishOptions, QoS};
+
+#[cfg(feature = "compression")]
+mod compressed;
+#[cfg(feature = "compression")]
+pub use self::compressed::{CompressedBus, Compression, CompressionError, MAX_DECOMPRESSED_SIZE};
+
+#[cfg(feature = "encryption")]
+mod encrypted;
+#[cfg(feature = "encryption")]
+pub use self::encrypted::{EncryptedBus, EncryptionError, EncryptionKeys};
+
+#[cfg(feature = "metrics")]
+mod metered;
+#[cfg(feature = "metrics")]
+pub use self::metered::{MeteredBus, Metrics};
+
+#[cfg(feature = "signing")]
+mod signed;
+#[cfg(feature = "signing")]
+pub use self::signed::{Keyring, SignedBus, SigningKey, VerifyingKey};
 
 #[cfg(feature = "zenoh")]
 mod zenoh;
 #[cfg(feature = "zenoh")]
-pub use self::zenoh::ZenohBus;
+pub use self::zenoh::{ZenohBus, ZenohConfig};
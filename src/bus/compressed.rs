@@ -0,0 +1,305 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::prelude::*;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use std::fmt;
+use std::io::Read;
+use std::marker::PhantomData;
+
+use super::Closable;
+
+/// Largest payload a message is decompressed to, anything larger is
+/// rejected rather than filling memory
+pub const MAX_DECOMPRESSED_SIZE: usize = 16 * 1024 * 1024;
+
+/// The first byte of every compressed payload tells how the rest is stored
+const STORED: u8 = 0;
+const ZSTD: u8 = 1;
+const LZ4: u8 = 2;
+
+/// Algorithms messages may be compressed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Zstandard at the given level, 0 picks the zstd default
+    Zstd(i32),
+    /// LZ4, faster than zstd but compressing less
+    Lz4,
+}
+
+/// Reasons a message could not be compressed or decompressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompressionError {
+    /// The received message was not compressed
+    NotCompressed,
+    /// The message was compressed with an algorithm that is not known
+    UnknownAlgorithm(u8),
+    /// The message decompresses to more than MAX_DECOMPRESSED_SIZE
+    TooLarge,
+    CompressFailed(String),
+    DecompressFailed(String),
+}
+
+impl fmt::Display for CompressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for CompressionError {}
+
+impl Compression {
+    /// Payloads compression does not make smaller are sent as they are
+    fn compress(&self, payload: &[u8]) -> Result<Vec<u8>, CompressionError> {
+        let (algorithm, compressed) = match *self {
+            Compression::Zstd(level) => (
+                ZSTD,
+                zstd::bulk::compress(payload, level)
+                    .map_err(|err| CompressionError::CompressFailed(err.to_string()))?,
+            ),
+            Compression::Lz4 => (LZ4, lz4_flex::block::compress_prepend_size(payload)),
+        };
+        let (algorithm, data) = if compressed.len() < payload.len() {
+            (algorithm, &compressed[..])
+        } else {
+            (STORED, payload)
+        };
+        let mut buf = Vec::with_capacity(1 + data.len());
+        buf.push(algorithm);
+        buf.extend_from_slice(data);
+        Ok(buf)
+    }
+}
+
+/// Decompress with whichever algorithm the payload was compressed with
+fn decompress(payload: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    let (algorithm, data) = payload
+        .split_first()
+        .ok_or(CompressionError::NotCompressed)?;
+    match *algorithm {
+        STORED => Ok(data.to_vec()),
+        ZSTD => {
+            let mut decompressed = Vec::new();
+            zstd::stream::read::Decoder::new(data)
+                .and_then(|decoder| {
+                    decoder
+                        .take(MAX_DECOMPRESSED_SIZE as u64 + 1)
+                        .read_to_end(&mut decompressed)
+                })
+                .map_err(|err| CompressionError::DecompressFailed(err.to_string()))?;
+            if decompressed.len() > MAX_DECOMPRESSED_SIZE {
+                return Err(CompressionError::TooLarge);
+            }
+            Ok(decompressed)
+        }
+        LZ4 => {
+            let size = data
+                .get(..4)
+                .map(|size| u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize)
+                .ok_or_else(|| CompressionError::DecompressFailed("missing size".to_string()))?;
+            if size > MAX_DECOMPRESSED_SIZE {
+                return Err(CompressionError::TooLarge);
+            }
+            lz4_flex::block::decompress_size_prepended(data)
+                .map_err(|err| CompressionError::DecompressFailed(err.to_string()))
+        }
+        unknown => Err(CompressionError::UnknownAlgorithm(unknown)),
+    }
+}
+
+/// A Message Bus compressing every published message, e.g. for slow links
+///
+/// Messages are encoded with E and compressed, which the wrapped bus passes
+/// on as a RawMessage. Subscribers decompress whichever algorithm a message
+/// was compressed with, so publishers may pick theirs independently.
+/// Messages that fail to decompress show up as a
+/// `SubscriptionError::DecodeError` holding a `CompressionError`.
+#[derive(Debug, Clone)]
+pub struct CompressedBus<B, E: MessageEncoding> {
+    inner: B,
+    compression: Compression,
+    encoding: PhantomData<E>,
+}
+
+impl<B, E: MessageEncoding> CompressedBus<B, E> {
+    pub fn new(inner: B, compression: Compression) -> CompressedBus<B, E> {
+        CompressedBus {
+            inner,
+            compression,
+            encoding: PhantomData,
+        }
+    }
+}
+
+fn decompressed<E, M>(subscription: Subscription<RawMessage>) -> Subscription<M>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    Box::pin(subscription.map(|raw| {
+        let raw = raw?;
+        let payload = decompress(&raw.payload)
+            .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))?;
+        let data: &[u8] = &payload;
+        M::decode_with_headers(raw.topic.iter(), &raw.headers, data)
+            .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
+    }))
+}
+
+#[async_trait]
+impl<B, E, M> Subscriber<M> for CompressedBus<B, E>
+where
+    B: Subscriber<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn subscribe<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T) -> SubscribeResult<M> {
+        let subscription = self.inner.subscribe(topic).await?;
+        Ok(decompressed::<E, M>(subscription))
+    }
+
+    async fn subscribe_queue<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        group: &str,
+    ) -> SubscribeResult<M> {
+        let subscription = self.inner.subscribe_queue(topic, group).await?;
+        Ok(decompressed::<E, M>(subscription))
+    }
+}
+
+#[async_trait]
+impl<B, E, M> Publisher<M> for CompressedBus<B, E>
+where
+    B: Publisher<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn publish<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T, msg: M) -> PublishResult<()> {
+        let topic = TopicBuf::from_topic(topic);
+        let mut payload = Vec::with_capacity(512);
+        msg.encode(&mut payload)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        let buf = self
+            .compression
+            .compress(&payload)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        self.inner
+            .publish(
+                topic.iter(),
+                RawMessage::new(topic.clone(), Bytes::from(buf))
+                    .with_headers(msg.headers().cloned().unwrap_or_default()),
+            )
+            .await
+    }
+}
+
+/// A compressed bus implements the MessageBus trait
+impl<B, E, M> MessageBus<M> for CompressedBus<B, E>
+where
+    B: MessageBus<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+}
+
+impl<B, E, C> Closable<C> for CompressedBus<B, E>
+where
+    B: Closable<C>,
+    E: MessageEncoding,
+{
+    fn close(&self) {
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decompress, CompressedBus, Compression, CompressionError, LZ4, ZSTD};
+    use crate::bus::LocalBus;
+    use crate::encoding::ProtobufEncoding;
+    use crate::prelude::*;
+    use futures::StreamExt;
+    use openfmb_messages::{
+        commonmodule::{IdentifiedObject, MessageInfo, StatusMessageInfo},
+        switchmodule::SwitchStatusProfile,
+        ProfileMessage,
+    };
+    use uuid::Uuid;
+
+    fn topic() -> ProfileTopic {
+        let mrid = Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+        ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid)
+    }
+
+    fn status() -> SwitchStatusProfile {
+        SwitchStatusProfile {
+            status_message_info: Some(StatusMessageInfo {
+                message_info: Some(MessageInfo {
+                    identified_object: Some(IdentifiedObject {
+                        description: Some("a switch ".repeat(50)),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compress_decompress() {
+        let mut local = LocalBus::<ProtobufEncoding>::new();
+        let mut raw: Subscription<RawMessage> = local.subscribe(topic().iter()).await.unwrap();
+        let mut zstd =
+            CompressedBus::<_, ProtobufEncoding>::new(local.clone(), Compression::Zstd(0));
+        let mut lz4 = CompressedBus::<_, ProtobufEncoding>::new(local, Compression::Lz4);
+        let mut sub: Subscription<ProfileMessage> = lz4.subscribe(topic().iter()).await.unwrap();
+        let mut uncompressed = Vec::new();
+        Message::<ProtobufEncoding>::encode(&status(), &mut uncompressed).unwrap();
+
+        zstd.publish(topic().iter(), status()).await.unwrap();
+        let payload = raw.next().await.unwrap().unwrap().payload;
+        assert_eq!(payload[0], ZSTD);
+        assert!(payload.len() < uncompressed.len());
+        assert_eq!(
+            sub.next().await.unwrap().unwrap(),
+            ProfileMessage::SwitchStatusProfile(status())
+        );
+
+        lz4.publish(
+            topic().iter(),
+            ProfileMessage::SwitchStatusProfile(status()),
+        )
+        .await
+        .unwrap();
+        let payload = raw.next().await.unwrap().unwrap().payload;
+        assert_eq!(payload[0], LZ4);
+        assert!(payload.len() < uncompressed.len());
+        assert_eq!(
+            sub.next().await.unwrap().unwrap(),
+            ProfileMessage::SwitchStatusProfile(status())
+        );
+    }
+
+    #[test]
+    fn test_incompressible_and_invalid() {
+        let payload = Compression::Zstd(0).compress(b"tiny").unwrap();
+        assert_eq!(payload, b"\0tiny");
+        assert_eq!(decompress(&payload).unwrap(), b"tiny");
+
+        assert_eq!(decompress(b""), Err(CompressionError::NotCompressed));
+        assert_eq!(
+            decompress(b"\x07abc"),
+            Err(CompressionError::UnknownAlgorithm(7))
+        );
+        // an lz4 payload claiming to decompress to 4 GiB
+        assert_eq!(
+            decompress(&[LZ4, 0xff, 0xff, 0xff, 0xff, 0]),
+            Err(CompressionError::TooLarge)
+        );
+    }
+}
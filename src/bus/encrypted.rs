@@ -0,0 +1,500 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::prelude::*;
+use crate::topic::ROOT;
+use async_trait::async_trait;
+use bytes::Bytes;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+use super::Closable;
+
+/// The envelope put on the wire around every encrypted message
+#[derive(Clone, PartialEq, prost::Message)]
+struct EncryptedEnvelope {
+    #[prost(string, tag = "1")]
+    key_id: String,
+    #[prost(bytes = "vec", tag = "2")]
+    nonce: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    ciphertext: Vec<u8>,
+}
+
+/// Reasons a message could not be encrypted or decrypted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncryptionError {
+    /// The received message was not encrypted
+    NotEncrypted,
+    /// No key is set to encrypt messages on the topic
+    NoKey(String),
+    /// The message was encrypted with a key that is not known
+    UnknownKey(String),
+    /// The message could not be encrypted with the key
+    EncryptFailed(String),
+    /// The message could not be decrypted with the key, it was tampered
+    /// with, moved to another topic or encrypted with a different key
+    DecryptFailed(String),
+}
+
+impl fmt::Display for EncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for EncryptionError {}
+
+/// Keys messages are encrypted and decrypted with, each known by a key id
+///
+/// Which key encrypts a message is picked by topic, the longest matching
+/// topic prefix wins. Decryption uses the key id sent along with the message
+/// so keys can be rotated by adding the new key, encrypting with it and
+/// removing the old key once nothing encrypted with it is in flight anymore.
+#[derive(Clone, Default)]
+pub struct EncryptionKeys {
+    keys: HashMap<String, XChaCha20Poly1305>,
+    encrypt_with: Vec<(TopicBuf, String)>,
+}
+
+impl EncryptionKeys {
+    pub fn new() -> EncryptionKeys {
+        EncryptionKeys::default()
+    }
+
+    /// Add a 256 bit key that messages may be encrypted or decrypted with
+    pub fn add_key<S: Into<String>>(mut self, key_id: S, key: &[u8; 32]) -> EncryptionKeys {
+        self.keys
+            .insert(key_id.into(), XChaCha20Poly1305::new(key.into()));
+        self
+    }
+
+    /// Forget a key, messages encrypted with it can no longer be decrypted
+    pub fn remove_key(mut self, key_id: &str) -> EncryptionKeys {
+        self.keys.remove(key_id);
+        self
+    }
+
+    /// Encrypt messages on every topic with the given key
+    pub fn encrypt_with<S: Into<String>>(self, key_id: S) -> EncryptionKeys {
+        self.encrypt_topic_with(TopicBuf::new(vec![], true).iter(), key_id)
+    }
+
+    /// Encrypt messages of a module with the given key, whatever the prefix
+    /// of their topic
+    pub fn encrypt_module_with<S: Into<String>>(self, module: Module, key_id: S) -> EncryptionKeys {
+        let topic = TopicBuf::new(
+            vec![
+                TopicLevel::Exact(ROOT.to_string()),
+                TopicLevel::Exact(module.as_str().to_string()),
+            ],
+            true,
+        );
+        self.encrypt_topic_with(topic.iter(), key_id)
+    }
+
+    /// Encrypt messages on topics starting with the given levels with the
+    /// given key, wildcard levels match any value
+    ///
+    /// Levels starting at the root are matched against the topic from its
+    /// root, so they apply whatever the prefix of the topic.
+    pub fn encrypt_topic_with<S, T, K>(mut self, topic: T, key_id: K) -> EncryptionKeys
+    where
+        S: AsRef<str>,
+        T: Topic<S>,
+        K: Into<String>,
+    {
+        let topic = TopicBuf::from_topic(topic);
+        let key_id = key_id.into();
+        self.encrypt_with
+            .retain(|(prefix, _)| prefix.levels != topic.levels);
+        self.encrypt_with.push((topic, key_id));
+        self
+    }
+
+    fn key_for(&self, topic: &TopicBuf) -> Option<&str> {
+        self.encrypt_with
+            .iter()
+            .filter(|(prefix, _)| {
+                let levels = match prefix.levels.first() {
+                    Some(TopicLevel::Exact(root)) if root == ROOT => match topic.root_levels() {
+                        Some(levels) => levels,
+                        None => return false,
+                    },
+                    _ => &topic.levels,
+                };
+                prefix.levels.len() <= levels.len()
+                    && prefix
+                        .levels
+                        .iter()
+                        .zip(levels.iter())
+                        .all(|(lvl, val)| match lvl {
+                            TopicLevel::WildCard => true,
+                            exact => exact == val,
+                        })
+            })
+            .max_by_key(|(prefix, _)| prefix.levels.len())
+            .map(|(_, key_id)| key_id.as_str())
+    }
+
+    fn encrypt(
+        &self,
+        topic: &TopicBuf,
+        payload: &[u8],
+    ) -> Result<EncryptedEnvelope, EncryptionError> {
+        let subject = topic.canonical_subject();
+        let key_id = self
+            .key_for(topic)
+            .ok_or_else(|| EncryptionError::NoKey(subject.clone()))?;
+        let cipher = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| EncryptionError::UnknownKey(key_id.to_string()))?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let aad = associated_data(&subject, key_id);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: payload,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_err| EncryptionError::EncryptFailed(key_id.to_string()))?;
+        Ok(EncryptedEnvelope {
+            key_id: key_id.to_string(),
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    fn decrypt(
+        &self,
+        topic: &TopicBuf,
+        envelope: &EncryptedEnvelope,
+    ) -> Result<Vec<u8>, EncryptionError> {
+        if envelope.key_id.is_empty() || envelope.nonce.len() != 24 {
+            return Err(EncryptionError::NotEncrypted);
+        }
+        let cipher = self
+            .keys
+            .get(&envelope.key_id)
+            .ok_or_else(|| EncryptionError::UnknownKey(envelope.key_id.clone()))?;
+        let aad = associated_data(&topic.canonical_subject(), &envelope.key_id);
+        cipher
+            .decrypt(
+                XNonce::from_slice(&envelope.nonce),
+                Payload {
+                    msg: &envelope.ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_err| EncryptionError::DecryptFailed(envelope.key_id.clone()))
+    }
+}
+
+/// Key material is left out
+impl fmt::Debug for EncryptionKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionKeys")
+            .field("keys", &self.keys.keys().collect::<Vec<_>>())
+            .field("encrypt_with", &self.encrypt_with)
+            .finish()
+    }
+}
+
+/// The topic and key id are authenticated along with the payload so an
+/// encrypted message can not be moved onto the topic of another device
+fn associated_data(subject: &str, key_id: &str) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(subject.len() + 1 + key_id.len());
+    aad.extend_from_slice(subject.as_bytes());
+    aad.push(0);
+    aad.extend_from_slice(key_id.as_bytes());
+    aad
+}
+
+/// A Message Bus encrypting every published message with ChaCha20-Poly1305
+///
+/// Messages are encoded with E and encrypted into an envelope holding the
+/// key id and nonce, which the wrapped bus passes on as a RawMessage.
+/// Messages that fail to decrypt show up as a `SubscriptionError::DecodeError`
+/// holding an `EncryptionError`. Cloned buses share their keys, so keys set
+/// with `set_keys` take effect for all of them.
+#[derive(Debug, Clone)]
+pub struct EncryptedBus<B, E: MessageEncoding> {
+    inner: B,
+    keys: Arc<RwLock<EncryptionKeys>>,
+    encoding: PhantomData<E>,
+}
+
+impl<B, E: MessageEncoding> EncryptedBus<B, E> {
+    pub fn new(inner: B, keys: EncryptionKeys) -> EncryptedBus<B, E> {
+        EncryptedBus {
+            inner,
+            keys: Arc::new(RwLock::new(keys)),
+            encoding: PhantomData,
+        }
+    }
+
+    /// Replace the keys, e.g. to rotate them
+    pub fn set_keys(&self, keys: EncryptionKeys) {
+        *self
+            .keys
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = keys;
+    }
+}
+
+fn decode_error(err: EncryptionError) -> SubscriptionError {
+    SubscriptionError::DecodeError(Box::new(err))
+}
+
+#[async_trait]
+impl<B, E, M> Subscriber<M> for EncryptedBus<B, E>
+where
+    B: Subscriber<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn subscribe<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T) -> SubscribeResult<M> {
+        let subscription = self.inner.subscribe(topic).await?;
+        Ok(self.decrypt(subscription))
+    }
+
+    async fn subscribe_queue<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        group: &str,
+    ) -> SubscribeResult<M> {
+        let subscription = self.inner.subscribe_queue(topic, group).await?;
+        Ok(self.decrypt(subscription))
+    }
+}
+
+impl<B, E> EncryptedBus<B, E>
+where
+    E: 'static + MessageEncoding + Send,
+{
+    fn decrypt<M>(&self, subscription: Subscription<RawMessage>) -> Subscription<M>
+    where
+        M: 'static + Message<E> + Send,
+    {
+        let keys = self.keys.clone();
+        Box::pin(subscription.map(move |raw| {
+            let raw = raw?;
+            let envelope = <EncryptedEnvelope as prost::Message>::decode(raw.payload)
+                .map_err(|_err| decode_error(EncryptionError::NotEncrypted))?;
+            let payload = keys
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .decrypt(&raw.topic, &envelope)
+                .map_err(decode_error)?;
+            let data: &[u8] = &payload;
+            M::decode_with_headers(raw.topic.iter(), &raw.headers, data)
+                .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
+        }))
+    }
+}
+
+#[async_trait]
+impl<B, E, M> Publisher<M> for EncryptedBus<B, E>
+where
+    B: Publisher<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn publish<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T, msg: M) -> PublishResult<()> {
+        let topic = TopicBuf::from_topic(topic);
+        let mut payload = Vec::with_capacity(512);
+        msg.encode(&mut payload)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        let envelope = self
+            .keys
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .encrypt(&topic, &payload)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        let mut buf = Vec::with_capacity(prost::Message::encoded_len(&envelope));
+        prost::Message::encode(&envelope, &mut buf)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        self.inner
+            .publish(
+                topic.iter(),
+                RawMessage::new(topic.clone(), Bytes::from(buf))
+                    .with_headers(msg.headers().cloned().unwrap_or_default()),
+            )
+            .await
+    }
+}
+
+/// An encrypted bus implements the MessageBus trait
+impl<B, E, M> MessageBus<M> for EncryptedBus<B, E>
+where
+    B: MessageBus<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+}
+
+impl<B, E, C> Closable<C> for EncryptedBus<B, E>
+where
+    B: Closable<C>,
+    E: MessageEncoding,
+{
+    fn close(&self) {
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EncryptedBus, EncryptionError, EncryptionKeys};
+    use crate::bus::LocalBus;
+    use crate::encoding::ProtobufEncoding;
+    use crate::prelude::*;
+    use futures::StreamExt;
+    use openfmb_messages::{
+        essmodule::EssControlProfile, switchmodule::SwitchStatusProfile, ProfileMessage,
+    };
+    use uuid::Uuid;
+
+    fn mrid() -> Uuid {
+        Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap()
+    }
+
+    fn decryption_error<T: std::fmt::Debug>(
+        res: Option<Result<T, SubscriptionError>>,
+    ) -> EncryptionError {
+        match res {
+            Some(Err(SubscriptionError::DecodeError(err))) => err
+                .downcast_ref::<EncryptionError>()
+                .expect("encryption error")
+                .clone(),
+            res => panic!("expected a decode error, got {:?}", res),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt() {
+        let topic = ProfileTopic::new(Module::EssModule, Profile::ESSControlProfile, mrid());
+        let keys = EncryptionKeys::new()
+            .add_key("all", &[1; 32])
+            .add_key("ess", &[2; 32])
+            .encrypt_with("all")
+            .encrypt_module_with(Module::EssModule, "ess");
+        let local = LocalBus::<ProtobufEncoding>::new();
+        let mut bus = EncryptedBus::<_, ProtobufEncoding>::new(local.clone(), keys);
+        let mut sub: Subscription<ProfileMessage> = bus.subscribe(topic.iter()).await.unwrap();
+
+        let msg = ProfileMessage::ESSControlProfile(EssControlProfile::default());
+        bus.publish(topic.iter(), msg.clone()).await.unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), msg);
+
+        // a subscriber only holding the key for other modules can not read it
+        let mut other = EncryptedBus::<_, ProtobufEncoding>::new(
+            local,
+            EncryptionKeys::new().add_key("all", &[1; 32]),
+        );
+        let mut other_sub: Subscription<ProfileMessage> =
+            other.subscribe(topic.iter()).await.unwrap();
+        bus.publish(topic.iter(), msg.clone()).await.unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), msg);
+        assert_eq!(
+            decryption_error(other_sub.next().await),
+            EncryptionError::UnknownKey("ess".to_string())
+        );
+
+        // the module key applies to a prefixed topic as well
+        let prefixed = topic.with_prefix("site-a");
+        let mut prefixed_sub: Subscription<ProfileMessage> =
+            other.subscribe(prefixed.iter()).await.unwrap();
+        bus.publish(prefixed.iter(), msg.clone()).await.unwrap();
+        assert_eq!(
+            decryption_error(prefixed_sub.next().await),
+            EncryptionError::UnknownKey("ess".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_key_rotation() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let old = EncryptionKeys::new()
+            .add_key("k1", &[1; 32])
+            .encrypt_with("k1");
+        let mut publisher = EncryptedBus::<_, ProtobufEncoding>::new(
+            LocalBus::<ProtobufEncoding>::new(),
+            old.clone(),
+        );
+        let mut subscriber = publisher.clone();
+        let mut sub: Subscription<SwitchStatusProfile> =
+            subscriber.subscribe(topic.iter()).await.unwrap();
+
+        let status = SwitchStatusProfile::default();
+        publisher
+            .publish(topic.iter(), status.clone())
+            .await
+            .unwrap();
+        publisher.set_keys(
+            old.add_key("k2", &[2; 32])
+                .encrypt_with("k2")
+                .remove_key("k1"),
+        );
+        publisher
+            .publish(topic.iter(), status.clone())
+            .await
+            .unwrap();
+
+        // the first message was still in flight when k1 was removed
+        assert_eq!(
+            decryption_error(sub.next().await),
+            EncryptionError::UnknownKey("k1".to_string())
+        );
+        assert_eq!(sub.next().await.unwrap().unwrap(), status);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_and_plain() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let mut local = LocalBus::<ProtobufEncoding>::new();
+        let keys = EncryptionKeys::new().add_key("k", &[1; 32]);
+        let mut bus = EncryptedBus::<_, ProtobufEncoding>::new(local.clone(), keys.clone());
+        let mut sub: Subscription<SwitchStatusProfile> = bus.subscribe(topic.iter()).await.unwrap();
+
+        // no key set for the topic
+        assert!(bus
+            .publish(topic.iter(), SwitchStatusProfile::default())
+            .await
+            .is_err());
+
+        local
+            .publish(topic.iter(), SwitchStatusProfile::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            decryption_error(sub.next().await),
+            EncryptionError::NotEncrypted
+        );
+
+        // encrypted for another topic and then republished on this one
+        let other = ProfileTopic::new(Module::SwitchModule, Profile::SwitchReadingProfile, mrid());
+        let mut encrypting =
+            EncryptedBus::<_, ProtobufEncoding>::new(local.clone(), keys.encrypt_with("k"));
+        let mut raw: Subscription<RawMessage> = local.subscribe(other.iter()).await.unwrap();
+        encrypting
+            .publish(other.iter(), SwitchStatusProfile::default())
+            .await
+            .unwrap();
+        let moved = raw.next().await.unwrap().unwrap();
+        local.publish(topic.iter(), moved).await.unwrap();
+        assert_eq!(
+            decryption_error(sub.next().await),
+            EncryptionError::DecryptFailed("k".to_string())
+        );
+    }
+}
@@ -0,0 +1,335 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::prelude::*;
+use crate::topic::prefix_levels;
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::{debug, warn};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use super::Closable;
+
+/// A layer sees every publish, subscribe and received message going through
+/// a LayeredBus, e.g. to log, count, validate or rewrite them.
+///
+/// Every function defaults to passing things through untouched so a layer
+/// only implements the ones it is interested in.
+pub trait Layer<M>: Send + Sync {
+    /// Called before a message is published, the topic and message may be
+    /// modified and an error stops the publish
+    fn on_publish(&self, _topic: &mut TopicBuf, _msg: &mut M) -> PublishResult<()> {
+        Ok(())
+    }
+
+    /// Called with the result of every publish that reached the wrapped bus
+    fn on_published(&self, _topic: &TopicBuf, _result: &PublishResult<()>) {}
+
+    /// Called before subscribing, the topic may be modified and an error
+    /// stops the subscribe
+    fn on_subscribe(&self, _topic: &mut TopicBuf) -> Result<(), SubscribeError> {
+        Ok(())
+    }
+
+    /// Called for every item received on a subscription made through the
+    /// bus along with the topic that was subscribed to
+    fn on_item(
+        &self,
+        _topic: &TopicBuf,
+        item: Result<M, SubscriptionError>,
+    ) -> Result<M, SubscriptionError> {
+        item
+    }
+}
+
+/// A Message Bus wrapping another with a Layer
+///
+/// Layers compose by wrapping a LayeredBus again, the outermost layer sees
+/// publishes first and received messages last.
+///
+/// ```ignore
+/// let bus = LayeredBus::new(bus, LoggingLayer).layer(ValidationLayer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LayeredBus<B, L> {
+    inner: B,
+    layer: Arc<L>,
+}
+
+impl<B, L> LayeredBus<B, L> {
+    pub fn new(inner: B, layer: L) -> LayeredBus<B, L> {
+        LayeredBus {
+            inner,
+            layer: Arc::new(layer),
+        }
+    }
+
+    /// Wrap this bus with another layer
+    pub fn layer<L2>(self, layer: L2) -> LayeredBus<LayeredBus<B, L>, L2> {
+        LayeredBus::new(self, layer)
+    }
+
+    /// The wrapped bus
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    /// The wrapped bus
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<B, L, M> Subscriber<M> for LayeredBus<B, L>
+where
+    B: Subscriber<M> + Send,
+    L: 'static + Layer<M>,
+    M: 'static + Send,
+{
+    async fn subscribe<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T) -> SubscribeResult<M> {
+        let mut topic = TopicBuf::from_topic(topic);
+        self.layer.on_subscribe(&mut topic)?;
+        let subscription = self.inner.subscribe(topic.iter()).await?;
+        let layer = self.layer.clone();
+        Ok(Box::pin(
+            subscription.map(move |item| layer.on_item(&topic, item)),
+        ))
+    }
+
+    /// Queue subscriptions go through on_subscribe and on_item the same as
+    /// any other subscription
+    async fn subscribe_queue<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        group: &str,
+    ) -> SubscribeResult<M> {
+        let mut topic = TopicBuf::from_topic(topic);
+        self.layer.on_subscribe(&mut topic)?;
+        let subscription = self.inner.subscribe_queue(topic.iter(), group).await?;
+        let layer = self.layer.clone();
+        Ok(Box::pin(
+            subscription.map(move |item| layer.on_item(&topic, item)),
+        ))
+    }
+}
+
+#[async_trait]
+impl<B, L, M> Publisher<M> for LayeredBus<B, L>
+where
+    B: Publisher<M> + Send,
+    L: 'static + Layer<M>,
+    M: 'static + Send,
+{
+    async fn publish<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        mut msg: M,
+    ) -> PublishResult<()> {
+        let mut topic = TopicBuf::from_topic(topic);
+        self.layer.on_publish(&mut topic, &mut msg)?;
+        let result = self.inner.publish(topic.iter(), msg).await;
+        self.layer.on_published(&topic, &result);
+        result
+    }
+}
+
+/// A layered bus implements the MessageBus trait
+impl<B, L, M> MessageBus<M> for LayeredBus<B, L>
+where
+    B: MessageBus<M> + Send,
+    L: 'static + Layer<M>,
+    M: 'static + Send,
+{
+}
+
+impl<B, L, M> Closable<M> for LayeredBus<B, L>
+where
+    B: Closable<M>,
+{
+    fn close(&self) {
+        self.inner.close();
+    }
+}
+
+/// Logs every publish, subscribe and received message
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggingLayer;
+
+impl<M: Debug> Layer<M> for LoggingLayer {
+    fn on_publish(&self, topic: &mut TopicBuf, msg: &mut M) -> PublishResult<()> {
+        debug!("publishing to {} {:?}", topic, msg);
+        Ok(())
+    }
+
+    fn on_published(&self, topic: &TopicBuf, result: &PublishResult<()>) {
+        if let Err(err) = result {
+            warn!("publish to {} failed {:?}", topic, err);
+        }
+    }
+
+    fn on_subscribe(&self, topic: &mut TopicBuf) -> Result<(), SubscribeError> {
+        debug!("subscribing to {}", topic);
+        Ok(())
+    }
+
+    fn on_item(
+        &self,
+        topic: &TopicBuf,
+        item: Result<M, SubscriptionError>,
+    ) -> Result<M, SubscriptionError> {
+        match &item {
+            Ok(msg) => debug!("received on {} {:?}", topic, msg),
+            Err(err) => warn!("subscription to {} failed {:?}", topic, err),
+        }
+        item
+    }
+}
+
+/// Namespaces every topic with a prefix, e.g. a site sharing a NATS cluster
+/// with other sites
+///
+/// Levels before the `openfmb` root of a topic are replaced with the prefix,
+/// so messages forwarded from another prefixed bus land under this one's.
+/// Received messages keep the prefixed topic, which the encodings skip
+/// over when decoding.
+///
+/// ```ignore
+/// let bus = LayeredBus::new(bus, PrefixLayer::new("site-a"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PrefixLayer {
+    prefix: Vec<String>,
+}
+
+impl PrefixLayer {
+    /// A layer adding the `.` separated prefix levels
+    pub fn new(prefix: &str) -> PrefixLayer {
+        PrefixLayer {
+            prefix: prefix_levels(prefix),
+        }
+    }
+
+    /// The prefix levels
+    pub fn prefix(&self) -> &[String] {
+        &self.prefix
+    }
+}
+
+impl<M> Layer<M> for PrefixLayer {
+    fn on_publish(&self, topic: &mut TopicBuf, _msg: &mut M) -> PublishResult<()> {
+        topic.set_prefix(&self.prefix);
+        Ok(())
+    }
+
+    fn on_subscribe(&self, topic: &mut TopicBuf) -> Result<(), SubscribeError> {
+        topic.set_prefix(&self.prefix);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layer, LayeredBus, LoggingLayer, PrefixLayer};
+    use crate::bus::{Closable, LocalBus};
+    use crate::encoding::ProtobufEncoding;
+    use crate::prelude::*;
+    use futures::StreamExt;
+    use openfmb_messages::{switchmodule::SwitchStatusProfile, ProfileMessage};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    /// Moves every topic below a site level and counts the messages seen
+    #[derive(Default)]
+    struct SiteLayer {
+        published: AtomicUsize,
+        received: AtomicUsize,
+    }
+
+    impl SiteLayer {
+        fn rewrite(topic: &mut TopicBuf) {
+            topic
+                .levels
+                .insert(0, TopicLevel::Exact("site-a".to_string()));
+        }
+    }
+
+    impl<M> Layer<M> for Arc<SiteLayer> {
+        fn on_publish(&self, topic: &mut TopicBuf, _msg: &mut M) -> PublishResult<()> {
+            SiteLayer::rewrite(topic);
+            self.published.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn on_subscribe(&self, topic: &mut TopicBuf) -> Result<(), SubscribeError> {
+            SiteLayer::rewrite(topic);
+            Ok(())
+        }
+
+        fn on_item(
+            &self,
+            _topic: &TopicBuf,
+            item: Result<M, SubscriptionError>,
+        ) -> Result<M, SubscriptionError> {
+            self.received.fetch_add(1, Ordering::SeqCst);
+            item
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layered_bus() {
+        let mrid = Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid);
+        let local = LocalBus::<ProtobufEncoding>::new();
+        let site = Arc::new(SiteLayer::default());
+        let mut bus = LayeredBus::new(local.clone(), site.clone()).layer(LoggingLayer);
+
+        let mut sub: Subscription<SwitchStatusProfile> = bus.subscribe(topic.iter()).await.unwrap();
+        let mut raw: Subscription<SwitchStatusProfile> = local
+            .clone()
+            .subscribe(TopicBuf::new(vec![TopicLevel::Exact("site-a".to_string())], true).iter())
+            .await
+            .unwrap();
+
+        let status = SwitchStatusProfile::default();
+        bus.publish(topic.iter(), status.clone()).await.unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), status);
+        assert_eq!(raw.next().await.unwrap().unwrap(), status);
+        assert_eq!(site.published.load(Ordering::SeqCst), 1);
+        assert_eq!(site.received.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prefix_layer() {
+        let mrid = Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid);
+        let local = LocalBus::<ProtobufEncoding>::new();
+        let mut site_a = LayeredBus::new(local.clone(), PrefixLayer::new("site-a"));
+        let mut site_b = LayeredBus::new(local.clone(), PrefixLayer::new("site-b"));
+
+        let mut sub_a: Subscription<ProfileMessage> = site_a
+            .subscribe(TopicFilter::openfmb().iter())
+            .await
+            .unwrap();
+        let mut sub_b: Subscription<ProfileMessage> = site_b
+            .subscribe(TopicFilter::openfmb().iter())
+            .await
+            .unwrap();
+
+        let msg = ProfileMessage::SwitchStatusProfile(SwitchStatusProfile::default());
+        site_b.publish(topic.iter(), msg.clone()).await.unwrap();
+        // topics already prefixed for another site are moved under this one
+        site_a
+            .publish(topic.clone().with_prefix("site-b").iter(), msg.clone())
+            .await
+            .unwrap();
+        assert_eq!(sub_b.next().await.unwrap().unwrap(), msg);
+        assert_eq!(sub_a.next().await.unwrap().unwrap(), msg);
+        local.close();
+        assert_eq!(sub_a.count().await, 0);
+        assert_eq!(sub_b.count().await, 0);
+    }
+}
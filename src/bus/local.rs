@@ -0,0 +1,635 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::prelude::*;
+use crate::topic;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::future;
+use futures::StreamExt;
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{with_timeout, Closable, Replier, Requests};
+
+/// A published message as seen by every matching subscriber, the topic
+/// levels it was published on, its headers, the encoded payload and for
+/// requests where to send the encoded reply.
+#[derive(Debug, Clone)]
+struct Delivery {
+    subject: Arc<Vec<String>>,
+    headers: Arc<Headers>,
+    data: Bytes,
+    reply: Option<UnboundedSender<Bytes>>,
+}
+
+#[derive(Debug)]
+struct LocalSubscriber {
+    topic: TopicBuf,
+    queue: Option<String>,
+    sender: UnboundedSender<Delivery>,
+}
+
+impl LocalSubscriber {
+    fn matches(&self, subject: &[String]) -> bool {
+        topic::matches(
+            self.topic.iter(),
+            LevelIter {
+                iter: subject.iter(),
+            },
+        )
+    }
+}
+
+#[derive(Debug)]
+struct LevelIter<'s> {
+    iter: std::slice::Iter<'s, String>,
+}
+
+impl<'s> Iterator for LevelIter<'s> {
+    type Item = TopicLevel<&'s str>;
+    fn next(&mut self) -> Option<TopicLevel<&'s str>> {
+        self.iter.next().map(|lvl| TopicLevel::Exact(lvl.as_str()))
+    }
+}
+
+impl<'a> Topic<&'a str> for LevelIter<'a> {
+    fn prefix_match(&self) -> bool {
+        false
+    }
+}
+
+fn topic_levels<S: AsRef<str>, T: Topic<S>>(topic: T) -> Vec<TopicLevel<String>> {
+    topic
+        .map(|lvl| match lvl {
+            TopicLevel::Exact(val) => TopicLevel::Exact(val.as_ref().to_string()),
+            TopicLevel::WildCard => TopicLevel::WildCard,
+        })
+        .collect()
+}
+
+/// Levels of a topic that may be published to, one without wildcards or
+/// prefix matching
+fn exact_levels<S: AsRef<str>, T: Topic<S>>(topic: T) -> Result<Vec<String>, String> {
+    if topic.prefix_match() {
+        return Err("cannot publish to a prefix matching topic".to_string());
+    }
+    topic
+        .map(|lvl| match lvl {
+            TopicLevel::Exact(val) => Ok(val.as_ref().to_string()),
+            TopicLevel::WildCard => Err("cannot publish to a wildcard topic".to_string()),
+        })
+        .collect()
+}
+
+fn decode<E, M>(delivery: &Delivery) -> Result<M, SubscriptionError>
+where
+    E: 'static + MessageEncoding,
+    M: Message<E>,
+{
+    let topic = LevelIter {
+        iter: delivery.subject.iter(),
+    };
+    M::decode_with_headers(topic, &delivery.headers, delivery.data.clone())
+        .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
+}
+
+struct LocalReplier<E> {
+    sender: UnboundedSender<Bytes>,
+    encoding: PhantomData<E>,
+}
+
+#[async_trait]
+impl<E, M> Replier<M> for LocalReplier<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn reply(self: Box<Self>, msg: M) -> PublishResult<()> {
+        let mut buf = Vec::with_capacity(512);
+        msg.encode(&mut buf)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        // the requester giving up before the reply arrives is not an error
+        let _ = self.sender.unbounded_send(Bytes::from(buf));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Subscribers {
+    subscribers: Vec<LocalSubscriber>,
+    /// Number of messages handed to each queue group so far, picks the
+    /// member receiving the next one
+    turns: HashMap<String, usize>,
+}
+
+/// Fans encoded messages out to in-process subscriptions by topic
+///
+/// Used directly by the LocalBus and by backends that receive every message
+/// of a connection on a single event loop and must route them on their own.
+/// Each queue group gets every message once, taking turns between members.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Dispatcher {
+    subscribers: Arc<Mutex<Subscribers>>,
+}
+
+impl Dispatcher {
+    /// Register a subscription, messages dispatched after this call that
+    /// match the topic are decoded into the returned stream
+    pub(crate) fn subscribe<E, M, S, T>(&self, topic: T) -> Subscription<M>
+    where
+        E: 'static + MessageEncoding + Send,
+        M: 'static + Message<E> + Send,
+        S: AsRef<str>,
+        T: Topic<S>,
+    {
+        let prefix_match = topic.prefix_match();
+        self.subscribe_levels(topic_levels(topic), prefix_match)
+    }
+
+    /// Register a subscription as a member of a queue group
+    pub(crate) fn subscribe_queue<E, M, S, T>(&self, topic: T, group: &str) -> Subscription<M>
+    where
+        E: 'static + MessageEncoding + Send,
+        M: 'static + Message<E> + Send,
+        S: AsRef<str>,
+        T: Topic<S>,
+    {
+        let prefix_match = topic.prefix_match();
+        Box::pin(
+            self.register(topic_levels(topic), prefix_match, Some(group.to_string()))
+                .map(|delivery| decode(&delivery)),
+        )
+    }
+
+    /// Register a subscription from already collected topic levels
+    pub(crate) fn subscribe_levels<E, M>(
+        &self,
+        levels: Vec<TopicLevel<String>>,
+        prefix_match: bool,
+    ) -> Subscription<M>
+    where
+        E: 'static + MessageEncoding + Send,
+        M: 'static + Message<E> + Send,
+    {
+        Box::pin(
+            self.register(levels, prefix_match, None)
+                .map(|delivery| decode(&delivery)),
+        )
+    }
+
+    /// Register a responder, requests dispatched after this call that match
+    /// the topic are decoded into the returned stream
+    pub(crate) fn serve<E, Req, Resp, S, T>(&self, topic: T) -> Requests<Req, Resp>
+    where
+        E: 'static + MessageEncoding + Send,
+        Req: 'static + Message<E> + Send,
+        Resp: 'static + Message<E> + Send,
+        S: AsRef<str>,
+        T: Topic<S>,
+    {
+        let prefix_match = topic.prefix_match();
+        Box::pin(
+            self.register(topic_levels(topic), prefix_match, None)
+                .filter_map(|delivery| {
+                    // plain publishes have no one to reply to
+                    future::ready(delivery.reply.clone().map(|sender| {
+                        decode(&delivery).map(|msg| {
+                            let replier: Box<dyn Replier<Resp>> = Box::new(LocalReplier {
+                                sender,
+                                encoding: PhantomData::<E>,
+                            });
+                            Request::new(msg, replier)
+                        })
+                    }))
+                }),
+        )
+    }
+
+    fn register(
+        &self,
+        levels: Vec<TopicLevel<String>>,
+        prefix_match: bool,
+        queue: Option<String>,
+    ) -> UnboundedReceiver<Delivery> {
+        let (sender, receiver) = unbounded();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .subscribers
+            .push(LocalSubscriber {
+                topic: TopicBuf::new(levels, prefix_match),
+                queue,
+                sender,
+            });
+        receiver
+    }
+
+    /// Hand an encoded message to every matching subscription
+    pub(crate) fn dispatch(&self, subject: Vec<String>, headers: Headers, data: Bytes) {
+        self.deliver(Delivery {
+            subject: Arc::new(subject),
+            headers: Arc::new(headers),
+            data,
+            reply: None,
+        });
+    }
+
+    /// Hand an encoded request to every matching subscription, the returned
+    /// stream yields the encoded replies and ends once no one is left to reply
+    pub(crate) fn request(
+        &self,
+        subject: Vec<String>,
+        headers: Headers,
+        data: Bytes,
+    ) -> UnboundedReceiver<Bytes> {
+        let (sender, receiver) = unbounded();
+        self.deliver(Delivery {
+            subject: Arc::new(subject),
+            headers: Arc::new(headers),
+            data,
+            reply: Some(sender),
+        });
+        receiver
+    }
+
+    fn deliver(&self, delivery: Delivery) {
+        let mut guard = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Subscribers { subscribers, turns } = &mut *guard;
+        // a closed channel means the subscription was dropped, forget about it
+        subscribers.retain(|sub| !sub.sender.is_closed());
+        let mut groups: BTreeMap<&str, Vec<&LocalSubscriber>> = BTreeMap::new();
+        for sub in subscribers
+            .iter()
+            .filter(|sub| sub.matches(&delivery.subject))
+        {
+            match &sub.queue {
+                Some(group) => groups.entry(group.as_str()).or_default().push(sub),
+                None => {
+                    let _ = sub.sender.unbounded_send(delivery.clone());
+                }
+            }
+        }
+        for (group, members) in groups {
+            let turn = turns.entry(group.to_string()).or_default();
+            let _ = members[*turn % members.len()]
+                .sender
+                .unbounded_send(delivery.clone());
+            *turn = turn.wrapping_add(1);
+        }
+    }
+
+    /// End every registered subscription stream
+    pub(crate) fn clear(&self) {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .subscribers
+            .clear();
+    }
+}
+
+/// In-process Message Bus
+///
+/// Messages are encoded on publish and decoded for each subscriber exactly as
+/// they would be over a network bus, but are delivered through in-memory
+/// channels. Cloned buses share the same set of subscribers, which makes it
+/// possible to run a client and a device against each other in one process.
+#[derive(Debug, Clone)]
+pub struct LocalBus<E: MessageEncoding> {
+    dispatcher: Dispatcher,
+    encoding: PhantomData<E>,
+}
+
+#[async_trait]
+impl<E, M> Subscriber<M> for LocalBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn subscribe<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+    ) -> Result<Subscription<M>, SubscribeError> {
+        Ok(self.dispatcher.subscribe(topic))
+    }
+
+    /// Members of a queue group take turns receiving messages
+    async fn subscribe_queue<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        group: &str,
+    ) -> Result<Subscription<M>, SubscribeError> {
+        Ok(self.dispatcher.subscribe_queue(topic, group))
+    }
+}
+
+#[async_trait]
+impl<E, M> Publisher<M> for LocalBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn publish<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T, msg: M) -> PublishResult<()> {
+        let subject = exact_levels(topic).map_err(PublishError::InvalidTopic)?;
+        let mut buf = Vec::with_capacity(512);
+        msg.encode(&mut buf)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        let headers = msg.headers().cloned().unwrap_or_default();
+        self.dispatcher.dispatch(subject, headers, Bytes::from(buf));
+        Ok(())
+    }
+}
+
+/// Requests are delivered to every matching responder, the first reply wins
+#[async_trait]
+impl<E, Req, Resp> Requester<Req, Resp> for LocalBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    Req: 'static + Message<E> + Send,
+    Resp: 'static + Message<E> + Send,
+{
+    async fn request<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        req: Req,
+        timeout: Duration,
+    ) -> RequestResult<Resp> {
+        let subject = exact_levels(topic).map_err(RequestError::InvalidTopic)?;
+        let mut buf = Vec::with_capacity(512);
+        req.encode(&mut buf)
+            .map_err(|err| RequestError::EncodeError(Box::new(err)))?;
+        let headers = req.headers().cloned().unwrap_or_default();
+        let mut replies = self
+            .dispatcher
+            .request(subject.clone(), headers, Bytes::from(buf));
+        match with_timeout(replies.next(), timeout).await? {
+            Some(data) => {
+                // replies are decoded as if they were published on the request topic
+                let topic = LevelIter {
+                    iter: subject.iter(),
+                };
+                Resp::decode(topic, data).map_err(|err| RequestError::DecodeError(Box::new(err)))
+            }
+            None => Err(RequestError::NoResponders),
+        }
+    }
+}
+
+#[async_trait]
+impl<E, Req, Resp> Responder<Req, Resp> for LocalBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    Req: 'static + Message<E> + Send,
+    Resp: 'static + Message<E> + Send,
+{
+    async fn serve<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+    ) -> Result<Requests<Req, Resp>, SubscribeError> {
+        Ok(self.dispatcher.serve(topic))
+    }
+}
+
+/// A local bus implements the MessageBus trait
+impl<E, M> MessageBus<M> for LocalBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Send + Message<E>,
+{
+}
+
+impl<E> Closable<E> for LocalBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+{
+    /// Ends every subscription stream of this bus and all of its clones
+    fn close(&self) {
+        self.dispatcher.clear();
+    }
+}
+
+impl<E> LocalBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+{
+    pub fn new() -> LocalBus<E> {
+        LocalBus {
+            dispatcher: Dispatcher::default(),
+            encoding: PhantomData,
+        }
+    }
+}
+
+impl<E> Default for LocalBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+{
+    fn default() -> Self {
+        LocalBus::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Closable, LocalBus};
+    use crate::bus::Requests;
+    use crate::encoding::ProtobufEncoding;
+    use crate::prelude::*;
+    use futures::StreamExt;
+    use openfmb_messages::{
+        switchmodule::{SwitchReadingProfile, SwitchStatusProfile},
+        ProfileMessage,
+    };
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    fn mrid() -> Uuid {
+        Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_publish_subscribe() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let mut publisher = bus.clone();
+        let mut sub: Subscription<SwitchStatusProfile> = bus.subscribe(topic.iter()).await.unwrap();
+
+        let status = SwitchStatusProfile::default();
+        publisher
+            .publish(topic.iter(), status.clone())
+            .await
+            .unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), status);
+
+        bus.close();
+        assert!(sub.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_queue_subscribe() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let mut all: Subscription<SwitchStatusProfile> = bus.subscribe(topic.iter()).await.unwrap();
+        let mut worker0: Subscription<SwitchStatusProfile> =
+            bus.subscribe_queue(topic.iter(), "workers").await.unwrap();
+        let mut worker1: Subscription<SwitchStatusProfile> =
+            bus.subscribe_queue(topic.iter(), "workers").await.unwrap();
+
+        let status = SwitchStatusProfile::default();
+        for _ in 0..4 {
+            bus.publish(topic.iter(), status.clone()).await.unwrap();
+        }
+        for _ in 0..4 {
+            assert_eq!(all.next().await.unwrap().unwrap(), status);
+        }
+        for _ in 0..2 {
+            assert_eq!(worker0.next().await.unwrap().unwrap(), status);
+            assert_eq!(worker1.next().await.unwrap().unwrap(), status);
+        }
+
+        // the remaining member gets everything once the other leaves
+        drop(worker1);
+        bus.publish(topic.iter(), status.clone()).await.unwrap();
+        bus.publish(topic.iter(), status.clone()).await.unwrap();
+        bus.close();
+        assert_eq!(worker0.count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_headers() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let mut sub: Subscription<WithHeaders<SwitchStatusProfile>> =
+            bus.subscribe(topic.iter()).await.unwrap();
+        let mut plain: Subscription<SwitchStatusProfile> =
+            bus.subscribe(topic.iter()).await.unwrap();
+
+        let mut headers = Headers::new();
+        headers.set_publisher_id("switch-a");
+        headers.set_sequence(7);
+        let status = SwitchStatusProfile::default();
+        bus.publish(
+            topic.iter(),
+            WithHeaders::new(headers.clone(), status.clone()),
+        )
+        .await
+        .unwrap();
+        let received = sub.next().await.unwrap().unwrap();
+        assert_eq!(received.headers, headers);
+        assert_eq!(received.headers.sequence(), Some(7));
+        assert_eq!(received.msg, status);
+        assert_eq!(plain.next().await.unwrap().unwrap(), status);
+
+        // messages published without headers arrive with none
+        bus.publish(topic.iter(), status.clone()).await.unwrap();
+        assert!(sub.next().await.unwrap().unwrap().headers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_profile_message_wildcard() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let everything = vec![
+            TopicLevel::Exact("openfmb"),
+            TopicLevel::WildCard,
+            TopicLevel::Exact("SwitchStatusProfile"),
+        ];
+        let mut sub: Subscription<ProfileMessage> = bus
+            .subscribe(PrefixTopic(everything.into_iter()))
+            .await
+            .unwrap();
+
+        let msg = ProfileMessage::SwitchStatusProfile(SwitchStatusProfile::default());
+        bus.publish(topic.iter(), msg.clone()).await.unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), msg);
+    }
+
+    #[tokio::test]
+    async fn test_typed_and_profile_message() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let mut typed: Subscription<SwitchStatusProfile> =
+            bus.subscribe(topic.iter()).await.unwrap();
+        let mut any: Subscription<ProfileMessage> =
+            bus.subscribe(TopicFilter::openfmb().iter()).await.unwrap();
+
+        let status = SwitchStatusProfile::default();
+        bus.publish(topic.iter(), status.clone()).await.unwrap();
+        assert_eq!(typed.next().await.unwrap().unwrap(), status);
+        assert_eq!(
+            any.next().await.unwrap().unwrap(),
+            ProfileMessage::SwitchStatusProfile(status.clone())
+        );
+
+        bus.publish(
+            topic.iter(),
+            ProfileMessage::SwitchStatusProfile(status.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(typed.next().await.unwrap().unwrap(), status);
+    }
+
+    #[tokio::test]
+    async fn test_request_reply() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let mut requester = bus.clone();
+        let timeout = Duration::from_millis(50);
+
+        let res: RequestResult<SwitchStatusProfile> = requester
+            .request(topic.iter(), SwitchReadingProfile::default(), timeout)
+            .await;
+        assert!(matches!(res, Err(RequestError::NoResponders)));
+
+        let mut requests: Requests<SwitchReadingProfile, SwitchStatusProfile> =
+            bus.serve(topic.iter()).await.unwrap();
+        let status = SwitchStatusProfile::default();
+        let reply = status.clone();
+        tokio::spawn(async move {
+            let req = requests.next().await.unwrap().unwrap();
+            req.reply(reply).await.unwrap();
+        });
+        let res: SwitchStatusProfile = requester
+            .request(topic.iter(), SwitchReadingProfile::default(), timeout)
+            .await
+            .unwrap();
+        assert_eq!(res, status);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let _requests: Requests<SwitchReadingProfile, SwitchStatusProfile> =
+            bus.serve(topic.iter()).await.unwrap();
+        let timeout = Duration::from_millis(10);
+        let res: RequestResult<SwitchStatusProfile> = bus
+            .request(topic.iter(), SwitchReadingProfile::default(), timeout)
+            .await;
+        assert!(matches!(res, Err(RequestError::Timeout(t)) if t == timeout));
+    }
+
+    struct PrefixTopic<I>(I);
+
+    impl<I: Send + Iterator<Item = TopicLevel<&'static str>>> Iterator for PrefixTopic<I> {
+        type Item = TopicLevel<&'static str>;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.0.next()
+        }
+    }
+
+    impl<I: Send + Iterator<Item = TopicLevel<&'static str>>> Topic<&'static str> for PrefixTopic<I> {
+        fn prefix_match(&self) -> bool {
+            true
+        }
+    }
+}
@@ -0,0 +1,529 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::prelude::*;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::StreamExt;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Response, Server};
+use prometheus::{
+    Encoder, Gauge, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry,
+    TextEncoder,
+};
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::Closable;
+
+/// Upper bounds in seconds of the latency histogram buckets
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Module, profile and mRID of an `openfmb.<module>.<profile>.<mrid>` topic,
+/// empty for levels the topic does not have, skipping any prefix before the
+/// root
+fn topic_labels(topic: &TopicBuf) -> [String; 3] {
+    let levels = topic.root_levels().unwrap_or(&topic.levels);
+    let mut levels = levels.iter().filter_map(|lvl| match lvl {
+        TopicLevel::Exact(val) if val.is_empty() => None,
+        TopicLevel::Exact(val) => Some(val.as_str()),
+        TopicLevel::WildCard => Some("*"),
+    });
+    let _root = levels.next();
+    let mut label = || levels.next().unwrap_or_default().to_string();
+    [label(), label(), label()]
+}
+
+/// Finds the first field with the tag in an encoded protobuf message
+fn protobuf_field(mut buf: &[u8], tag: u64) -> Option<ProtobufField<'_>> {
+    while !buf.is_empty() {
+        let key = prost::encoding::decode_varint(&mut buf).ok()?;
+        let field = match key & 0x07 {
+            0 => ProtobufField::Varint(prost::encoding::decode_varint(&mut buf).ok()?),
+            1 => {
+                buf = buf.get(8..)?;
+                ProtobufField::Other
+            }
+            2 => {
+                let len = prost::encoding::decode_varint(&mut buf).ok()? as usize;
+                let (field, rest) = (buf.get(..len)?, buf.get(len..)?);
+                buf = rest;
+                ProtobufField::Bytes(field)
+            }
+            5 => {
+                buf = buf.get(4..)?;
+                ProtobufField::Other
+            }
+            _ => return None,
+        };
+        if key >> 3 == tag {
+            return Some(field);
+        }
+    }
+    None
+}
+
+enum ProtobufField<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Other,
+}
+
+fn protobuf_message(buf: &[u8], tag: u64) -> Option<&[u8]> {
+    match protobuf_field(buf, tag)? {
+        ProtobufField::Bytes(field) => Some(field),
+        _ => None,
+    }
+}
+
+fn protobuf_varint(buf: &[u8], tag: u64) -> u64 {
+    match protobuf_field(buf, tag) {
+        Some(ProtobufField::Varint(val)) => val,
+        _ => 0,
+    }
+}
+
+/// Reads `MessageInfo.message_time_stamp` from a protobuf encoded profile
+///
+/// Every profile starts with its message info (tag 1) which in turn starts
+/// with the MessageInfo (tag 1) holding the time stamp (tag 2) so this works
+/// without knowing the profile.
+fn message_time_stamp(payload: &[u8]) -> Option<SystemTime> {
+    let profile_info = protobuf_message(payload, 1)?;
+    let message_info = protobuf_message(profile_info, 1)?;
+    let time_stamp = protobuf_message(message_info, 2)?;
+    let seconds = protobuf_varint(time_stamp, 2);
+    let nanoseconds = protobuf_varint(time_stamp, 4);
+    // the payload is not trusted, a time stamp past what SystemTime holds is
+    // left out rather than overflowing
+    let since_epoch =
+        Duration::from_secs(seconds).checked_add(Duration::from_nanos(nanoseconds))?;
+    UNIX_EPOCH.checked_add(since_epoch)
+}
+
+/// Message counts, sizes and latencies of one or more MeteredBus
+///
+/// Cloning gives another handle to the same metrics.
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    registry: Registry,
+    messages: IntCounterVec,
+    bytes: IntCounterVec,
+    decode_errors: IntCounterVec,
+    publish_failures: IntCounterVec,
+    latency: HistogramVec,
+    last_received: GaugeVec,
+}
+
+impl Metrics {
+    /// Metrics in a registry of their own
+    pub fn new() -> Result<Metrics, prometheus::Error> {
+        Metrics::with_registry(Registry::new())
+    }
+
+    /// Metrics registered to an existing registry
+    pub fn with_registry(registry: Registry) -> Result<Metrics, prometheus::Error> {
+        let labels = &["direction", "module", "profile", "mrid"];
+        let messages = IntCounterVec::new(
+            Opts::new("openfmb_messages_total", "Messages published and received"),
+            labels,
+        )?;
+        let bytes = IntCounterVec::new(
+            Opts::new(
+                "openfmb_message_bytes_total",
+                "Encoded bytes of the messages published and received",
+            ),
+            labels,
+        )?;
+        let decode_errors = IntCounterVec::new(
+            Opts::new(
+                "openfmb_decode_errors_total",
+                "Received messages that failed to decode",
+            ),
+            &["module", "profile", "mrid"],
+        )?;
+        let publish_failures = IntCounterVec::new(
+            Opts::new(
+                "openfmb_publish_failures_total",
+                "Messages that failed to encode or publish",
+            ),
+            &["module", "profile", "mrid"],
+        )?;
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "openfmb_message_latency_seconds",
+                "Time from the message time stamp until the message was received",
+            )
+            .buckets(LATENCY_BUCKETS.to_vec()),
+            &["module", "profile"],
+        )?;
+        let last_received = GaugeVec::new(
+            Opts::new(
+                "openfmb_last_received_timestamp_seconds",
+                "Unix time the last message was received",
+            ),
+            &["module", "profile", "mrid"],
+        )?;
+        registry.register(Box::new(messages.clone()))?;
+        registry.register(Box::new(bytes.clone()))?;
+        registry.register(Box::new(decode_errors.clone()))?;
+        registry.register(Box::new(publish_failures.clone()))?;
+        registry.register(Box::new(latency.clone()))?;
+        registry.register(Box::new(last_received.clone()))?;
+        Ok(Metrics {
+            registry,
+            messages,
+            bytes,
+            decode_errors,
+            publish_failures,
+            latency,
+            last_received,
+        })
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// The metrics in the Prometheus text format
+    pub fn encode_text(&self) -> String {
+        let mut buf = Vec::new();
+        // writing to a Vec only fails on invalid metric families, which
+        // the metrics registered here never are
+        let _ = TextEncoder::new().encode(&self.registry.gather(), &mut buf);
+        String::from_utf8(buf).unwrap_or_default()
+    }
+
+    /// Serve the metrics in the Prometheus text format over HTTP on any path
+    ///
+    /// Runs until the server fails, so is typically spawned onto the tokio
+    /// runtime.
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), hyper::Error> {
+        let make_service = make_service_fn(move |_conn| {
+            let metrics = self.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_req| {
+                    let body = metrics.encode_text();
+                    async move {
+                        Ok::<_, Infallible>(
+                            Response::builder()
+                                .header(header::CONTENT_TYPE, TextEncoder::new().format_type())
+                                .body(Body::from(body))
+                                .unwrap_or_default(),
+                        )
+                    }
+                }))
+            }
+        });
+        Server::try_bind(&addr)?.serve(make_service).await
+    }
+
+    fn published(&self, topic: &TopicBuf, len: usize) {
+        let [module, profile, mrid] = topic_labels(topic);
+        let labels = &["published", &module, &profile, &mrid];
+        self.messages.with_label_values(labels).inc();
+        self.bytes.with_label_values(labels).inc_by(len as u64);
+    }
+
+    fn publish_failed(&self, topic: &TopicBuf) {
+        let [module, profile, mrid] = topic_labels(topic);
+        self.publish_failures
+            .with_label_values(&[&module, &profile, &mrid])
+            .inc();
+    }
+
+    fn received(&self, raw: &RawMessage) {
+        let now = SystemTime::now();
+        let [module, profile, mrid] = topic_labels(&raw.topic);
+        let labels = &["received", &module, &profile, &mrid];
+        self.messages.with_label_values(labels).inc();
+        self.bytes
+            .with_label_values(labels)
+            .inc_by(raw.payload.len() as u64);
+        let last_received: Gauge = self
+            .last_received
+            .with_label_values(&[&module, &profile, &mrid]);
+        last_received.set(
+            now.duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64(),
+        );
+        // messages from a publisher with a clock ahead of ours are left out
+        // rather than recorded as zero latency
+        if let Some(latency) =
+            message_time_stamp(&raw.payload).and_then(|sent| now.duration_since(sent).ok())
+        {
+            self.latency
+                .with_label_values(&[&module, &profile])
+                .observe(latency.as_secs_f64());
+        }
+    }
+
+    fn decode_failed(&self, topic: &TopicBuf) {
+        let [module, profile, mrid] = topic_labels(topic);
+        self.decode_errors
+            .with_label_values(&[&module, &profile, &mrid])
+            .inc();
+    }
+}
+
+/// A Message Bus recording Metrics of every message published and received
+///
+/// Messages are encoded with E and passed on as a RawMessage by the wrapped
+/// bus, so counts and sizes are kept per module, profile and mRID of the
+/// actual topic even for wildcard subscriptions. Latency is measured from
+/// the `message_time_stamp` of protobuf encoded profiles.
+///
+/// ```ignore
+/// let metrics = Metrics::new()?;
+/// tokio::spawn(metrics.clone().serve(([127, 0, 0, 1], 9898).into()));
+/// let bus = MeteredBus::<_, ProtobufEncoding>::new(nats_bus, metrics);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MeteredBus<B, E: MessageEncoding> {
+    inner: B,
+    metrics: Metrics,
+    encoding: PhantomData<E>,
+}
+
+impl<B, E: MessageEncoding> MeteredBus<B, E> {
+    pub fn new(inner: B, metrics: Metrics) -> MeteredBus<B, E> {
+        MeteredBus {
+            inner,
+            metrics,
+            encoding: PhantomData,
+        }
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+}
+
+#[async_trait]
+impl<B, E, M> Subscriber<M> for MeteredBus<B, E>
+where
+    B: Subscriber<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn subscribe<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T) -> SubscribeResult<M> {
+        let subscription = self.inner.subscribe(topic).await?;
+        Ok(self.measure(subscription))
+    }
+
+    async fn subscribe_queue<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        group: &str,
+    ) -> SubscribeResult<M> {
+        let subscription = self.inner.subscribe_queue(topic, group).await?;
+        Ok(self.measure(subscription))
+    }
+}
+
+impl<B, E> MeteredBus<B, E>
+where
+    E: 'static + MessageEncoding + Send,
+{
+    fn measure<M>(&self, subscription: Subscription<RawMessage>) -> Subscription<M>
+    where
+        M: 'static + Message<E> + Send,
+    {
+        let metrics = self.metrics.clone();
+        Box::pin(subscription.map(move |raw| {
+            let raw = raw?;
+            metrics.received(&raw);
+            M::decode_with_headers(raw.topic.iter(), &raw.headers, raw.payload.clone()).map_err(
+                |err| {
+                    metrics.decode_failed(&raw.topic);
+                    SubscriptionError::DecodeError(Box::new(err))
+                },
+            )
+        }))
+    }
+}
+
+#[async_trait]
+impl<B, E, M> Publisher<M> for MeteredBus<B, E>
+where
+    B: Publisher<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn publish<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T, msg: M) -> PublishResult<()> {
+        let topic = TopicBuf::from_topic(topic);
+        // messages are ~400 bytes
+        let mut payload = Vec::with_capacity(512);
+        if let Err(err) = msg.encode(&mut payload) {
+            self.metrics.publish_failed(&topic);
+            return Err(PublishError::EncodeError(Box::new(err)));
+        }
+        let len = payload.len();
+        let result = self
+            .inner
+            .publish(
+                topic.iter(),
+                RawMessage::new(topic.clone(), Bytes::from(payload))
+                    .with_headers(msg.headers().cloned().unwrap_or_default()),
+            )
+            .await;
+        match result {
+            Ok(()) => self.metrics.published(&topic, len),
+            Err(_) => self.metrics.publish_failed(&topic),
+        }
+        result
+    }
+}
+
+/// A metered bus implements the MessageBus trait
+impl<B, E, M> MessageBus<M> for MeteredBus<B, E>
+where
+    B: MessageBus<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+}
+
+impl<B, E, C> Closable<C> for MeteredBus<B, E>
+where
+    B: Closable<C>,
+    E: MessageEncoding,
+{
+    fn close(&self) {
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{message_time_stamp, topic_labels, MeteredBus, Metrics};
+    use crate::bus::LocalBus;
+    use crate::encoding::ProtobufEncoding;
+    use crate::prelude::*;
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use openfmb_messages::commonmodule::{MessageInfo, StatusMessageInfo, Timestamp};
+    use openfmb_messages::switchmodule::SwitchStatusProfile;
+    use std::time::{Duration, UNIX_EPOCH};
+    use uuid::Uuid;
+
+    fn topic() -> ProfileTopic {
+        let mrid = Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+        ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid)
+    }
+
+    fn status(seconds: u64) -> SwitchStatusProfile {
+        SwitchStatusProfile {
+            status_message_info: Some(StatusMessageInfo {
+                message_info: Some(MessageInfo {
+                    message_time_stamp: Some(Timestamp {
+                        seconds,
+                        nanoseconds: 500_000_000,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_message_time_stamp() {
+        let payload = prost::Message::encode_to_vec(&status(1_600_000_000));
+        assert_eq!(
+            message_time_stamp(&payload),
+            Some(UNIX_EPOCH + Duration::from_millis(1_600_000_000_500))
+        );
+        assert_eq!(message_time_stamp(&[]), None);
+        assert_eq!(message_time_stamp(&[0x0a, 0xff]), None);
+        let payload = prost::Message::encode_to_vec(&status(u64::MAX));
+        assert_eq!(message_time_stamp(&payload), None);
+    }
+
+    #[test]
+    fn test_topic_labels() {
+        let labels = [
+            "switchmodule".to_string(),
+            "SwitchStatusProfile".to_string(),
+            "06fb668d-f87a-4b1b-8d99-0949513126ff".to_string(),
+        ];
+        assert_eq!(topic_labels(&TopicBuf::from_topic(topic().iter())), labels);
+        assert_eq!(
+            topic_labels(&TopicBuf::from_topic(topic().with_prefix("site-a").iter())),
+            labels
+        );
+        assert_eq!(
+            topic_labels(&TopicBuf::from_subject("openfmb.switchmodule")),
+            ["switchmodule".to_string(), String::new(), String::new()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metered_bus() {
+        let metrics = Metrics::new().unwrap();
+        let local = LocalBus::<ProtobufEncoding>::new();
+        let mut bus = MeteredBus::<_, ProtobufEncoding>::new(local.clone(), metrics.clone());
+        let mut sub: Subscription<SwitchStatusProfile> =
+            bus.subscribe(topic().iter()).await.unwrap();
+
+        let msg = status(1_600_000_000);
+        bus.publish(topic().iter(), msg.clone()).await.unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), msg);
+
+        // not a valid protobuf message
+        local
+            .clone()
+            .publish(
+                topic().iter(),
+                RawMessage::new(
+                    TopicBuf::from_topic(topic().iter()),
+                    Bytes::from_static(&[0xff]),
+                ),
+            )
+            .await
+            .unwrap();
+        assert!(sub.next().await.unwrap().is_err());
+
+        let text = metrics.encode_text();
+        let labels = "module=\"switchmodule\",mrid=\"06fb668d-f87a-4b1b-8d99-0949513126ff\",profile=\"SwitchStatusProfile\"";
+        assert!(text.contains(&format!(
+            "openfmb_messages_total{{direction=\"published\",{}}} 1",
+            labels
+        )));
+        assert!(text.contains(&format!(
+            "openfmb_messages_total{{direction=\"received\",{}}} 2",
+            labels
+        )));
+        assert!(text.contains(&format!("openfmb_decode_errors_total{{{}}} 1", labels)));
+        assert!(text.contains(
+            "openfmb_message_latency_seconds_count{module=\"switchmodule\",profile=\"SwitchStatusProfile\"} 1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_metered_bus_prefixed() {
+        let metrics = Metrics::new().unwrap();
+        let local = LocalBus::<ProtobufEncoding>::new();
+        let mut bus = MeteredBus::<_, ProtobufEncoding>::new(local, metrics.clone());
+        let topic = topic().with_prefix("site-a");
+        let mut sub: Subscription<SwitchStatusProfile> = bus.subscribe(topic.iter()).await.unwrap();
+
+        let msg = status(1_600_000_000);
+        bus.publish(topic.iter(), msg.clone()).await.unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), msg);
+
+        let text = metrics.encode_text();
+        let labels = "module=\"switchmodule\",mrid=\"06fb668d-f87a-4b1b-8d99-0949513126ff\",profile=\"SwitchStatusProfile\"";
+        assert!(text.contains(&format!(
+            "openfmb_messages_total{{direction=\"received\",{}}} 1",
+            labels
+        )));
+    }
+}
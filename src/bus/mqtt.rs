@@ -0,0 +1,463 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::prelude::*;
+use async_trait::async_trait;
+use log::warn;
+use rumqttc::v5;
+use std::error::Error;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::local::Dispatcher;
+use super::Closable;
+
+pub use rumqttc::QoS;
+
+/// Number of outstanding requests buffered between the bus and its event loop
+const REQUEST_CAPACITY: usize = 64;
+
+/// Time to wait before polling the event loop again after a connection error,
+/// polling again is what makes rumqttc reconnect
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Options applied to an individual MQTT publish
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MqttPublishOptions {
+    pub qos: QoS,
+    pub retain: bool,
+}
+
+impl Default for MqttPublishOptions {
+    fn default() -> Self {
+        MqttPublishOptions {
+            qos: QoS::AtMostOnce,
+            retain: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum MqttClient {
+    V4(rumqttc::AsyncClient),
+    V5(v5::AsyncClient),
+}
+
+fn v5_qos(qos: QoS) -> v5::mqttbytes::QoS {
+    match qos {
+        QoS::AtMostOnce => v5::mqttbytes::QoS::AtMostOnce,
+        QoS::AtLeastOnce => v5::mqttbytes::QoS::AtLeastOnce,
+        QoS::ExactlyOnce => v5::mqttbytes::QoS::ExactlyOnce,
+    }
+}
+
+impl MqttClient {
+    async fn publish(
+        &self,
+        topic: String,
+        options: MqttPublishOptions,
+        payload: Vec<u8>,
+    ) -> Result<(), Box<dyn Error>> {
+        match self {
+            MqttClient::V4(client) => client
+                .publish(topic, options.qos, options.retain, payload)
+                .await
+                .map_err(|err| Box::new(err) as Box<dyn Error>),
+            MqttClient::V5(client) => client
+                .publish(topic, v5_qos(options.qos), options.retain, payload)
+                .await
+                .map_err(|err| Box::new(err) as Box<dyn Error>),
+        }
+    }
+
+    async fn subscribe(&self, filter: String, qos: QoS) -> Result<(), Box<dyn Error>> {
+        match self {
+            MqttClient::V4(client) => client
+                .subscribe(filter, qos)
+                .await
+                .map_err(|err| Box::new(err) as Box<dyn Error>),
+            MqttClient::V5(client) => client
+                .subscribe(filter, v5_qos(qos))
+                .await
+                .map_err(|err| Box::new(err) as Box<dyn Error>),
+        }
+    }
+
+    /// Subscribe to every filter again with a single request, without
+    /// waiting as it is called from the event loop that takes the request
+    fn resubscribe(&self, filters: &[(String, QoS)]) {
+        if filters.is_empty() {
+            return;
+        }
+        let res = match self {
+            MqttClient::V4(client) => client
+                .try_subscribe_many(
+                    filters
+                        .iter()
+                        .map(|(filter, qos)| rumqttc::SubscribeFilter::new(filter.clone(), *qos)),
+                )
+                .map_err(|err| err.to_string()),
+            MqttClient::V5(client) => client
+                .try_subscribe_many(
+                    filters
+                        .iter()
+                        .map(|(filter, qos)| v5::mqttbytes::Filter::new(filter, v5_qos(*qos))),
+                )
+                .map_err(|err| err.to_string()),
+        };
+        if let Err(err) = res {
+            warn!("mqtt resubscribe failed {}", err);
+        }
+    }
+
+    fn disconnect(&self) {
+        let res = match self {
+            MqttClient::V4(client) => client.try_disconnect().map_err(|err| err.to_string()),
+            MqttClient::V5(client) => client.try_disconnect().map_err(|err| err.to_string()),
+        };
+        if let Err(err) = res {
+            warn!("mqtt disconnect failed {}", err);
+        }
+    }
+}
+
+/// MQTT Message Bus
+///
+/// Topic levels are separated by `/`, wildcards map to `+` and prefix
+/// matching topics to a trailing `#`. Every subscription shares the single
+/// connection, received messages are routed to subscriptions by the bus itself.
+///
+/// Messages received over MQTT v5 carry their user properties as Headers,
+/// headers are not published as the client has no way to send properties.
+///
+/// The filters subscribed to are sent to the broker again whenever it
+/// reconnects without the session, as a clean session loses them.
+#[derive(Debug, Clone)]
+pub struct MqttBus<E: MessageEncoding> {
+    client: MqttClient,
+    dispatcher: Dispatcher,
+    filters: Filters,
+    closed: Arc<AtomicBool>,
+    publish_options: MqttPublishOptions,
+    subscribe_qos: QoS,
+    encoding: PhantomData<E>,
+}
+
+fn topic_to_subject<S, I>(topic: I, prefix_match: bool) -> String
+where
+    S: AsRef<str>,
+    I: Iterator<Item = TopicLevel<S>>,
+{
+    let mut subject = String::with_capacity(128);
+    topic.for_each(|lvl| match lvl {
+        TopicLevel::Exact(val) => {
+            subject.push_str(val.as_ref());
+            subject.push('/');
+        }
+        TopicLevel::WildCard => subject.push_str("+/"),
+    });
+
+    if prefix_match {
+        subject.push('#');
+    } else {
+        subject.pop();
+    }
+    subject
+}
+
+fn subject_to_levels(subject: &str) -> Vec<String> {
+    subject.split('/').map(String::from).collect()
+}
+
+/// The filters subscribed to on the broker with their QoS
+type Filters = Arc<Mutex<Vec<(String, QoS)>>>;
+
+/// Everything the task driving the event loop shares with the bus
+struct Connection {
+    client: MqttClient,
+    dispatcher: Dispatcher,
+    filters: Filters,
+    closed: Arc<AtomicBool>,
+    connected: bool,
+}
+
+impl Connection {
+    /// Subscribe again on a reconnect the broker kept no session for, the
+    /// first connect sends the subscriptions queued by the bus itself
+    fn connected(&mut self, session_present: bool) {
+        if self.connected && !session_present {
+            let filters = self
+                .filters
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone();
+            self.client.resubscribe(&filters);
+        }
+        self.connected = true;
+    }
+}
+
+fn spawn_v4(mut eventloop: rumqttc::EventLoop, mut conn: Connection) {
+    tokio::spawn(async move {
+        let (dispatcher, closed) = (conn.dispatcher.clone(), conn.closed.clone());
+        while !closed.load(Ordering::Relaxed) {
+            match eventloop.poll().await {
+                Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(connack))) => {
+                    conn.connected(connack.session_present)
+                }
+                Ok(rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish))) => dispatcher
+                    .dispatch(
+                        subject_to_levels(&publish.topic),
+                        Headers::default(),
+                        publish.payload,
+                    ),
+                Ok(_) => (),
+                Err(err) => {
+                    warn!("mqtt connection error {:?}", err);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        }
+    });
+}
+
+fn spawn_v5(mut eventloop: v5::EventLoop, mut conn: Connection) {
+    tokio::spawn(async move {
+        let (dispatcher, closed) = (conn.dispatcher.clone(), conn.closed.clone());
+        while !closed.load(Ordering::Relaxed) {
+            match eventloop.poll().await {
+                Ok(v5::Event::Incoming(packet)) => {
+                    if let v5::mqttbytes::v5::Packet::ConnAck(connack) = &*packet {
+                        conn.connected(connack.session_present);
+                    }
+                    if let v5::mqttbytes::v5::Packet::Publish(publish, properties) = *packet {
+                        let headers: Headers = properties
+                            .map(|properties| properties.user_properties)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .collect();
+                        match std::str::from_utf8(&publish.topic) {
+                            Ok(topic) => dispatcher.dispatch(
+                                subject_to_levels(topic),
+                                headers,
+                                publish.payload,
+                            ),
+                            Err(err) => warn!("mqtt publish with invalid topic {:?}", err),
+                        }
+                    }
+                }
+                Ok(_) => (),
+                Err(err) => {
+                    warn!("mqtt connection error {:?}", err);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        }
+    });
+}
+
+#[async_trait]
+impl<E, M> Subscriber<M> for MqttBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn subscribe<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+    ) -> Result<Subscription<M>, SubscribeError> {
+        let prefix_match = topic.prefix_match();
+        let levels: Vec<TopicLevel<String>> = topic
+            .map(|lvl| match lvl {
+                TopicLevel::Exact(val) => TopicLevel::Exact(val.as_ref().to_string()),
+                TopicLevel::WildCard => TopicLevel::WildCard,
+            })
+            .collect();
+        let filter = topic_to_subject(
+            levels.iter().map(|lvl| match lvl {
+                TopicLevel::Exact(val) => TopicLevel::Exact(val.as_str()),
+                TopicLevel::WildCard => TopicLevel::WildCard,
+            }),
+            prefix_match,
+        );
+
+        // register locally first so nothing the broker sends back is missed
+        let subscription = self.dispatcher.subscribe_levels(levels, prefix_match);
+        {
+            let mut filters = self
+                .filters
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            match filters.iter_mut().find(|(existing, _)| *existing == filter) {
+                Some(existing) => existing.1 = self.subscribe_qos,
+                None => filters.push((filter.clone(), self.subscribe_qos)),
+            }
+        }
+        self.client
+            .subscribe(filter, self.subscribe_qos)
+            .await
+            .map_err(SubscribeError::BusError)?;
+        Ok(subscription)
+    }
+}
+
+#[async_trait]
+impl<E, M> Publisher<M> for MqttBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn publish<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T, msg: M) -> PublishResult<()> {
+        let options = self.publish_options;
+        self.publish_with(topic, msg, options).await
+    }
+}
+
+/// A mqtt connection implements the MessageBus trait
+impl<E, M> MessageBus<M> for MqttBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Send + Message<E>,
+{
+}
+
+impl<E> Closable<E> for MqttBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+{
+    fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        self.dispatcher.clear();
+        self.client.disconnect();
+    }
+}
+
+impl<E> MqttBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+{
+    /// Connect to a broker using MQTT 3.1.1
+    ///
+    /// The connection is driven by a task spawned on the current tokio runtime.
+    pub fn new(options: rumqttc::MqttOptions) -> MqttBus<E> {
+        let (client, eventloop) = rumqttc::AsyncClient::new(options, REQUEST_CAPACITY);
+        let bus = MqttBus::with_client(MqttClient::V4(client));
+        spawn_v4(eventloop, bus.connection());
+        bus
+    }
+
+    /// Connect to a broker using MQTT 5
+    ///
+    /// The connection is driven by a task spawned on the current tokio runtime.
+    pub fn new_v5(options: v5::MqttOptions) -> MqttBus<E> {
+        let (client, eventloop) = v5::AsyncClient::new(options, REQUEST_CAPACITY);
+        let bus = MqttBus::with_client(MqttClient::V5(client));
+        spawn_v5(eventloop, bus.connection());
+        bus
+    }
+
+    fn with_client(client: MqttClient) -> MqttBus<E> {
+        MqttBus {
+            client,
+            dispatcher: Dispatcher::default(),
+            filters: Filters::default(),
+            closed: Arc::new(AtomicBool::new(false)),
+            publish_options: MqttPublishOptions::default(),
+            subscribe_qos: QoS::AtMostOnce,
+            encoding: PhantomData,
+        }
+    }
+
+    fn connection(&self) -> Connection {
+        Connection {
+            client: self.client.clone(),
+            dispatcher: self.dispatcher.clone(),
+            filters: self.filters.clone(),
+            closed: self.closed.clone(),
+            connected: false,
+        }
+    }
+
+    /// Set the options used by `Publisher::publish`
+    pub fn with_publish_options(mut self, options: MqttPublishOptions) -> MqttBus<E> {
+        self.publish_options = options;
+        self
+    }
+
+    /// Set the QoS requested for subscriptions
+    pub fn with_subscribe_qos(mut self, qos: QoS) -> MqttBus<E> {
+        self.subscribe_qos = qos;
+        self
+    }
+
+    /// Publish a message with the given QoS and retain flag rather than the
+    /// bus defaults
+    pub async fn publish_with<M, S, T>(
+        &mut self,
+        topic: T,
+        msg: M,
+        options: MqttPublishOptions,
+    ) -> PublishResult<()>
+    where
+        M: 'static + Message<E> + Send,
+        S: AsRef<str>,
+        T: Topic<S>,
+    {
+        // 512 bytes is a reasonable guess, on average we saw about ~400 byte message sizes for openfmb
+        let mut buf = Vec::with_capacity(512);
+        msg.encode(&mut buf)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        let prefix_match = topic.prefix_match();
+        let subject: String = topic_to_subject(topic, prefix_match);
+        self.client
+            .publish(subject, options, buf)
+            .await
+            .map_err(PublishError::BusError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{subject_to_levels, topic_to_subject};
+    use crate::prelude::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_subject_conversion() {
+        let mrid = Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid);
+        assert_eq!(
+            topic_to_subject(topic.iter(), false),
+            "openfmb/switchmodule/SwitchStatusProfile/06fb668d-f87a-4b1b-8d99-0949513126ff"
+        );
+        let levels = vec![TopicLevel::Exact("x"), TopicLevel::WildCard];
+        assert_eq!(topic_to_subject(levels.clone().into_iter(), false), "x/+");
+        assert_eq!(topic_to_subject(levels.into_iter(), true), "x/+/#");
+        assert_eq!(
+            topic_to_subject(vec![TopicLevel::Exact("x")].into_iter(), true),
+            "x/#"
+        );
+        assert_eq!(subject_to_levels("x/y"), vec!["x", "y"]);
+    }
+
+    #[test]
+    fn test_filter_subject() {
+        let subject = |filter: TopicFilter| {
+            let levels = filter.iter();
+            let prefix_match = levels.prefix_match();
+            topic_to_subject(levels, prefix_match)
+        };
+        assert_eq!(subject(TopicFilter::openfmb()), "openfmb/+/+/+");
+        assert_eq!(
+            subject(
+                TopicFilter::openfmb()
+                    .prefix("site1.feeder7")
+                    .profile(Profile::SwitchStatusProfile)
+            ),
+            "site1/feeder7/openfmb/+/SwitchStatusProfile/+"
+        );
+    }
+}
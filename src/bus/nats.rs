@@ -4,11 +4,18 @@
 
 use crate::prelude::*;
 use async_trait::async_trait;
+use futures::channel::{mpsc, oneshot};
 use futures::prelude::*;
 use futures::StreamExt;
+use log::debug;
+use std::io;
 use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::Duration;
 
-use super::Closable;
+use super::{Closable, Replier, Requests};
 
 /// Nats Message Bus
 #[derive(Debug, Clone)]
@@ -67,6 +74,77 @@ fn subject_to_topic<'a>(subject: &'a str) -> SubjectIter<'a> {
     }
 }
 
+fn nats_headers(headers: &Headers) -> nats::header::HeaderMap {
+    let mut map = nats::header::HeaderMap::new();
+    for (name, value) in headers.iter() {
+        map.insert(name, value);
+    }
+    map
+}
+
+/// The headers of a received message, only the first value of headers
+/// given more than once is kept
+fn message_headers(msg: &nats::Message) -> Headers {
+    msg.headers
+        .iter()
+        .flat_map(|map| map.iter())
+        .filter_map(|(name, values)| {
+            values
+                .iter()
+                .next()
+                .map(|value| (name.as_str(), value.as_str()))
+        })
+        .collect()
+}
+
+/// Messages of a nats subscription as a Stream
+///
+/// Waiting on a nats::Subscription blocks, so a dedicated thread waits for
+/// messages and hands them over a channel rather than blocking the executor
+/// polling the stream. Dropping the stream unsubscribes, which also ends the
+/// thread.
+struct NatsMessages {
+    messages: mpsc::UnboundedReceiver<nats::Message>,
+    subscription: nats::Subscription,
+}
+
+impl NatsMessages {
+    fn new(subject: &str, subscription: nats::Subscription) -> io::Result<NatsMessages> {
+        let (sender, messages) = mpsc::unbounded();
+        let receiving = subscription.clone();
+        thread::Builder::new()
+            .name(format!("nats-sub {}", subject))
+            .spawn(move || {
+                // next returns None once unsubscribed or the connection closed
+                while let Some(msg) = receiving.next() {
+                    if sender.unbounded_send(msg).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        Ok(NatsMessages {
+            messages,
+            subscription,
+        })
+    }
+}
+
+impl Stream for NatsMessages {
+    type Item = nats::Message;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<nats::Message>> {
+        self.messages.poll_next_unpin(cx)
+    }
+}
+
+impl Drop for NatsMessages {
+    fn drop(&mut self) {
+        if let Err(err) = self.subscription.clone().unsubscribe() {
+            debug!("failed to unsubscribe from nats {:?}", err);
+        }
+    }
+}
+
 #[async_trait]
 impl<E, M> Subscriber<M> for NatsBus<E>
 where
@@ -80,18 +158,37 @@ where
         //debug!("subscribing to {:?}", topic);
         let subject: String = topic_to_subject(topic);
 
-        Ok(Box::pin(stream::iter(self.conn.subscribe(&subject)?).map(
-            |msg| {
-                // we can cheat here and split and map as we know the topic will not have * or >
-                let topic = subject_to_topic(&msg.subject);
-                let data: &[u8] = &msg.data;
+        let messages = NatsMessages::new(&subject, self.conn.subscribe(&subject)?)?;
+        Ok(decode_messages(messages))
+    }
 
-                M::decode(topic, data).map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
-            },
-        )))
+    /// Subscribes as a member of a NATS queue group
+    async fn subscribe_queue<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        group: &str,
+    ) -> Result<Subscription<M>, SubscribeError> {
+        let subject: String = topic_to_subject(topic);
+        let messages = NatsMessages::new(&subject, self.conn.queue_subscribe(&subject, group)?)?;
+        Ok(decode_messages(messages))
     }
 }
 
+fn decode_messages<E, M>(messages: NatsMessages) -> Subscription<M>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    Box::pin(messages.map(|msg| {
+        // we can cheat here and split and map as we know the topic will not have * or >
+        let topic = subject_to_topic(&msg.subject);
+        let data: &[u8] = &msg.data;
+
+        M::decode_with_headers(topic, &message_headers(&msg), data)
+            .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
+    }))
+}
+
 #[async_trait]
 impl<E, M> Publisher<M> for NatsBus<E>
 where
@@ -106,10 +203,133 @@ where
         msg.encode(&mut buf)
             .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
         let subject: String = topic_to_subject(topic);
+        match msg.headers().filter(|headers| !headers.is_empty()) {
+            Some(headers) => Ok(self
+                .conn
+                .publish_with_reply_or_headers(&subject, None, Some(&nats_headers(headers)), buf)
+                .map_err(PublishError::IoError)?),
+            None => Ok(self
+                .conn
+                .publish(&subject, buf)
+                .map_err(PublishError::IoError)?),
+        }
+    }
+}
+
+/// Requests are sent with a reply subject on a fresh inbox
+#[async_trait]
+impl<E, Req, Resp> Requester<Req, Resp> for NatsBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    Req: 'static + Message<E> + Send,
+    Resp: 'static + Message<E> + Send,
+{
+    async fn request<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        req: Req,
+        timeout: Duration,
+    ) -> RequestResult<Resp> {
+        let mut buf = Vec::with_capacity(512);
+        req.encode(&mut buf)
+            .map_err(|err| RequestError::EncodeError(Box::new(err)))?;
+        let subject: String = topic_to_subject(topic);
+        let headers = req
+            .headers()
+            .filter(|headers| !headers.is_empty())
+            .map(nats_headers);
+        // waiting on the reply blocks, so like subscriptions the request is
+        // made on a thread of its own rather than on the executor
+        let conn = self.conn.clone();
+        let requested = subject.clone();
+        let (sender, reply) = oneshot::channel();
+        thread::Builder::new()
+            .name(format!("nats-req {}", subject))
+            .spawn(move || {
+                let _ = sender.send(conn.request_with_headers_or_timeout(
+                    &requested,
+                    headers.as_ref(),
+                    Some(timeout),
+                    buf,
+                ));
+            })
+            .map_err(RequestError::IoError)?;
+        let msg = reply
+            .await
+            .unwrap_or_else(|_| {
+                Err(io::Error::other(
+                    "nats request thread ended without a reply",
+                ))
+            })
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::TimedOut => RequestError::Timeout(timeout),
+                io::ErrorKind::NotFound => RequestError::NoResponders,
+                _ => RequestError::IoError(err),
+            })?;
+        // replies come from an inbox, decode them as if they were published on the request subject
+        let data: &[u8] = &msg.data;
+        Resp::decode_with_headers(subject_to_topic(&subject), &message_headers(&msg), data)
+            .map_err(|err| RequestError::DecodeError(Box::new(err)))
+    }
+}
+
+struct NatsReplier<E> {
+    conn: nats::Connection,
+    reply: String,
+    encoding: PhantomData<E>,
+}
+
+#[async_trait]
+impl<E, M> Replier<M> for NatsReplier<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn reply(self: Box<Self>, msg: M) -> PublishResult<()> {
+        let mut buf = Vec::with_capacity(512);
+        msg.encode(&mut buf)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        let headers = msg
+            .headers()
+            .filter(|headers| !headers.is_empty())
+            .map(nats_headers);
         Ok(self
             .conn
-            .publish(&subject, buf)
-            .map_err(PublishError::IoError)?)
+            .publish_with_reply_or_headers(&self.reply, None, headers.as_ref(), buf)?)
+    }
+}
+
+#[async_trait]
+impl<E, Req, Resp> Responder<Req, Resp> for NatsBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    Req: 'static + Message<E> + Send,
+    Resp: 'static + Message<E> + Send,
+{
+    async fn serve<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+    ) -> Result<Requests<Req, Resp>, SubscribeError> {
+        let subject: String = topic_to_subject(topic);
+
+        let messages = NatsMessages::new(&subject, self.conn.subscribe(&subject)?)?;
+
+        let conn = self.conn.clone();
+        Ok(Box::pin(messages.filter_map(move |msg| {
+            // plain publishes have no reply subject to respond to
+            future::ready(msg.reply.clone().map(|reply| {
+                let topic = subject_to_topic(&msg.subject);
+                let data: &[u8] = &msg.data;
+                let req = Req::decode_with_headers(topic, &message_headers(&msg), data)
+                    .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))?;
+                let replier: Box<dyn Replier<Resp>> = Box::new(NatsReplier {
+                    conn: conn.clone(),
+                    reply,
+                    encoding: PhantomData::<E>,
+                });
+                Ok(Request::new(req, replier))
+            }))
+        })))
     }
 }
 
@@ -3,14 +3,83 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::prelude::*;
+use crate::topic::{prefix_levels, ROOT};
+use async_nats::jetstream::{self, consumer::pull, consumer::DeliverPolicy, stream};
 use async_trait::async_trait;
 use bytes::BytesMut;
-use futures::StreamExt;
+use futures::{future, StreamExt};
+use std::error::Error;
 use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
 
-use super::Closable;
+use super::{with_timeout, Closable, Replier, Requests};
+
+/// Where a JetStream consumer starts delivering messages from when it is
+/// first created, a durable consumer that already exists resumes where it
+/// left off instead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliverFrom {
+    /// Every message still held by the stream
+    All,
+    /// Only messages published after the consumer is created
+    New,
+    /// Messages published at or after the given time
+    Time(SystemTime),
+}
+
+impl DeliverFrom {
+    fn policy(self) -> DeliverPolicy {
+        match self {
+            DeliverFrom::All => DeliverPolicy::All,
+            DeliverFrom::New => DeliverPolicy::New,
+            DeliverFrom::Time(time) => DeliverPolicy::ByStartTime {
+                start_time: time.into(),
+            },
+        }
+    }
+}
+
+/// Name of the JetStream stream holding `<module subject>.>`, e.g.
+/// `OPENFMB_SWITCHMODULE` or `SITE-A_OPENFMB_SWITCHMODULE` with a prefix
+fn stream_name(module_subject: &str) -> String {
+    module_subject.replace('.', "_").to_uppercase()
+}
+
+/// The subject of an openfmb topic up to its module level, e.g.
+/// `openfmb.switchmodule` or `site-a.openfmb.switchmodule` with a prefix.
+/// Streams are created per module so the prefix and module must be given
+/// exactly.
+fn module_subject<S: AsRef<str>>(levels: &[TopicLevel<S>]) -> Result<String, SubscribeError> {
+    let invalid = || {
+        SubscribeError::InvalidTopic(
+            "jetstream subscriptions need a topic starting with [prefix.]openfmb.<module>"
+                .to_string(),
+        )
+    };
+    let root = levels
+        .iter()
+        .position(|lvl| matches!(lvl, TopicLevel::Exact(val) if val.as_ref() == ROOT))
+        .ok_or_else(invalid)?;
+    levels
+        .get(..root + 2)
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|lvl| match lvl {
+            TopicLevel::Exact(val) => Ok(val.as_ref()),
+            TopicLevel::WildCard => Err(invalid()),
+        })
+        .collect::<Result<Vec<&str>, SubscribeError>>()
+        .map(|levels| levels.join("."))
+}
+
+fn bus_error<T: Into<Box<dyn Error + Send + Sync>>>(err: T) -> Box<dyn Error + Send + Sync> {
+    err.into()
+}
 
 /// Nats Message Bus
+///
+/// Subscriptions use core NATS unless made through `subscribe_durable` or
+/// `replay`, which consume from a JetStream stream per module.
 #[derive(Debug, Clone)]
 pub struct NatsAsyncBus<E: MessageEncoding> {
     conn: async_nats::Client,
@@ -85,14 +154,59 @@ where
             .subscribe(subject)
             .await
             .map_err(|err| SubscribeError::BusError(Box::new(err)))?;
+        Ok(decode_messages(subscriber))
+    }
 
-        Ok(Box::pin(subscriber.map(|msg| {
-            let topic = subject_to_topic(&msg.subject);
-            let data: &[u8] = &msg.payload;
+    /// Subscribes as a member of a NATS queue group
+    async fn subscribe_queue<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        group: &str,
+    ) -> Result<Subscription<M>, SubscribeError> {
+        let subject: String = topic_to_subject(topic);
+        let subscriber = self
+            .conn
+            .queue_subscribe(subject, group.to_string())
+            .await
+            .map_err(|err| SubscribeError::BusError(Box::new(err)))?;
+        Ok(decode_messages(subscriber))
+    }
+}
 
-            M::decode(topic, data).map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
-        })))
+fn decode_messages<E, M>(subscriber: async_nats::Subscriber) -> Subscription<M>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    Box::pin(subscriber.map(|msg| {
+        let topic = subject_to_topic(&msg.subject);
+        let data: &[u8] = &msg.payload;
+
+        M::decode_with_headers(topic, &message_headers(&msg), data)
+            .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
+    }))
+}
+
+fn nats_headers(headers: &Headers) -> async_nats::HeaderMap {
+    let mut map = async_nats::HeaderMap::new();
+    for (name, value) in headers.iter() {
+        map.insert(name, value);
     }
+    map
+}
+
+/// The headers of a received message, only the first value of headers
+/// given more than once is kept
+fn message_headers(msg: &async_nats::Message) -> Headers {
+    msg.headers
+        .iter()
+        .flat_map(|map| map.iter())
+        .filter_map(|(name, values)| {
+            values
+                .first()
+                .map(|value| (name.to_string(), value.to_string()))
+        })
+        .collect()
 }
 
 #[async_trait]
@@ -109,11 +223,134 @@ where
 
         let buf = buf.freeze();
 
-        Ok(self
+        match msg.headers().filter(|headers| !headers.is_empty()) {
+            Some(headers) => Ok(self
+                .conn
+                .publish_with_headers(subject, nats_headers(headers), buf)
+                .await
+                .map_err(|err| PublishError::BusError(Box::new(err)))?),
+            None => Ok(self
+                .conn
+                .publish(subject, buf)
+                .await
+                .map_err(|err| PublishError::BusError(Box::new(err)))?),
+        }
+    }
+}
+
+/// async-nats reports a request nobody subscribed to and one that timed out
+/// as io errors, map them like NatsBus does
+fn request_error(err: async_nats::Error, timeout: Duration) -> RequestError {
+    match err.downcast_ref::<std::io::Error>().map(|err| err.kind()) {
+        Some(std::io::ErrorKind::NotFound) => RequestError::NoResponders,
+        Some(std::io::ErrorKind::TimedOut) => RequestError::Timeout(timeout),
+        _ => RequestError::BusError(bus_error(err)),
+    }
+}
+
+/// Requests are sent with a reply subject on a fresh inbox
+#[async_trait]
+impl<E, Req, Resp> Requester<Req, Resp> for NatsAsyncBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    Req: 'static + Message<E> + Send,
+    Resp: 'static + Message<E> + Send,
+{
+    async fn request<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        req: Req,
+        timeout: Duration,
+    ) -> RequestResult<Resp> {
+        let mut buf = BytesMut::new();
+        req.encode(&mut buf)
+            .map_err(|err| RequestError::EncodeError(Box::new(err)))?;
+        let subject: String = topic_to_subject(topic);
+
+        let request = match req.headers().filter(|headers| !headers.is_empty()) {
+            Some(headers) => future::Either::Left(self.conn.request_with_headers(
+                subject.clone(),
+                nats_headers(headers),
+                buf.freeze(),
+            )),
+            None => future::Either::Right(self.conn.request(subject.clone(), buf.freeze())),
+        };
+        let msg = with_timeout(Box::pin(request), timeout)
+            .await?
+            .map_err(|err| request_error(err, timeout))?;
+        // replies come from an inbox, decode them as if they were published on the request subject
+        let data: &[u8] = &msg.payload;
+        Resp::decode_with_headers(subject_to_topic(&subject), &message_headers(&msg), data)
+            .map_err(|err| RequestError::DecodeError(Box::new(err)))
+    }
+}
+
+struct NatsAsyncReplier<E> {
+    conn: async_nats::Client,
+    reply: String,
+    encoding: PhantomData<E>,
+}
+
+#[async_trait]
+impl<E, M> Replier<M> for NatsAsyncReplier<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn reply(self: Box<Self>, msg: M) -> PublishResult<()> {
+        let mut buf = BytesMut::new();
+        msg.encode(&mut buf)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        match msg.headers().filter(|headers| !headers.is_empty()) {
+            Some(headers) => self
+                .conn
+                .publish_with_headers(self.reply, nats_headers(headers), buf.freeze())
+                .await
+                .map_err(|err| PublishError::BusError(Box::new(err))),
+            None => self
+                .conn
+                .publish(self.reply, buf.freeze())
+                .await
+                .map_err(|err| PublishError::BusError(Box::new(err))),
+        }
+    }
+}
+
+#[async_trait]
+impl<E, Req, Resp> Responder<Req, Resp> for NatsAsyncBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    Req: 'static + Message<E> + Send,
+    Resp: 'static + Message<E> + Send,
+{
+    async fn serve<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+    ) -> Result<Requests<Req, Resp>, SubscribeError> {
+        let subject: String = topic_to_subject(topic);
+
+        let subscriber = self
             .conn
-            .publish(subject, buf)
+            .subscribe(subject)
             .await
-            .map_err(|err| PublishError::BusError(Box::new(err)))?)
+            .map_err(|err| SubscribeError::BusError(Box::new(err)))?;
+
+        let conn = self.conn.clone();
+        Ok(Box::pin(subscriber.filter_map(move |msg| {
+            // plain publishes have no reply subject to respond to
+            future::ready(msg.reply.clone().map(|reply| {
+                let topic = subject_to_topic(&msg.subject);
+                let data: &[u8] = &msg.payload;
+                let req = Req::decode_with_headers(topic, &message_headers(&msg), data)
+                    .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))?;
+                let replier: Box<dyn Replier<Resp>> = Box::new(NatsAsyncReplier {
+                    conn: conn.clone(),
+                    reply,
+                    encoding: PhantomData::<E>,
+                });
+                Ok(Request::new(req, replier))
+            }))
+        })))
     }
 }
 
@@ -144,13 +381,149 @@ where
             encoding: PhantomData,
         }
     }
+
+    /// Create the JetStream stream capturing everything published to
+    /// `openfmb.<module>.>` if it does not exist yet
+    ///
+    /// Only messages published while the stream exists can be replayed, so
+    /// create it ahead of time when consumers may start late.
+    pub async fn create_stream(&self, module: Module) -> Result<(), SubscribeError> {
+        self.create_prefixed_stream("", module).await
+    }
+
+    /// Create the JetStream stream capturing everything published to
+    /// `<prefix>.openfmb.<module>.>` if it does not exist yet
+    pub async fn create_prefixed_stream(
+        &self,
+        prefix: &str,
+        module: Module,
+    ) -> Result<(), SubscribeError> {
+        let mut levels = prefix_levels(prefix);
+        levels.push(ROOT.to_string());
+        levels.push(module.as_str().to_string());
+        self.module_stream(&levels.join(".")).await.map(|_| ())
+    }
+
+    /// Subscribe through a durable JetStream consumer
+    ///
+    /// The consumer is created on first use starting at `from` and remembers
+    /// which messages were acknowledged, so subscribing again with the same
+    /// name after a restart delivers everything published in the meantime.
+    pub async fn subscribe_durable<M, S, T>(
+        &mut self,
+        topic: T,
+        name: &str,
+        from: DeliverFrom,
+    ) -> SubscribeResult<M>
+    where
+        M: 'static + Message<E> + Send,
+        S: AsRef<str>,
+        T: Topic<S>,
+    {
+        self.jetstream_subscribe(topic, Some(name.to_string()), from)
+            .await
+    }
+
+    /// Replay the messages held by JetStream for a topic through an
+    /// ephemeral consumer, continuing with new messages as they arrive
+    pub async fn replay<M, S, T>(&mut self, topic: T, from: DeliverFrom) -> SubscribeResult<M>
+    where
+        M: 'static + Message<E> + Send,
+        S: AsRef<str>,
+        T: Topic<S>,
+    {
+        self.jetstream_subscribe(topic, None, from).await
+    }
+
+    async fn module_stream(&self, module_subject: &str) -> Result<stream::Stream, SubscribeError> {
+        jetstream::new(self.conn.clone())
+            .get_or_create_stream(stream::Config {
+                name: stream_name(module_subject),
+                subjects: vec![format!("{}.>", module_subject)],
+                ..Default::default()
+            })
+            .await
+            .map_err(|err| SubscribeError::BusError(bus_error(err)))
+    }
+
+    async fn jetstream_subscribe<M, S, T>(
+        &mut self,
+        topic: T,
+        durable_name: Option<String>,
+        from: DeliverFrom,
+    ) -> SubscribeResult<M>
+    where
+        M: 'static + Message<E> + Send,
+        S: AsRef<str>,
+        T: Topic<S>,
+    {
+        let prefix_match = topic.prefix_match();
+        let levels: Vec<TopicLevel<String>> = topic
+            .map(|lvl| match lvl {
+                TopicLevel::Exact(val) => TopicLevel::Exact(val.as_ref().to_string()),
+                TopicLevel::WildCard => TopicLevel::WildCard,
+            })
+            .collect();
+        let stream = self.module_stream(&module_subject(&levels)?).await?;
+        let config = pull::Config {
+            durable_name: durable_name.clone(),
+            deliver_policy: from.policy(),
+            filter_subject: topic_to_subject(TopicLevels {
+                iter: levels.into_iter(),
+                prefix_match,
+            }),
+            ..Default::default()
+        };
+        let consumer = match durable_name {
+            Some(name) => stream.get_or_create_consumer(&name, config).await,
+            None => stream.create_consumer(config).await,
+        }
+        .map_err(|err| SubscribeError::BusError(bus_error(err)))?;
+        let messages = consumer
+            .messages()
+            .await
+            .map_err(|err| SubscribeError::BusError(bus_error(err)))?;
+
+        Ok(Box::pin(messages.then(|msg| async move {
+            let msg = msg.map_err(|err| SubscriptionError::BusError(bus_error(err)))?;
+            // acknowledge before decoding, a message that fails to decode
+            // would fail again on every redelivery
+            msg.ack()
+                .await
+                .map_err(|err| SubscriptionError::BusError(bus_error(err)))?;
+            let topic = subject_to_topic(&msg.subject);
+            let data: &[u8] = &msg.payload;
+
+            M::decode_with_headers(topic, &message_headers(&msg), data)
+                .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
+        })))
+    }
+}
+
+/// Already collected topic levels as a Topic
+struct TopicLevels<I> {
+    iter: I,
+    prefix_match: bool,
+}
+
+impl<S: AsRef<str>, I: Send + Iterator<Item = TopicLevel<S>>> Iterator for TopicLevels<I> {
+    type Item = TopicLevel<S>;
+    fn next(&mut self) -> Option<TopicLevel<S>> {
+        self.iter.next()
+    }
+}
+
+impl<S: AsRef<str>, I: Send + Iterator<Item = TopicLevel<S>>> Topic<S> for TopicLevels<I> {
+    fn prefix_match(&self) -> bool {
+        self.prefix_match
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::prelude::{Topic, TopicLevel};
 
-    use super::{subject_to_topic, topic_to_subject};
+    use super::{module_subject, stream_name, subject_to_topic, topic_to_subject};
 
     #[test]
     fn test_subject_conversion() {
@@ -163,4 +536,41 @@ mod tests {
         let parts1: Vec<TopicLevel<&str>> = subject_to_topic("x.*").collect();
         assert_eq!(parts1, vec![TopicLevel::Exact("x"), TopicLevel::WildCard]);
     }
+
+    #[test]
+    fn test_stream_module() {
+        assert_eq!(stream_name("openfmb.switchmodule"), "OPENFMB_SWITCHMODULE");
+        let levels = vec![
+            TopicLevel::Exact("openfmb"),
+            TopicLevel::Exact("switchmodule"),
+            TopicLevel::WildCard,
+        ];
+        assert_eq!(module_subject(&levels).unwrap(), "openfmb.switchmodule");
+        let levels = vec![
+            TopicLevel::Exact("site-a"),
+            TopicLevel::Exact("openfmb"),
+            TopicLevel::Exact("switchmodule"),
+        ];
+        assert_eq!(
+            module_subject(&levels).unwrap(),
+            "site-a.openfmb.switchmodule"
+        );
+        assert_eq!(
+            stream_name("site-a.openfmb.switchmodule"),
+            "SITE-A_OPENFMB_SWITCHMODULE"
+        );
+        let levels = vec![TopicLevel::Exact("openfmb"), TopicLevel::WildCard];
+        assert!(module_subject(&levels).is_err());
+        let levels = vec![
+            TopicLevel::WildCard,
+            TopicLevel::Exact("openfmb"),
+            TopicLevel::Exact("switchmodule"),
+        ];
+        assert!(module_subject(&levels).is_err());
+        let levels = vec![
+            TopicLevel::Exact("other"),
+            TopicLevel::Exact("switchmodule"),
+        ];
+        assert!(module_subject(&levels).is_err());
+    }
 }
@@ -0,0 +1,387 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::SignatureError;
+use crate::prelude::*;
+use async_trait::async_trait;
+use bytes::Bytes;
+use ed25519_dalek::{Signer, Verifier};
+use futures::StreamExt;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use super::Closable;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The envelope put on the wire around every signed message
+#[derive(Clone, PartialEq, prost::Message)]
+struct SignedEnvelope {
+    #[prost(string, tag = "1")]
+    key_id: String,
+    #[prost(bytes = "bytes", tag = "2")]
+    payload: Bytes,
+    #[prost(bytes = "vec", tag = "3")]
+    signature: Vec<u8>,
+}
+
+/// Key used to sign published messages
+#[derive(Clone)]
+pub enum SigningKey {
+    /// HMAC-SHA256 with a secret shared by publishers and subscribers
+    Hmac(Vec<u8>),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+impl SigningKey {
+    pub fn hmac(secret: &[u8]) -> SigningKey {
+        SigningKey::Hmac(secret.to_vec())
+    }
+
+    pub fn ed25519(secret: &[u8; 32]) -> SigningKey {
+        SigningKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(secret))
+    }
+
+    /// The key subscribers need to verify messages signed with this key
+    pub fn verifying_key(&self) -> VerifyingKey {
+        match self {
+            SigningKey::Hmac(secret) => VerifyingKey::Hmac(secret.clone()),
+            SigningKey::Ed25519(key) => VerifyingKey::Ed25519(key.verifying_key()),
+        }
+    }
+
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            SigningKey::Hmac(secret) => {
+                let mut mac = hmac_sha256(secret);
+                mac.update(data);
+                mac.finalize().into_bytes().to_vec()
+            }
+            SigningKey::Ed25519(key) => key.sign(data).to_bytes().to_vec(),
+        }
+    }
+}
+
+/// Secrets are left out
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigningKey::Hmac(_) => f.write_str("SigningKey::Hmac(..)"),
+            SigningKey::Ed25519(_) => f.write_str("SigningKey::Ed25519(..)"),
+        }
+    }
+}
+
+/// Key used to verify received messages
+#[derive(Clone)]
+pub enum VerifyingKey {
+    Hmac(Vec<u8>),
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl VerifyingKey {
+    pub fn hmac(secret: &[u8]) -> VerifyingKey {
+        VerifyingKey::Hmac(secret.to_vec())
+    }
+
+    pub fn ed25519(public: &[u8; 32]) -> Result<VerifyingKey, ed25519_dalek::SignatureError> {
+        Ok(VerifyingKey::Ed25519(
+            ed25519_dalek::VerifyingKey::from_bytes(public)?,
+        ))
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        match self {
+            VerifyingKey::Hmac(secret) => {
+                let mut mac = hmac_sha256(secret);
+                mac.update(data);
+                mac.verify_slice(signature).is_ok()
+            }
+            VerifyingKey::Ed25519(key) => ed25519_dalek::Signature::from_slice(signature)
+                .map(|signature| key.verify(data, &signature).is_ok())
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Secrets are left out
+impl fmt::Debug for VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyingKey::Hmac(_) => f.write_str("VerifyingKey::Hmac(..)"),
+            VerifyingKey::Ed25519(key) => write!(f, "VerifyingKey::Ed25519({:?})", key.as_bytes()),
+        }
+    }
+}
+
+fn hmac_sha256(secret: &[u8]) -> HmacSha256 {
+    // hmac accepts keys of any length
+    HmacSha256::new_from_slice(secret).expect("hmac key")
+}
+
+/// The key published messages are signed with and the keys trusted to sign
+/// received messages, each known by a key id
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    signer: Option<(String, SigningKey)>,
+    trusted: HashMap<String, VerifyingKey>,
+}
+
+impl Keyring {
+    pub fn new() -> Keyring {
+        Keyring::default()
+    }
+
+    /// Sign published messages with the given key, which is trusted as well
+    pub fn sign_with<S: Into<String>>(mut self, key_id: S, key: SigningKey) -> Keyring {
+        let key_id = key_id.into();
+        self.trusted.insert(key_id.clone(), key.verifying_key());
+        self.signer = Some((key_id, key));
+        self
+    }
+
+    /// Accept messages signed with the given key
+    pub fn trust<S: Into<String>>(mut self, key_id: S, key: VerifyingKey) -> Keyring {
+        self.trusted.insert(key_id.into(), key);
+        self
+    }
+
+    fn sign(&self, topic: &TopicBuf, payload: Vec<u8>) -> Result<SignedEnvelope, SignatureError> {
+        let (key_id, key) = self.signer.as_ref().ok_or(SignatureError::NoSigningKey)?;
+        let signature = key.sign(&signed_data(topic, &payload));
+        Ok(SignedEnvelope {
+            key_id: key_id.clone(),
+            payload: Bytes::from(payload),
+            signature,
+        })
+    }
+
+    fn verify(&self, topic: &TopicBuf, envelope: &SignedEnvelope) -> Result<(), SignatureError> {
+        if envelope.key_id.is_empty() || envelope.signature.is_empty() {
+            return Err(SignatureError::Unsigned);
+        }
+        let key = self
+            .trusted
+            .get(&envelope.key_id)
+            .ok_or_else(|| SignatureError::UnknownKey(envelope.key_id.clone()))?;
+        if key.verify(&signed_data(topic, &envelope.payload), &envelope.signature) {
+            Ok(())
+        } else {
+            Err(SignatureError::Invalid(envelope.key_id.clone()))
+        }
+    }
+}
+
+/// The signature covers the topic as well as the payload so a signed message
+/// can not be replayed onto the topic of another device
+fn signed_data(topic: &TopicBuf, payload: &[u8]) -> Vec<u8> {
+    let subject = topic.canonical_subject();
+    let mut data = Vec::with_capacity(subject.len() + 1 + payload.len());
+    data.extend_from_slice(subject.as_bytes());
+    data.push(0);
+    data.extend_from_slice(payload);
+    data
+}
+
+/// A Message Bus signing every published message and rejecting received
+/// messages that are not signed by a trusted key
+///
+/// Messages are encoded with E and wrapped in an envelope holding the key id
+/// and signature, which the wrapped bus passes on as a RawMessage. Rejected
+/// messages show up as a `SubscriptionError::SignatureError` on the
+/// subscription.
+#[derive(Debug, Clone)]
+pub struct SignedBus<B, E: MessageEncoding> {
+    inner: B,
+    keyring: Arc<Keyring>,
+    encoding: PhantomData<E>,
+}
+
+impl<B, E: MessageEncoding> SignedBus<B, E> {
+    pub fn new(inner: B, keyring: Keyring) -> SignedBus<B, E> {
+        SignedBus {
+            inner,
+            keyring: Arc::new(keyring),
+            encoding: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<B, E, M> Subscriber<M> for SignedBus<B, E>
+where
+    B: Subscriber<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn subscribe<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T) -> SubscribeResult<M> {
+        let subscription = self.inner.subscribe(topic).await?;
+        Ok(self.verify(subscription))
+    }
+
+    async fn subscribe_queue<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        group: &str,
+    ) -> SubscribeResult<M> {
+        let subscription = self.inner.subscribe_queue(topic, group).await?;
+        Ok(self.verify(subscription))
+    }
+}
+
+impl<B, E> SignedBus<B, E>
+where
+    E: 'static + MessageEncoding + Send,
+{
+    fn verify<M>(&self, subscription: Subscription<RawMessage>) -> Subscription<M>
+    where
+        M: 'static + Message<E> + Send,
+    {
+        let keyring = self.keyring.clone();
+        Box::pin(subscription.map(move |raw| {
+            let raw = raw?;
+            let envelope = <SignedEnvelope as prost::Message>::decode(raw.payload)
+                .map_err(|_err| SubscriptionError::SignatureError(SignatureError::Unsigned))?;
+            keyring
+                .verify(&raw.topic, &envelope)
+                .map_err(SubscriptionError::SignatureError)?;
+            M::decode_with_headers(raw.topic.iter(), &raw.headers, envelope.payload)
+                .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
+        }))
+    }
+}
+
+#[async_trait]
+impl<B, E, M> Publisher<M> for SignedBus<B, E>
+where
+    B: Publisher<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn publish<S: AsRef<str>, T: Topic<S>>(&mut self, topic: T, msg: M) -> PublishResult<()> {
+        let topic = TopicBuf::from_topic(topic);
+        let mut payload = Vec::with_capacity(512);
+        msg.encode(&mut payload)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        let envelope = self
+            .keyring
+            .sign(&topic, payload)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        let mut buf = Vec::with_capacity(prost::Message::encoded_len(&envelope));
+        prost::Message::encode(&envelope, &mut buf)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        self.inner
+            .publish(
+                topic.iter(),
+                RawMessage::new(topic.clone(), Bytes::from(buf))
+                    .with_headers(msg.headers().cloned().unwrap_or_default()),
+            )
+            .await
+    }
+}
+
+/// A signed bus implements the MessageBus trait
+impl<B, E, M> MessageBus<M> for SignedBus<B, E>
+where
+    B: MessageBus<RawMessage> + Send,
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+}
+
+impl<B, E, C> Closable<C> for SignedBus<B, E>
+where
+    B: Closable<C>,
+    E: MessageEncoding,
+{
+    fn close(&self) {
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Keyring, SignedBus, SigningKey, VerifyingKey};
+    use crate::bus::LocalBus;
+    use crate::encoding::ProtobufEncoding;
+    use crate::error::SignatureError;
+    use crate::prelude::*;
+    use futures::StreamExt;
+    use openfmb_messages::{switchmodule::SwitchDiscreteControlProfile, ProfileMessage};
+    use uuid::Uuid;
+
+    fn topic() -> ProfileTopic {
+        let mrid = Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+        ProfileTopic::new(
+            Module::SwitchModule,
+            Profile::SwitchDiscreteControlProfile,
+            mrid,
+        )
+    }
+
+    async fn receive(
+        publisher: Keyring,
+        subscriber: Keyring,
+    ) -> Result<SwitchDiscreteControlProfile, SubscriptionError> {
+        let local = LocalBus::<ProtobufEncoding>::new();
+        let mut publisher = SignedBus::<_, ProtobufEncoding>::new(local.clone(), publisher);
+        let mut subscriber = SignedBus::<_, ProtobufEncoding>::new(local, subscriber);
+        let mut sub: Subscription<SwitchDiscreteControlProfile> =
+            subscriber.subscribe(topic().iter()).await.unwrap();
+        publisher
+            .publish(topic().iter(), SwitchDiscreteControlProfile::default())
+            .await
+            .unwrap();
+        sub.next().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_hmac() {
+        let key = SigningKey::hmac(b"secret");
+        let keyring = Keyring::new().sign_with("a", key.clone());
+        assert!(receive(keyring.clone(), keyring).await.is_ok());
+
+        let other = Keyring::new().trust("a", VerifyingKey::hmac(b"other"));
+        let res = receive(Keyring::new().sign_with("a", key), other).await;
+        assert!(matches!(
+            res,
+            Err(SubscriptionError::SignatureError(SignatureError::Invalid(id))) if id == "a"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ed25519() {
+        let key = SigningKey::ed25519(&[7; 32]);
+        let trusted = Keyring::new().trust("a", key.verifying_key());
+        assert!(receive(Keyring::new().sign_with("a", key.clone()), trusted)
+            .await
+            .is_ok());
+
+        let res = receive(Keyring::new().sign_with("b", key), Keyring::new()).await;
+        assert!(matches!(
+            res,
+            Err(SubscriptionError::SignatureError(SignatureError::UnknownKey(id))) if id == "b"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unsigned() {
+        let mut local = LocalBus::<ProtobufEncoding>::new();
+        let keyring = Keyring::new().trust("a", VerifyingKey::hmac(b"secret"));
+        let mut bus = SignedBus::<_, ProtobufEncoding>::new(local.clone(), keyring);
+        let mut sub: Subscription<ProfileMessage> = bus.subscribe(topic().iter()).await.unwrap();
+        local
+            .publish(topic().iter(), SwitchDiscreteControlProfile::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            sub.next().await.unwrap(),
+            Err(SubscriptionError::SignatureError(SignatureError::Unsigned))
+        ));
+    }
+}
@@ -4,27 +4,125 @@
 
 use crate::prelude::*;
 use async_trait::async_trait;
-use futures::StreamExt;
-use std::convert::TryInto;
+use futures::channel::{mpsc, oneshot};
+use futures::future::Either;
+use futures::{future, Future, Stream, StreamExt};
+use log::debug;
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+use std::io;
 use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::Duration;
 use zenoh::*;
 
-use lazy_static::lazy_static;
+use super::{with_timeout, Closable, Replier, Requests};
 
-lazy_static! {
-    static ref ZENOH: Zenoh = Zenoh::new(net::config::default()).wait().unwrap();
-    static ref WORKSPACE: Workspace<'static> = ZENOH
-        .workspace(Some("/".try_into().unwrap()))
-        .wait()
-        .unwrap();
+/// Selector property carrying the hex encoded request, zenoh queries have no
+/// payload of their own
+const REQUEST_PROPERTY: &str = "request";
+
+/// Zenoh session configuration
+///
+/// Locators use the zenoh format, e.g. `tcp/10.0.0.1:7447`. Anything left
+/// unset keeps the zenoh default, a peer using multicast scouting.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ZenohConfig {
+    mode: Option<String>,
+    peers: Vec<String>,
+    listeners: Vec<String>,
+    multicast_scouting: Option<bool>,
+}
+
+impl ZenohConfig {
+    /// Run the session as a peer
+    pub fn peer() -> ZenohConfig {
+        ZenohConfig {
+            mode: Some("peer".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Run the session as a client of a router
+    pub fn client() -> ZenohConfig {
+        ZenohConfig {
+            mode: Some("client".to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Peers or routers to connect to
+    pub fn peers<I, S>(mut self, locators: I) -> ZenohConfig
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.peers.extend(locators.into_iter().map(Into::into));
+        self
+    }
+
+    /// Endpoints to listen on for incoming connections
+    pub fn listeners<I, S>(mut self, locators: I) -> ZenohConfig
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.listeners.extend(locators.into_iter().map(Into::into));
+        self
+    }
+
+    /// Enable or disable multicast scouting of other peers
+    pub fn multicast_scouting(mut self, enabled: bool) -> ZenohConfig {
+        self.multicast_scouting = Some(enabled);
+        self
+    }
+
+    fn properties(&self) -> Properties {
+        let mut props = Properties::default();
+        if let Some(mode) = &self.mode {
+            props.insert("mode".to_string(), mode.clone());
+        }
+        if !self.peers.is_empty() {
+            props.insert("peer".to_string(), self.peers.join(","));
+        }
+        if !self.listeners.is_empty() {
+            props.insert("listener".to_string(), self.listeners.join(","));
+        }
+        if let Some(enabled) = self.multicast_scouting {
+            props.insert("multicast_scouting".to_string(), enabled.to_string());
+        }
+        props
+    }
 }
 
 /// Zenoh Message Bus
-#[derive(Debug, Clone)]
+///
+/// The session is opened on the first publish or subscribe, failing to open
+/// it is reported as that call's error and retried on the next one. Cloned
+/// buses share the session, each `connect` opens an independent one, which
+/// is closed once the buses and the subscriptions using it are dropped or
+/// the bus is closed.
+///
+/// This zenoh version has no attachments, so message Headers are neither
+/// published nor received.
+#[derive(Clone)]
 pub struct ZenohBus<E: MessageEncoding> {
+    config: ZenohConfig,
+    session: Arc<Mutex<Option<Arc<Zenoh>>>>,
     encoding: PhantomData<E>,
 }
 
+impl<E: MessageEncoding> fmt::Debug for ZenohBus<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZenohBus")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
 fn topic_to_subject<S: AsRef<str>, T: Topic<S>>(topic: T) -> String {
     let prefix_match = topic.prefix_match();
     let mut subject = String::with_capacity(128);
@@ -76,6 +174,105 @@ fn subject_to_topic<'a>(subject: &'a zenoh::Path) -> SubjectIter<'a> {
     }
 }
 
+/// Items of a zenoh stream as a Stream owning the session
+///
+/// Streams of this zenoh version borrow the session, so a dedicated thread
+/// holding the session opens the stream and hands its items over a channel.
+/// Dropping the stream ends the thread, releasing the session.
+struct SessionStream<T> {
+    items: mpsc::UnboundedReceiver<T>,
+    _cancel: oneshot::Sender<()>,
+}
+
+impl<T: 'static + Send> SessionStream<T> {
+    /// Runs `open` on the thread, which should open the stream with the
+    /// session and `forward` it, and waits for the stream to be opened
+    async fn spawn<F, Fut>(
+        subject: &str,
+        zenoh: Arc<Zenoh>,
+        open: F,
+    ) -> Result<SessionStream<T>, SubscribeError>
+    where
+        F: 'static + Send + FnOnce(Arc<Zenoh>, Forward<T>) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let (sender, items) = mpsc::unbounded();
+        let (cancel, cancelled) = oneshot::channel();
+        let (opened, opening) = oneshot::channel();
+        let forward = Forward {
+            opened,
+            items: sender,
+            cancelled,
+        };
+        thread::Builder::new()
+            .name(format!("zenoh-sub {}", subject))
+            .spawn(move || futures::executor::block_on(open(zenoh, forward)))?;
+        match opening.await {
+            Ok(result) => result?,
+            Err(_) => {
+                return Err(SubscribeError::IoError(io::Error::other(
+                    "zenoh stream thread ended before opening the stream",
+                )))
+            }
+        }
+        Ok(SessionStream {
+            items,
+            _cancel: cancel,
+        })
+    }
+}
+
+impl<T> Stream for SessionStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.items.poll_next_unpin(cx)
+    }
+}
+
+/// The thread side of a SessionStream
+struct Forward<T> {
+    opened: oneshot::Sender<ZResult<()>>,
+    items: mpsc::UnboundedSender<T>,
+    cancelled: oneshot::Receiver<()>,
+}
+
+impl<T> Forward<T> {
+    fn fail(self, err: ZError) {
+        let _ = self.opened.send(Err(err));
+    }
+
+    /// Reports whether the stream opened, then sends its items until it
+    /// ends or the SessionStream is dropped
+    async fn forward<S: Stream<Item = T>>(self, stream: ZResult<S>) {
+        let Forward {
+            opened,
+            items,
+            mut cancelled,
+        } = self;
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                let _ = opened.send(Err(err));
+                return;
+            }
+        };
+        let _ = opened.send(Ok(()));
+        let mut stream = Box::pin(stream);
+        while let Either::Left((Some(item), _)) =
+            future::select(stream.next(), &mut cancelled).await
+        {
+            if items.unbounded_send(item).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+async fn workspace(zenoh: &Zenoh) -> ZResult<Workspace<'_>> {
+    zenoh.workspace(Some("/".try_into()?)).await
+}
+
 #[async_trait]
 impl<E, M> Subscriber<M> for ZenohBus<E>
 where
@@ -88,28 +285,165 @@ where
     ) -> Result<Subscription<M>, SubscribeError> {
         //debug!("subscribing to {:?}", topic);
         let subject: String = topic_to_subject(topic);
-        let stream = WORKSPACE
-            .subscribe(&subject.try_into().unwrap())
-            .await
-            .map_err(|err| SubscribeError::BusError(Box::new(err)))?;
-        Ok(Box::pin(stream.map(|change| {
-            let topic = subject_to_topic(&change.path);
-            let mut raw = vec![];
-            let data: &[u8] = match change.value {
-                Some(val) => match val {
-                    Value::Raw(_c, mut buf) => {
-                        raw = buf.read_vec();
-                        &raw
-                    }
-                    _ => &raw,
-                },
-                _ => &raw,
+        let zenoh = self.session().await?;
+        let selector = subject.clone();
+        let stream = SessionStream::spawn(&subject, zenoh, |zenoh, forward| async move {
+            let workspace = match workspace(&zenoh).await {
+                Ok(workspace) => workspace,
+                Err(err) => return forward.fail(err),
             };
+            let changes = match selector.try_into() {
+                Ok(selector) => workspace.subscribe(&selector).await,
+                Err(err) => Err(err),
+            };
+            forward.forward(changes).await
+        })
+        .await?;
+        Ok(Box::pin(stream.map(|change: Change| {
+            let topic = subject_to_topic(&change.path);
+            let raw = change.value.map(raw_value).unwrap_or_default();
+            let data: &[u8] = &raw;
             M::decode(topic, data).map_err(|err| SubscriptionError::DecodeError(Box::new(err)))
         })))
     }
 }
 
+fn raw_value(value: Value) -> Vec<u8> {
+    match value {
+        Value::Raw(_c, mut buf) => buf.read_vec(),
+        _ => vec![],
+    }
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect()
+}
+
+/// Requests are zenoh queries answered by the first responding queryable
+#[async_trait]
+impl<E, Req, Resp> Requester<Req, Resp> for ZenohBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    Req: 'static + Message<E> + Send,
+    Resp: 'static + Message<E> + Send,
+{
+    async fn request<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+        req: Req,
+        timeout: Duration,
+    ) -> RequestResult<Resp> {
+        let mut buf = Vec::with_capacity(512);
+        req.encode(&mut buf)
+            .map_err(|err| RequestError::EncodeError(Box::new(err)))?;
+        let subject: String = topic_to_subject(topic);
+        let selector: Selector =
+            format!("{}?({}={})", subject, REQUEST_PROPERTY, to_hex(&buf)).try_into()?;
+        let zenoh = self.session().await?;
+        let workspace = workspace(&zenoh).await?;
+        let mut replies = Box::pin(workspace.get(&selector).await?);
+        let reply = with_timeout(replies.next(), timeout)
+            .await?
+            .ok_or(RequestError::NoResponders)?;
+        let topic = subject_to_topic(&reply.path);
+        let raw = raw_value(reply.value);
+        let data: &[u8] = &raw;
+        Resp::decode(topic, data).map_err(|err| RequestError::DecodeError(Box::new(err)))
+    }
+}
+
+struct ZenohReplier<E> {
+    request: GetRequest,
+    path: Path,
+    encoding: PhantomData<E>,
+}
+
+#[async_trait]
+impl<E, M> Replier<M> for ZenohReplier<E>
+where
+    E: 'static + MessageEncoding + Send,
+    M: 'static + Message<E> + Send,
+{
+    async fn reply(self: Box<Self>, msg: M) -> PublishResult<()> {
+        let mut buf = Vec::with_capacity(512);
+        msg.encode(&mut buf)
+            .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
+        let ZenohReplier { request, path, .. } = *self;
+        request.reply(path, buf.into()).await;
+        Ok(())
+    }
+}
+
+/// Requests are served by registering a queryable (an eval) on the topic
+#[async_trait]
+impl<E, Req, Resp> Responder<Req, Resp> for ZenohBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+    Req: 'static + Message<E> + Send,
+    Resp: 'static + Message<E> + Send,
+{
+    async fn serve<S: AsRef<str>, T: Topic<S>>(
+        &mut self,
+        topic: T,
+    ) -> Result<Requests<Req, Resp>, SubscribeError> {
+        let subject: String = topic_to_subject(topic);
+        let zenoh = self.session().await?;
+        let path = subject.clone();
+        let requests = SessionStream::spawn(&subject, zenoh, |zenoh, forward| async move {
+            let workspace = match workspace(&zenoh).await {
+                Ok(workspace) => workspace,
+                Err(err) => return forward.fail(err),
+            };
+            let requests = match path.try_into() {
+                Ok(path) => workspace.register_eval(&path).await,
+                Err(err) => Err(err),
+            };
+            forward.forward(requests).await
+        })
+        .await?;
+        Ok(Box::pin(requests.filter_map(|request: GetRequest| {
+            // plain gets carry no request to decode
+            let data = request
+                .selector
+                .properties
+                .get(REQUEST_PROPERTY)
+                .map(|hex| from_hex(hex));
+            future::ready(data.map(|data| {
+                let data = data.ok_or_else(|| {
+                    SubscriptionError::DecodeError(Box::new(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "request property is not hex encoded",
+                    )))
+                })?;
+                let path = Path::try_from(request.selector.path_expr.to_string())
+                    .map_err(|err| SubscriptionError::BusError(Box::new(err)))?;
+                let data: &[u8] = &data;
+                let req = Req::decode(subject_to_topic(&path), data)
+                    .map_err(|err| SubscriptionError::DecodeError(Box::new(err)))?;
+                let replier: Box<dyn Replier<Resp>> = Box::new(ZenohReplier {
+                    request,
+                    path,
+                    encoding: PhantomData::<E>,
+                });
+                Ok(Request::new(req, replier))
+            }))
+        })))
+    }
+}
+
 #[async_trait]
 impl<E, M> Publisher<M> for ZenohBus<E>
 where
@@ -124,7 +458,9 @@ where
         msg.encode(&mut buf)
             .map_err(|err| PublishError::EncodeError(Box::new(err)))?;
         let subject: String = topic_to_subject(topic);
-        Ok(WORKSPACE.put(&subject.try_into()?, buf.into()).await?)
+        let zenoh = self.session().await?;
+        let workspace = workspace(&zenoh).await?;
+        Ok(workspace.put(&subject.try_into()?, buf.into()).await?)
     }
 }
 
@@ -136,22 +472,70 @@ where
 {
 }
 
+impl<E> Closable<E> for ZenohBus<E>
+where
+    E: 'static + MessageEncoding + Send,
+{
+    /// Releases the session of this bus and its clones, the next publish or
+    /// subscribe opens a new one. Subscriptions still open keep the session
+    /// until they are dropped.
+    fn close(&self) {
+        let session = self
+            .session
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        if let Some(Ok(zenoh)) = session.map(Arc::try_unwrap) {
+            if let Err(err) = futures::executor::block_on(zenoh.close()) {
+                debug!("failed to close zenoh session {:?}", err);
+            }
+        }
+    }
+}
+
 impl<E> ZenohBus<E>
 where
     E: 'static + MessageEncoding + Send,
 {
+    /// Zenoh bus using the default session configuration
     pub fn new() -> ZenohBus<E> {
+        ZenohBus::connect(ZenohConfig::default())
+    }
+
+    /// Zenoh bus with its own session using the given configuration
+    pub fn connect(config: ZenohConfig) -> ZenohBus<E> {
         ZenohBus {
+            config,
+            session: Arc::new(Mutex::new(None)),
             encoding: PhantomData,
         }
     }
+
+    async fn session(&self) -> ZResult<Arc<Zenoh>> {
+        if let Some(zenoh) = self
+            .session
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+        {
+            return Ok(zenoh);
+        }
+        let opened = Arc::new(Zenoh::new(self.config.properties().into()).await?);
+        // a session opened meanwhile by a clone wins, dropping this one
+        Ok(self
+            .session
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get_or_insert(opened)
+            .clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::prelude::{Topic, TopicLevel};
 
-    use super::{subject_to_topic, topic_to_subject};
+    use super::{from_hex, subject_to_topic, to_hex, topic_to_subject, ZenohConfig};
 
     #[test]
     fn test_subject_conversion() {
@@ -172,4 +556,36 @@ mod tests {
         let parts0: Vec<TopicLevel<&str>> = subject_to_topic(&path).collect();
         assert_eq!(parts0, vec![TopicLevel::Exact("x"), TopicLevel::Exact("y")]);
     }
+
+    #[test]
+    fn test_config_properties() {
+        let props = ZenohConfig::client()
+            .peers(vec!["tcp/10.0.0.1:7447", "tcp/10.0.0.2:7447"])
+            .listeners(vec!["tcp/0.0.0.0:7448"])
+            .multicast_scouting(false)
+            .properties();
+        assert_eq!(props.get("mode").map(String::as_str), Some("client"));
+        assert_eq!(
+            props.get("peer").map(String::as_str),
+            Some("tcp/10.0.0.1:7447,tcp/10.0.0.2:7447")
+        );
+        assert_eq!(
+            props.get("listener").map(String::as_str),
+            Some("tcp/0.0.0.0:7448")
+        );
+        assert_eq!(
+            props.get("multicast_scouting").map(String::as_str),
+            Some("false")
+        );
+        assert!(ZenohConfig::default().properties().is_empty());
+    }
+
+    #[test]
+    fn test_hex() {
+        let data = vec![0x00, 0x0a, 0xff, 0x42];
+        assert_eq!(to_hex(&data), "000aff42");
+        assert_eq!(from_hex("000aff42"), Some(data));
+        assert_eq!(from_hex("000"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
 }
@@ -0,0 +1,486 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Recording of bus traffic to capture files and replaying it onto a bus
+//!
+//! A capture file starts with the `OFMBCAP1` magic followed by one record per
+//! message, each holding the receive time in microseconds since the unix
+//! epoch, the subject levels joined by `.` and the raw message bytes. Numbers
+//! and lengths are written as protobuf varints. Records are only ever
+//! appended so a capture may be extended by recording into it again.
+//!
+//! ```ignore
+//! let mut recorder = Recorder::append("incident.ofc")?;
+//! record(&mut bus, topic, &mut recorder).await?;
+//!
+//! let reader = CaptureReader::open("incident.ofc")?;
+//! let filter = ReplayFilter::new().module(Module::SwitchModule);
+//! replay(&mut bus, reader, Speed::Scaled(10.0), &filter).await?;
+//! ```
+
+use crate::prelude::*;
+use bytes::Bytes;
+use futures::StreamExt;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// Marks the start of a capture file, the trailing digit is the format version
+const MAGIC: &[u8; 8] = b"OFMBCAP1";
+
+/// A single captured message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub time: SystemTime,
+    pub topic: TopicBuf,
+    pub payload: Bytes,
+}
+
+/// A common capture error type
+#[derive(Debug)]
+pub enum CaptureError {
+    IoError(io::Error),
+    InvalidCapture(String),
+    SubscribeError(SubscribeError),
+    SubscriptionError(SubscriptionError),
+    PublishError(PublishError),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for CaptureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            CaptureError::IoError(ref err) => Some(err),
+            CaptureError::SubscribeError(ref err) => Some(err),
+            CaptureError::SubscriptionError(ref err) => Some(err),
+            CaptureError::PublishError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CaptureError {
+    fn from(err: io::Error) -> CaptureError {
+        CaptureError::IoError(err)
+    }
+}
+
+/// Type alias for a capture result
+pub type CaptureResult<T> = Result<T, CaptureError>;
+
+fn write_varint<W: Write>(writer: &mut W, value: u64) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(10);
+    prost::encoding::encode_varint(value, &mut buf);
+    writer.write_all(&buf)
+}
+
+/// Reads a varint, None at a clean end of the file
+fn read_varint<R: Read>(reader: &mut R) -> CaptureResult<Option<u64>> {
+    let mut value = 0u64;
+    for i in 0..10 {
+        let mut byte = [0u8];
+        if reader.read(&mut byte)? == 0 {
+            return if i == 0 {
+                Ok(None)
+            } else {
+                Err(CaptureError::InvalidCapture("truncated record".to_string()))
+            };
+        }
+        value |= u64::from(byte[0] & 0x7f) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    Err(CaptureError::InvalidCapture("invalid varint".to_string()))
+}
+
+/// Writes records to a capture
+#[derive(Debug)]
+pub struct Recorder<W: Write> {
+    writer: W,
+}
+
+impl Recorder<BufWriter<File>> {
+    /// Create a new capture file, replacing any existing one
+    pub fn create<P: AsRef<Path>>(path: P) -> CaptureResult<Self> {
+        Recorder::new(BufWriter::new(File::create(path)?))
+    }
+
+    /// Append to a capture file, creating it if it does not exist yet
+    pub fn append<P: AsRef<Path>>(path: P) -> CaptureResult<Self> {
+        let path = path.as_ref();
+        if path.exists() && std::fs::metadata(path)?.len() > 0 {
+            // make sure we are appending to a capture and not something else
+            CaptureReader::open(path)?;
+            let file = OpenOptions::new().append(true).open(path)?;
+            Ok(Recorder {
+                writer: BufWriter::new(file),
+            })
+        } else {
+            Recorder::create(path)
+        }
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Start a new capture on the writer
+    pub fn new(mut writer: W) -> CaptureResult<Self> {
+        writer.write_all(MAGIC)?;
+        Ok(Recorder { writer })
+    }
+
+    /// Append a record, it is flushed right away so a capture survives the
+    /// recording process being killed
+    pub fn write(&mut self, record: &Record) -> CaptureResult<()> {
+        let micros = record
+            .time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        let subject = record.topic.to_string();
+        write_varint(&mut self.writer, micros)?;
+        write_varint(&mut self.writer, subject.len() as u64)?;
+        self.writer.write_all(subject.as_bytes())?;
+        write_varint(&mut self.writer, record.payload.len() as u64)?;
+        self.writer.write_all(&record.payload)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads the records of a capture in the order they were recorded
+#[derive(Debug)]
+pub struct CaptureReader<R: Read> {
+    reader: R,
+}
+
+impl CaptureReader<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> CaptureResult<Self> {
+        CaptureReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    pub fn new(mut reader: R) -> CaptureResult<Self> {
+        let mut magic = [0u8; 8];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|_err| CaptureError::InvalidCapture("missing header".to_string()))?;
+        if &magic != MAGIC {
+            return Err(CaptureError::InvalidCapture(
+                "not an openfmb capture".to_string(),
+            ));
+        }
+        Ok(CaptureReader { reader })
+    }
+
+    fn read_bytes(&mut self, len: u64) -> CaptureResult<Vec<u8>> {
+        let mut buf = Vec::new();
+        (&mut self.reader).take(len).read_to_end(&mut buf)?;
+        if (buf.len() as u64) < len {
+            return Err(CaptureError::InvalidCapture("truncated record".to_string()));
+        }
+        Ok(buf)
+    }
+
+    fn read_record(&mut self) -> CaptureResult<Option<Record>> {
+        let micros = match read_varint(&mut self.reader)? {
+            Some(micros) => micros,
+            None => return Ok(None),
+        };
+        let truncated = || CaptureError::InvalidCapture("truncated record".to_string());
+        let subject_len = read_varint(&mut self.reader)?.ok_or_else(truncated)?;
+        let subject = String::from_utf8(self.read_bytes(subject_len)?)
+            .map_err(|_err| CaptureError::InvalidCapture("invalid subject".to_string()))?;
+        let payload_len = read_varint(&mut self.reader)?.ok_or_else(truncated)?;
+        let payload = self.read_bytes(payload_len)?;
+        Ok(Some(Record {
+            time: UNIX_EPOCH + Duration::from_micros(micros),
+            topic: TopicBuf::from_subject(&subject),
+            payload: Bytes::from(payload),
+        }))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = CaptureResult<Record>;
+
+    fn next(&mut self) -> Option<CaptureResult<Record>> {
+        self.read_record().transpose()
+    }
+}
+
+/// Record every message received on a topic until the subscription ends
+pub async fn record<B, S, T, W>(
+    bus: &mut B,
+    topic: T,
+    recorder: &mut Recorder<W>,
+) -> CaptureResult<()>
+where
+    B: Subscriber<RawMessage>,
+    S: AsRef<str>,
+    T: Topic<S>,
+    W: Write,
+{
+    let mut subscription = bus
+        .subscribe(topic)
+        .await
+        .map_err(CaptureError::SubscribeError)?;
+    while let Some(msg) = subscription.next().await {
+        let msg = msg.map_err(CaptureError::SubscriptionError)?;
+        recorder.write(&Record {
+            time: SystemTime::now(),
+            topic: msg.topic,
+            payload: msg.payload,
+        })?;
+    }
+    Ok(())
+}
+
+/// How fast a capture is replayed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Speed {
+    /// With the time between messages as recorded
+    Original,
+    /// Faster (above 1.0) or slower (below 1.0) than recorded, as Max for a
+    /// factor that is not above 0.0 or so small the gaps overflow a Duration
+    Scaled(f64),
+    /// As fast as the bus accepts messages
+    Max,
+}
+
+/// Selects the records replayed, each given module, profile or mRID list
+/// must contain the one of the record's topic
+#[derive(Debug, Clone, Default)]
+pub struct ReplayFilter {
+    modules: Vec<Module>,
+    profiles: Vec<Profile>,
+    mrids: Vec<String>,
+}
+
+impl ReplayFilter {
+    /// A filter passing everything
+    pub fn new() -> ReplayFilter {
+        ReplayFilter::default()
+    }
+
+    pub fn module(mut self, module: Module) -> ReplayFilter {
+        self.modules.push(module);
+        self
+    }
+
+    pub fn profile(mut self, profile: Profile) -> ReplayFilter {
+        self.profiles.push(profile);
+        self
+    }
+
+    pub fn mrid(mut self, mrid: Uuid) -> ReplayFilter {
+        self.mrids
+            .push(mrid.as_hyphenated().to_string().to_lowercase());
+        self
+    }
+
+    /// Topics are expected as `openfmb.<module>.<profile>.<mrid>`, any
+    /// prefix before the root is ignored
+    pub fn matches(&self, topic: &TopicBuf) -> bool {
+        let levels: Vec<&str> = topic
+            .root_levels()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|lvl| match lvl {
+                TopicLevel::Exact(val) => Some(val.as_str()),
+                TopicLevel::WildCard => None,
+            })
+            .collect();
+        let level_in = |idx: usize, allowed: &[&str]| {
+            allowed.is_empty() || levels.get(idx).is_some_and(|lvl| allowed.contains(lvl))
+        };
+        let modules: Vec<&str> = self.modules.iter().map(|m| m.as_str()).collect();
+        let profiles: Vec<&str> = self.profiles.iter().map(|p| p.as_str()).collect();
+        let mrids: Vec<&str> = self.mrids.iter().map(String::as_str).collect();
+        level_in(1, &modules) && level_in(2, &profiles) && level_in(3, &mrids)
+    }
+}
+
+/// Publish the records of a capture that pass the filter onto a bus,
+/// returning the number of messages published
+pub async fn replay<B, I>(
+    bus: &mut B,
+    records: I,
+    speed: Speed,
+    filter: &ReplayFilter,
+) -> CaptureResult<usize>
+where
+    B: Publisher<RawMessage>,
+    I: IntoIterator<Item = CaptureResult<Record>>,
+{
+    let started = Instant::now();
+    let mut first: Option<SystemTime> = None;
+    let mut published = 0;
+    for record in records {
+        let record = record?;
+        if !filter.matches(&record.topic) {
+            continue;
+        }
+        let recorded_at = record
+            .time
+            .duration_since(*first.get_or_insert(record.time))
+            .unwrap_or_default();
+        let offset = match speed {
+            Speed::Original => Some(recorded_at),
+            Speed::Scaled(factor) if factor > 0.0 => {
+                Duration::try_from_secs_f64(recorded_at.as_secs_f64() / factor).ok()
+            }
+            _ => None,
+        };
+        if let Some(offset) = offset {
+            // sleeping for the remainder rather than the gap keeps the
+            // replay from drifting behind the recording
+            if let Some(wait) = offset.checked_sub(started.elapsed()) {
+                futures_timer::Delay::new(wait).await;
+            }
+        }
+        let raw = RawMessage::new(record.topic, record.payload);
+        bus.publish(raw.topic.clone().iter(), raw)
+            .await
+            .map_err(CaptureError::PublishError)?;
+        published += 1;
+    }
+    Ok(published)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay, CaptureError, CaptureReader, Record, Recorder, ReplayFilter, Speed};
+    use crate::bus::LocalBus;
+    use crate::encoding::ProtobufEncoding;
+    use crate::prelude::*;
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use std::time::{Duration, Instant, UNIX_EPOCH};
+    use uuid::Uuid;
+
+    fn mrid() -> Uuid {
+        Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap()
+    }
+
+    fn record(millis: u64, module: Module, profile: Profile) -> Record {
+        let topic = ProfileTopic::new(module, profile, mrid());
+        Record {
+            time: UNIX_EPOCH + Duration::from_millis(1_600_000_000_000 + millis),
+            topic: TopicBuf::from_topic(topic.iter()),
+            payload: Bytes::from(vec![8, millis as u8]),
+        }
+    }
+
+    fn capture(records: &[Record]) -> Vec<u8> {
+        let mut recorder = Recorder::new(Vec::new()).unwrap();
+        for record in records {
+            recorder.write(record).unwrap();
+        }
+        recorder.into_inner()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let records = vec![
+            record(0, Module::SwitchModule, Profile::SwitchStatusProfile),
+            record(250, Module::EssModule, Profile::ESSEventProfile),
+        ];
+        let buf = capture(&records);
+        let read: Vec<Record> = CaptureReader::new(&buf[..])
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(read, records);
+
+        assert!(matches!(
+            CaptureReader::new(&b"not a capture"[..]),
+            Err(CaptureError::InvalidCapture(_))
+        ));
+        let truncated: Vec<_> = CaptureReader::new(&buf[..buf.len() - 1]).unwrap().collect();
+        assert!(truncated[0].is_ok());
+        assert!(matches!(truncated[1], Err(CaptureError::InvalidCapture(_))));
+    }
+
+    #[test]
+    fn test_filter() {
+        let status = record(0, Module::SwitchModule, Profile::SwitchStatusProfile);
+        assert!(ReplayFilter::new().matches(&status.topic));
+        assert!(ReplayFilter::new()
+            .module(Module::SwitchModule)
+            .mrid(mrid())
+            .matches(&status.topic));
+        assert!(!ReplayFilter::new()
+            .module(Module::SwitchModule)
+            .profile(Profile::SwitchReadingProfile)
+            .matches(&status.topic));
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let records = vec![
+            record(0, Module::SwitchModule, Profile::SwitchStatusProfile),
+            record(100, Module::EssModule, Profile::ESSEventProfile),
+            record(200, Module::SwitchModule, Profile::SwitchStatusProfile),
+        ];
+        let buf = capture(&records);
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let mut sub: Subscription<RawMessage> = bus.subscribe(topic.iter()).await.unwrap();
+
+        let filter = ReplayFilter::new().module(Module::SwitchModule);
+        let started = Instant::now();
+        let reader = CaptureReader::new(&buf[..]).unwrap();
+        let published = replay(&mut bus, reader, Speed::Scaled(2.0), &filter)
+            .await
+            .unwrap();
+        assert_eq!(published, 2);
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_eq!(
+            sub.next().await.unwrap().unwrap().payload,
+            records[0].payload
+        );
+        assert_eq!(
+            sub.next().await.unwrap().unwrap().payload,
+            records[2].payload
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_invalid_factor() {
+        let records = vec![
+            record(0, Module::SwitchModule, Profile::SwitchStatusProfile),
+            record(200, Module::SwitchModule, Profile::SwitchStatusProfile),
+        ];
+        let buf = capture(&records);
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        // replayed as Max: the gap divided by the first overflows a Duration,
+        // the others are not above 0.0
+        for factor in [f64::MIN_POSITIVE, f64::NAN, -1.0] {
+            let reader = CaptureReader::new(&buf[..]).unwrap();
+            let published = replay(
+                &mut bus,
+                reader,
+                Speed::Scaled(factor),
+                &ReplayFilter::new(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(published, 2);
+        }
+    }
+}
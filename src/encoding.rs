@@ -10,5 +10,123 @@ pub trait MessageEncoding: std::fmt::Debug {
     type DecodeError: Send + std::error::Error;
 }
 
+use crate::topic::{Profile, Topic, TopicLevel, ROOT};
+use std::str::FromStr;
+
+/// The profile of a message from its topic, for decoding a ProfileMessage
+/// whose payload does not tell which profile it holds. The profile is two
+/// levels after the root, skipping any prefix before it.
+///
+/// Fails with None if the topic has no profile level, or why the level is
+/// not a profile.
+pub(crate) fn topic_profile<S: AsRef<str>, T: Topic<S>>(
+    topic: T,
+) -> Result<Profile, Option<String>> {
+    let mut from_root =
+        topic.skip_while(|lvl| !matches!(lvl, TopicLevel::Exact(root) if root.as_ref() == ROOT));
+    match from_root.nth(2) {
+        Some(TopicLevel::Exact(profile)) => Profile::from_str(profile.as_ref())
+            .map_err(|_err| Some(format!("{:?}", profile.as_ref()))),
+        Some(TopicLevel::WildCard) => Err(Some(
+            "Wildcard given for last topic level, not convertable to an OpenFMB Profile"
+                .to_string(),
+        )),
+        None => Err(None),
+    }
+}
+
 mod protobufs;
-pub use protobufs::{ProtobufEncoding, ProtobufVariantDecodeError, ProtobufVariantEncoding};
+#[allow(deprecated)]
+pub use protobufs::{
+    ProtobufDecodeError, ProtobufEncoding, ProtobufVariantDecodeError, ProtobufVariantEncoding,
+};
+
+#[cfg(feature = "dynamic")]
+mod dynamic;
+#[cfg(feature = "dynamic")]
+pub use dynamic::decode_dynamic;
+
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+pub use json::{JsonDecodeError, JsonEncoding};
+
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "cbor")]
+pub use cbor::{CborDecodeError, CborEncoding};
+
+#[cfg(feature = "msgpack")]
+mod msgpack;
+#[cfg(feature = "msgpack")]
+pub use msgpack::{MsgPackDecodeError, MsgPackEncoding};
+
+/// Round trip shared by the tests of the encodings
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::bus::LocalBus;
+    use crate::prelude::*;
+    use futures::StreamExt;
+    use openfmb_messages::{
+        commonmodule::{
+            EngGridConnectModeKind, GridConnectModeKind, MessageInfo, Mv, StatusMessageInfo,
+            Timestamp,
+        },
+        essmodule::{EssStatus, EssStatusProfile, EssStatusZbat},
+        ProfileMessage,
+    };
+    use uuid::Uuid;
+
+    /// Publishes a profile over a LocalBus with the encoding and receives it
+    /// both typed and as a ProfileMessage
+    pub(crate) async fn round_trip<E>()
+    where
+        E: 'static + MessageEncoding + Send,
+        EssStatusProfile: Message<E>,
+        ProfileMessage: Message<E>,
+    {
+        let mrid = Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+        let topic = ProfileTopic::new(Module::EssModule, Profile::ESSStatusProfile, mrid);
+        let msg = EssStatusProfile {
+            status_message_info: Some(StatusMessageInfo {
+                message_info: Some(MessageInfo {
+                    message_time_stamp: Some(Timestamp {
+                        seconds: u64::MAX,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            }),
+            ess_status: Some(EssStatus {
+                ess_status_zbat: Some(EssStatusZbat {
+                    gri_mod: Some(EngGridConnectModeKind {
+                        set_val: GridConnectModeKind::VcVsi as i32,
+                        ..Default::default()
+                    }),
+                    soc: Some(Mv {
+                        mag: 87.5,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut bus = LocalBus::<E>::new();
+        let mut sub: Subscription<EssStatusProfile> = bus.subscribe(topic.iter()).await.unwrap();
+        let mut profiles: Subscription<ProfileMessage> = bus.subscribe(topic.iter()).await.unwrap();
+        bus.publish(topic.iter(), msg.clone()).await.unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap(), msg);
+        assert_eq!(
+            profiles.next().await.unwrap().unwrap(),
+            ProfileMessage::ESSStatusProfile(msg)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_protobuf_round_trip() {
+        round_trip::<super::ProtobufEncoding>().await;
+    }
+}
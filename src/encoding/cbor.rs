@@ -0,0 +1,101 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use super::topic_profile;
+use crate::prelude::*;
+use bytes::{Buf, BufMut};
+use openfmb_messages::{ProfileDecoder, ProfileMessage};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// CBOR (RFC 8949) encoding of the generated messages, self-describing so
+/// a payload can be inspected without the proto files
+///
+/// Fields are keyed by their proto3 JSON names, enums are written as their
+/// numbers. Like ProtobufEncoding it handles ProfileMessage as well, whose
+/// CBOR is that of the profile held, which is told by the topic.
+#[derive(Debug, Clone)]
+pub enum CborEncoding {}
+
+#[derive(Debug)]
+pub enum CborDecodeError {
+    CborError(ciborium::de::Error<std::io::Error>),
+    InvalidTopic,
+    InvalidProfile(String),
+}
+
+impl std::fmt::Display for CborDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for CborDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            CborDecodeError::CborError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ciborium::de::Error<std::io::Error>> for CborDecodeError {
+    fn from(err: ciborium::de::Error<std::io::Error>) -> CborDecodeError {
+        CborDecodeError::CborError(err)
+    }
+}
+
+impl MessageEncoding for CborEncoding {
+    type DecodeError = CborDecodeError;
+    type EncodeError = ciborium::ser::Error<std::io::Error>;
+}
+
+impl<M: TypedMessage + Serialize + DeserializeOwned + Send> Message<CborEncoding> for M {
+    fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), ciborium::ser::Error<std::io::Error>> {
+        ciborium::ser::into_writer(self, buf.writer())
+    }
+
+    fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(_topic: T, buf: B) -> Result<M, CborDecodeError> {
+        Ok(ciborium::de::from_reader(buf.reader())?)
+    }
+}
+
+/// ciborium has no public Deserializer to give to
+/// ProfileMessage::deserialize_profile, so the profile is decoded by type
+struct CborProfile<B>(B);
+
+impl<B: Buf> ProfileDecoder for CborProfile<B> {
+    type Error = ciborium::de::Error<std::io::Error>;
+
+    fn decode<M: DeserializeOwned>(self) -> Result<M, Self::Error> {
+        ciborium::de::from_reader(self.0.reader())
+    }
+}
+
+impl Message<CborEncoding> for ProfileMessage {
+    fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), ciborium::ser::Error<std::io::Error>> {
+        ciborium::ser::into_writer(self, buf.writer())
+    }
+
+    fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        topic: T,
+        buf: B,
+    ) -> Result<ProfileMessage, CborDecodeError> {
+        let profile = topic_profile(topic).map_err(|err| match err {
+            Some(reason) => CborDecodeError::InvalidProfile(reason),
+            None => CborDecodeError::InvalidTopic,
+        })?;
+        Ok(ProfileMessage::decode_profile(profile, CborProfile(buf))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CborEncoding;
+    use crate::encoding::tests::round_trip;
+
+    #[tokio::test]
+    async fn test_cbor_round_trip() {
+        round_trip::<CborEncoding>().await;
+    }
+}
@@ -0,0 +1,195 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{ProtobufDecodeError, ProtobufEncoding};
+use crate::prelude::*;
+use crate::topic::ROOT;
+use bytes::{Buf, BufMut};
+use openfmb_descriptors::dynamic::{DescriptorPool, DynamicMessage};
+use std::sync::Arc;
+
+/// Full name of the message type on a topic from its module and profile
+/// levels, which need not be known to this crate
+fn topic_type_name<S: AsRef<str>, T: Topic<S>>(topic: T) -> Option<String> {
+    let mut from_root =
+        topic.skip_while(|lvl| !matches!(lvl, TopicLevel::Exact(root) if root.as_ref() == ROOT));
+    match (from_root.next(), from_root.next(), from_root.next()) {
+        (Some(_root), Some(TopicLevel::Exact(module)), Some(TopicLevel::Exact(profile))) => {
+            Some(format!("{}.{}", module.as_ref(), profile.as_ref()))
+        }
+        _ => None,
+    }
+}
+
+/// Decode the protobuf payload of a message on a topic with the given pool
+/// rather than the global one, e.g. for the peers of one bus that run a
+/// newer schema, by subscribing for RawMessage and decoding each with it
+pub fn decode_dynamic<S: AsRef<str>, T: Topic<S>, B: Buf>(
+    pool: Arc<DescriptorPool>,
+    topic: T,
+    buf: B,
+) -> Result<DynamicMessage, ProtobufDecodeError> {
+    let type_name = topic_type_name(topic).ok_or(ProtobufDecodeError::InvalidTopic)?;
+    let mut msg = DynamicMessage::new(pool, &type_name)
+        .ok_or(ProtobufDecodeError::InvalidProfile(type_name))?;
+    prost::Message::merge(&mut msg, buf)?;
+    Ok(msg)
+}
+
+/// Protobuf encoding of a message decoded with the global DescriptorPool,
+/// so any profile it knows may be received without generated code
+impl Message<ProtobufEncoding> for DynamicMessage {
+    fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), prost::EncodeError> {
+        prost::Message::encode(self, buf)
+    }
+
+    fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        topic: T,
+        buf: B,
+    ) -> Result<DynamicMessage, ProtobufDecodeError> {
+        decode_dynamic(DescriptorPool::global(), topic, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_dynamic;
+    use crate::bus::LocalBus;
+    use crate::encoding::{ProtobufDecodeError, ProtobufEncoding};
+    use crate::prelude::*;
+    use bytes::Bytes;
+    use futures::StreamExt;
+    use openfmb_descriptors::dynamic::{DescriptorPool, DynamicMessage, Value};
+    use openfmb_descriptors::proto_types::{
+        field_descriptor_proto::Type, DescriptorProto, FieldDescriptorProto, FileDescriptorProto,
+        FileDescriptorSet,
+    };
+    use openfmb_messages::{
+        commonmodule::{
+            DynamicTestKind, EnsDynamicTestKind, IdentifiedObject, MessageInfo, StatusMessageInfo,
+            Timestamp,
+        },
+        switchmodule::{SwitchStatus, SwitchStatusProfile, SwitchStatusXswi},
+    };
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    fn mrid() -> Uuid {
+        Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_message() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let status = SwitchStatusProfile {
+            status_message_info: Some(StatusMessageInfo {
+                message_info: Some(MessageInfo {
+                    identified_object: Some(IdentifiedObject {
+                        name: Some("feeder 7".to_string()),
+                        ..Default::default()
+                    }),
+                    message_time_stamp: Some(Timestamp {
+                        seconds: 1_600_000_000,
+                        ..Default::default()
+                    }),
+                }),
+            }),
+            switch_status: Some(SwitchStatus {
+                switch_status_xswi: Some(SwitchStatusXswi {
+                    dynamic_test: Some(EnsDynamicTestKind {
+                        st_val: DynamicTestKind::Testing as i32,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let mut sub: Subscription<DynamicMessage> =
+            bus.subscribe(TopicFilter::openfmb().iter()).await.unwrap();
+        bus.publish(topic.iter(), status.clone()).await.unwrap();
+        let msg = sub.next().await.unwrap().unwrap();
+
+        assert_eq!(msg.type_name(), "switchmodule.SwitchStatusProfile");
+        assert_eq!(
+            msg.get_raw_path("statusMessageInfo.messageInfo.messageTimeStamp.seconds"),
+            Some(&Value::U64(1_600_000_000))
+        );
+        assert_eq!(
+            msg.get_raw_path("statusMessageInfo.messageInfo.identifiedObject.name.value"),
+            Some(&Value::String("feeder 7".to_string()))
+        );
+        assert_eq!(
+            msg.get_raw_path("switchStatus.switchStatusXSWI.dynamicTest.stVal"),
+            Some(&Value::Enum(DynamicTestKind::Testing as i32))
+        );
+        assert_eq!(msg.get_raw_path("switchStatus.switchStatusXSWI.Pos"), None);
+        assert_eq!(
+            msg.fields().map(|(name, _)| name).collect::<Vec<_>>(),
+            vec!["statusMessageInfo", "switchStatus"]
+        );
+        assert!(msg
+            .to_string()
+            .contains("DynamicTest { stVal: DynamicTestKind_testing }"));
+
+        // encoding again gives the bytes of the generated message
+        assert_eq!(
+            prost::Message::encode_to_vec(&msg),
+            prost::Message::encode_to_vec(&status)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_newer_schema() {
+        let field = FieldDescriptorProto {
+            name: Some("level".to_string()),
+            json_name: Some("level".to_string()),
+            number: Some(1),
+            r#type: Some(Type::Uint32 as i32),
+            ..Default::default()
+        };
+        let set = FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("futuremodule/futuremodule.proto".to_string()),
+                package: Some("futuremodule".to_string()),
+                message_type: vec![DescriptorProto {
+                    name: Some("FutureProfile".to_string()),
+                    field: vec![field],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        // a pool of its own, the global one is shared by the other tests
+        let mut pool = DescriptorPool::openfmb();
+        pool.add(&set);
+        let pool = Arc::new(pool);
+
+        let topic =
+            TopicBuf::from_subject(&format!("openfmb.futuremodule.FutureProfile.{}", mrid()));
+        let mut bus = LocalBus::<ProtobufEncoding>::new();
+        let mut sub: Subscription<RawMessage> = bus.subscribe(topic.iter()).await.unwrap();
+        // field 1 set to 42 and field 2, which even this schema does not know
+        let payload = Bytes::from_static(&[0x08, 42, 0x10, 1]);
+        bus.publish(topic.iter(), RawMessage::new(topic.clone(), payload))
+            .await
+            .unwrap();
+        let raw = sub.next().await.unwrap().unwrap();
+        match decode_dynamic(
+            DescriptorPool::global(),
+            raw.topic.iter(),
+            raw.payload.clone(),
+        ) {
+            Err(ProtobufDecodeError::InvalidProfile(name)) => {
+                assert_eq!(name, "futuremodule.FutureProfile")
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let msg = decode_dynamic(pool, raw.topic.iter(), raw.payload).unwrap();
+        assert_eq!(msg.get("level"), Some(&Value::U32(42)));
+        assert_eq!(msg.to_string(), "futuremodule.FutureProfile { level: 42 }");
+    }
+}
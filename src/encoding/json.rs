@@ -0,0 +1,188 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use super::topic_profile;
+use crate::prelude::*;
+use bytes::{Buf, BufMut};
+use openfmb_messages::ProfileMessage;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// JSON encoding following the proto3 JSON mapping, e.g. for web tools
+/// that do not speak protobuf
+///
+/// Fields are named in camelCase as in the proto files, enums are written
+/// as their value names and 64-bit integers as strings. Messages convert to
+/// and from ProtobufEncoding without loss. Like ProtobufEncoding it handles
+/// every TypedMessage as well as ProfileMessage, whose JSON is that of the
+/// profile held, which is told by the topic.
+#[derive(Debug, Clone)]
+pub enum JsonEncoding {}
+
+#[derive(Debug)]
+pub enum JsonDecodeError {
+    JsonError(serde_json::Error),
+    InvalidTopic,
+    InvalidProfile(String),
+}
+
+impl std::fmt::Display for JsonDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for JsonDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            JsonDecodeError::JsonError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for JsonDecodeError {
+    fn from(err: serde_json::Error) -> JsonDecodeError {
+        JsonDecodeError::JsonError(err)
+    }
+}
+
+impl MessageEncoding for JsonEncoding {
+    type DecodeError = JsonDecodeError;
+    type EncodeError = serde_json::Error;
+}
+
+impl<M: TypedMessage + Serialize + DeserializeOwned + Send> Message<JsonEncoding> for M {
+    fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(buf.writer(), self)
+    }
+
+    fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(_topic: T, buf: B) -> Result<M, JsonDecodeError> {
+        Ok(serde_json::from_reader(buf.reader())?)
+    }
+}
+
+impl Message<JsonEncoding> for ProfileMessage {
+    fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(buf.writer(), self)
+    }
+
+    fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        topic: T,
+        buf: B,
+    ) -> Result<ProfileMessage, JsonDecodeError> {
+        let profile = topic_profile(topic).map_err(|err| match err {
+            Some(reason) => JsonDecodeError::InvalidProfile(reason),
+            None => JsonDecodeError::InvalidTopic,
+        })?;
+        let mut deserializer = serde_json::Deserializer::from_reader(buf.reader());
+        let msg = ProfileMessage::deserialize_profile(profile, &mut deserializer)?;
+        deserializer.end()?;
+        Ok(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonEncoding;
+    use crate::encoding::ProtobufEncoding;
+    use crate::prelude::*;
+    use openfmb_messages::{
+        commonmodule::{
+            DynamicTestKind, EnsDynamicTestKind, MessageInfo, StatusMessageInfo, Timestamp,
+        },
+        switchmodule::{SwitchStatus, SwitchStatusProfile, SwitchStatusXswi},
+        ProfileMessage,
+    };
+    use uuid::Uuid;
+
+    fn topic() -> ProfileTopic {
+        let mrid = Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+        ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid)
+    }
+
+    fn profile() -> SwitchStatusProfile {
+        SwitchStatusProfile {
+            status_message_info: Some(StatusMessageInfo {
+                message_info: Some(MessageInfo {
+                    message_time_stamp: Some(Timestamp {
+                        seconds: 1_600_000_000,
+                        nanoseconds: 5,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            }),
+            switch_status: Some(SwitchStatus {
+                switch_status_xswi: Some(SwitchStatusXswi {
+                    dynamic_test: Some(EnsDynamicTestKind {
+                        st_val: DynamicTestKind::Testing as i32,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_json_mapping() {
+        let msg = profile();
+        let mut buf = Vec::new();
+        Message::<JsonEncoding>::encode(&msg, &mut buf).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        let timestamp = &json["statusMessageInfo"]["messageInfo"]["messageTimeStamp"];
+        assert_eq!(timestamp["seconds"], "1600000000");
+        assert_eq!(timestamp["nanoseconds"], 5);
+        assert_eq!(
+            json["switchStatus"]["switchStatusXSWI"]["DynamicTest"]["stVal"],
+            "DynamicTestKind_testing"
+        );
+
+        let decoded: SwitchStatusProfile =
+            Message::<JsonEncoding>::decode(topic().iter(), &buf[..]).unwrap();
+        assert_eq!(decoded, msg);
+
+        let mut json_protobuf = Vec::new();
+        let mut protobuf = Vec::new();
+        Message::<ProtobufEncoding>::encode(&decoded, &mut json_protobuf).unwrap();
+        Message::<ProtobufEncoding>::encode(&msg, &mut protobuf).unwrap();
+        assert_eq!(json_protobuf, protobuf);
+    }
+
+    #[test]
+    fn test_json_lenient_decode() {
+        let json = br#"{
+            "statusMessageInfo": {"messageInfo": {"messageTimeStamp": {"seconds": 1600000000, "nanoseconds": null}}},
+            "switchStatus": {"switchStatusXSWI": {"DynamicTest": {"stVal": 2}}}
+        }"#;
+        let decoded: SwitchStatusProfile =
+            Message::<JsonEncoding>::decode(topic().iter(), &json[..]).unwrap();
+        let mut expected = profile();
+        expected
+            .status_message_info
+            .as_mut()
+            .and_then(|info| info.message_info.as_mut())
+            .and_then(|info| info.message_time_stamp.as_mut())
+            .unwrap()
+            .nanoseconds = 0;
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn test_json_variant() {
+        let msg = ProfileMessage::SwitchStatusProfile(profile());
+        let mut buf = Vec::new();
+        Message::<JsonEncoding>::encode(&msg, &mut buf).unwrap();
+        let decoded: ProfileMessage =
+            Message::<JsonEncoding>::decode(topic().iter(), &buf[..]).unwrap();
+        assert_eq!(decoded, msg);
+
+        let wildcard = TopicFilter::openfmb();
+        assert!(Message::<JsonEncoding>::decode(wildcard.iter(), &buf[..])
+            .map(|_: ProfileMessage| ())
+            .is_err());
+    }
+}
@@ -0,0 +1,96 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use super::topic_profile;
+use crate::prelude::*;
+use bytes::{Buf, BufMut};
+use openfmb_messages::ProfileMessage;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// MessagePack encoding of the generated messages, for peers with a
+/// MessagePack library at hand but no protobuf code generation
+///
+/// Messages are written as maps keyed by their proto3 JSON names. Unset
+/// fields are left out and enums are written as their numbers. Like
+/// ProtobufEncoding it handles ProfileMessage as well, told by the topic.
+#[derive(Debug, Clone)]
+pub enum MsgPackEncoding {}
+
+#[derive(Debug)]
+pub enum MsgPackDecodeError {
+    MsgPackError(rmp_serde::decode::Error),
+    InvalidTopic,
+    InvalidProfile(String),
+}
+
+impl std::fmt::Display for MsgPackDecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for MsgPackDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            MsgPackDecodeError::MsgPackError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<rmp_serde::decode::Error> for MsgPackDecodeError {
+    fn from(err: rmp_serde::decode::Error) -> MsgPackDecodeError {
+        MsgPackDecodeError::MsgPackError(err)
+    }
+}
+
+impl MessageEncoding for MsgPackEncoding {
+    type DecodeError = MsgPackDecodeError;
+    type EncodeError = rmp_serde::encode::Error;
+}
+
+impl<M: TypedMessage + Serialize + DeserializeOwned + Send> Message<MsgPackEncoding> for M {
+    fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), rmp_serde::encode::Error> {
+        rmp_serde::encode::write_named(&mut buf.writer(), self)
+    }
+
+    fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        _topic: T,
+        buf: B,
+    ) -> Result<M, MsgPackDecodeError> {
+        Ok(rmp_serde::decode::from_read(buf.reader())?)
+    }
+}
+
+impl Message<MsgPackEncoding> for ProfileMessage {
+    fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), rmp_serde::encode::Error> {
+        rmp_serde::encode::write_named(&mut buf.writer(), self)
+    }
+
+    fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        topic: T,
+        buf: B,
+    ) -> Result<ProfileMessage, MsgPackDecodeError> {
+        let profile = topic_profile(topic).map_err(|err| match err {
+            Some(reason) => MsgPackDecodeError::InvalidProfile(reason),
+            None => MsgPackDecodeError::InvalidTopic,
+        })?;
+        let mut deserializer = rmp_serde::Deserializer::new(buf.reader());
+        Ok(ProfileMessage::deserialize_profile(
+            profile,
+            &mut deserializer,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MsgPackEncoding;
+    use crate::encoding::tests::round_trip;
+
+    #[tokio::test]
+    async fn test_msgpack_round_trip() {
+        round_trip::<MsgPackEncoding>().await;
+    }
+}
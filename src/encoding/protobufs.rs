@@ -2,83 +2,87 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use super::topic_profile;
 use crate::prelude::*;
 use bytes::{Buf, BufMut};
-use std::str::FromStr;
+use openfmb_messages::ProfileMessage;
 
-/// Sadly we need a type alias until specialization lands in stable
-/// to do what we really want, which is create another impl Message for the variant ProfileMessage type
-///
-/// So instead this alias is used for the time being. This forces *two* buses to be created if you wish to have type specific
-/// subscribes/publishes or use the variant containing ProfileMessage type
+/// ProtobufEncoding (zero sized, non-constructable type)
 ///
-/// TODO: remove this type alias when specialization lands
+/// Encodes every TypedMessage as well as the ProfileMessage variant enum,
+/// whose profile is told by the topic, so a single bus may publish typed
+/// profiles and subscribe to any profile as a ProfileMessage.
 #[derive(Debug, Clone)]
-pub enum ProtobufVariantEncoding {}
+pub enum ProtobufEncoding {}
+
+/// Before ProtobufEncoding handled ProfileMessage as well a separate
+/// encoding, and so a separate bus, was needed for it
+#[deprecated(note = "ProtobufEncoding handles ProfileMessage as well")]
+pub type ProtobufVariantEncoding = ProtobufEncoding;
 
 #[derive(Debug, Clone)]
-pub enum ProtobufVariantDecodeError {
+pub enum ProtobufDecodeError {
     ProstDecodeError(prost::DecodeError),
     InvalidTopic,
     InvalidProfile(String),
 }
 
-impl std::fmt::Display for ProtobufVariantDecodeError {
+#[deprecated(note = "renamed to ProtobufDecodeError")]
+pub type ProtobufVariantDecodeError = ProtobufDecodeError;
+
+impl std::fmt::Display for ProtobufDecodeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
-impl std::error::Error for ProtobufVariantDecodeError {}
+impl std::error::Error for ProtobufDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            ProtobufDecodeError::ProstDecodeError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
-impl MessageEncoding for ProtobufVariantEncoding {
-    type DecodeError = ProtobufVariantDecodeError;
+impl From<prost::DecodeError> for ProtobufDecodeError {
+    fn from(err: prost::DecodeError) -> ProtobufDecodeError {
+        ProtobufDecodeError::ProstDecodeError(err)
+    }
+}
+
+impl MessageEncoding for ProtobufEncoding {
+    type DecodeError = ProtobufDecodeError;
     type EncodeError = prost::EncodeError;
 }
 
-/// Protobuf encoding for the ProfileMessage variant enum
-impl Message<ProtobufVariantEncoding> for openfmb_messages::ProfileMessage {
+impl<M: TypedMessage + prost::Message + Default> Message<ProtobufEncoding> for M {
     fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), prost::EncodeError> {
-        openfmb_messages::ProfileMessage::encode(self, buf)
+        M::encode(self, buf)
     }
 
     fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(
-        mut topic: T,
+        _topic: T,
         buf: B,
-    ) -> Result<openfmb_messages::ProfileMessage, ProtobufVariantDecodeError> {
-        // maybe decode and validate the topic here into a ProfileTopic instead?
-        let profile = if let Some(last) = topic.nth(2) {
-            match last  {
-                TopicLevel::Exact(profile_str) =>  Profile::from_str(profile_str.as_ref()).map_err(|_err| ProtobufVariantDecodeError::InvalidProfile(format!("{:?}", profile_str.as_ref())))?,
-                TopicLevel::WildCard => return Err(ProtobufVariantDecodeError::InvalidProfile(format!("Wildcard given for last topic level, not convertable to an OpenFMB Profile"))),
-            }
-        } else {
-            // NOTE uses a hidden API here
-            return Err(ProtobufVariantDecodeError::InvalidTopic);
-        };
-        openfmb_messages::ProfileMessage::decode(profile, buf)
-            .map_err(|err| ProtobufVariantDecodeError::ProstDecodeError(err))
+    ) -> Result<M, ProtobufDecodeError> {
+        Ok(M::decode(buf)?)
     }
 }
 
-/// ProtobufEncoding (zero sized, non-constructable type)
-#[derive(Debug, Clone)]
-pub enum ProtobufEncoding {}
-
-impl MessageEncoding for ProtobufEncoding {
-    type DecodeError = prost::DecodeError;
-    type EncodeError = prost::EncodeError;
-}
-
-impl<M: prost::Message + Default> Message<ProtobufEncoding> for M {
+/// Protobuf encoding for the ProfileMessage variant enum
+impl Message<ProtobufEncoding> for ProfileMessage {
     fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), prost::EncodeError> {
-        M::encode(self, buf)
+        ProfileMessage::encode(self, buf)
     }
 
     fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(
-        _topic: T,
+        topic: T,
         buf: B,
-    ) -> Result<M, prost::DecodeError> {
-        M::decode(buf)
+    ) -> Result<ProfileMessage, ProtobufDecodeError> {
+        let profile = topic_profile(topic).map_err(|err| match err {
+            Some(reason) => ProtobufDecodeError::InvalidProfile(reason),
+            None => ProtobufDecodeError::InvalidTopic,
+        })?;
+        Ok(ProfileMessage::decode(profile, buf)?)
     }
 }
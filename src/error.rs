@@ -13,6 +13,7 @@ pub enum PublishError {
     IoError(std::io::Error),
     EncodeError(Box<dyn Error>),
     BusError(Box<dyn Error>),
+    InvalidTopic(String),
 }
 
 impl fmt::Display for PublishError {
@@ -27,6 +28,7 @@ impl Error for PublishError {
             PublishError::IoError(ref err) => Some(err),
             PublishError::EncodeError(ref err) => Some(&**err),
             PublishError::BusError(ref err) => Some(&**err),
+            _ => None,
         }
     }
 }
@@ -47,6 +49,7 @@ pub enum SubscriptionError {
     IoError(std::io::Error),
     DecodeError(Box<(dyn Error + Send)>),
     BusError(Box<(dyn Error + Send)>),
+    SignatureError(SignatureError),
     Unsubscribed,
 }
 
@@ -62,6 +65,7 @@ impl Error for SubscriptionError {
             SubscriptionError::IoError(ref err) => Some(err),
             SubscriptionError::DecodeError(ref err) => Some(&**err),
             SubscriptionError::BusError(ref err) => Some(&**err),
+            SubscriptionError::SignatureError(ref err) => Some(err),
             _ => None,
         }
     }
@@ -73,6 +77,49 @@ impl From<std::io::Error> for SubscriptionError {
     }
 }
 
+/// Reasons a signed message was rejected or could not be signed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The message carried no signature
+    Unsigned,
+    /// The message was signed with a key that is not trusted
+    UnknownKey(String),
+    /// The signature does not match the message and topic for the key
+    Invalid(String),
+    /// A message was published without a key to sign it with
+    NoSigningKey,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for SignatureError {}
+
+/// Reasons a topic is not a valid profile topic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopicError {
+    /// The topic has a wildcard or is prefix matching
+    NotExact,
+    /// The topic has this many levels, fewer than the four of a profile topic
+    LevelCount(usize),
+    /// The level four from the end, following any prefix, is not `openfmb`
+    InvalidRoot(String),
+    UnknownModule(String),
+    UnknownProfile(String),
+    InvalidMrid(String),
+}
+
+impl fmt::Display for TopicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for TopicError {}
+
 /// A common publish error type with erased enclosed error types, useful
 /// for matching on and correcting issues potentially.
 #[derive(Debug)]
@@ -80,6 +127,7 @@ pub enum SubscribeError {
     IoError(std::io::Error),
     BusError(Box<dyn Error>),
     InvalidTopic(String),
+    Unsupported(String),
 }
 
 impl fmt::Display for SubscribeError {
@@ -110,12 +158,52 @@ pub type Subscription<T> = BoxStream<'static, Result<T, SubscriptionError>>;
 /// Type alias for a publish result
 pub type SubscribeResult<T, E = SubscribeError> = Result<Subscription<T>, E>;
 
+/// A common request error type with erased enclosed error types
+#[derive(Debug)]
+pub enum RequestError {
+    IoError(std::io::Error),
+    EncodeError(Box<dyn Error>),
+    DecodeError(Box<dyn Error>),
+    BusError(Box<dyn Error>),
+    InvalidTopic(String),
+    NoResponders,
+    Timeout(Duration),
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for RequestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            RequestError::IoError(ref err) => Some(err),
+            RequestError::EncodeError(ref err) => Some(&**err),
+            RequestError::DecodeError(ref err) => Some(&**err),
+            RequestError::BusError(ref err) => Some(&**err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for RequestError {
+    fn from(err: std::io::Error) -> RequestError {
+        RequestError::IoError(err)
+    }
+}
+
+/// Type alias for a request result
+pub type RequestResult<T, E = RequestError> = Result<T, E>;
+
 /// A commonly useful Error type for control functions
 #[derive(Debug)]
 pub enum ControlError {
     PublishError(PublishError),
     SubscribeError(SubscribeError),
     SubscriptionError(SubscriptionError),
+    RequestError(RequestError),
     RetryError(usize),
     TimeoutError(Duration),
 }
@@ -132,6 +220,7 @@ impl Error for ControlError {
             ControlError::PublishError(ref err) => Some(err),
             ControlError::SubscribeError(ref err) => Some(err),
             ControlError::SubscriptionError(ref err) => Some(err),
+            ControlError::RequestError(ref err) => Some(err),
             _ => None,
         }
     }
@@ -155,6 +244,12 @@ impl From<SubscriptionError> for ControlError {
     }
 }
 
+impl From<RequestError> for ControlError {
+    fn from(err: RequestError) -> ControlError {
+        ControlError::RequestError(err)
+    }
+}
+
 /// Type alias for a control result
 pub type ControlResult<T, E = ControlError> = Result<T, E>;
 
@@ -171,3 +266,10 @@ impl From<zenoh::ZError> for PublishError {
         PublishError::BusError(Box::new(err))
     }
 }
+
+#[cfg(feature = "zenoh")]
+impl From<zenoh::ZError> for RequestError {
+    fn from(err: zenoh::ZError) -> RequestError {
+        RequestError::BusError(Box::new(err))
+    }
+}
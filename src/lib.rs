@@ -60,23 +60,31 @@
 //! }
 //! ```
 
+pub mod bridge;
 pub mod bus;
+pub mod capture;
 #[cfg(feature = "client")]
 pub mod client;
 pub mod device;
 pub mod encoding;
 pub mod error;
 pub mod message;
+#[cfg(feature = "dynamic")]
+pub use openfmb_descriptors as descriptors;
 pub use openfmb_messages as messages;
+#[cfg(feature = "dynamic")]
+pub mod path;
 pub mod prelude {
     //! Exports very commonly used types and traits
-    pub use crate::bus::{MessageBus, Publisher, Subscriber};
+    pub use crate::bus::{MessageBus, Publisher, Request, Requester, Responder, Subscriber};
     pub use crate::encoding::MessageEncoding;
     pub use crate::error::{
-        ControlError, ControlResult, PublishError, PublishResult, SubscribeError, SubscribeResult,
-        Subscription, SubscriptionError,
+        ControlError, ControlResult, PublishError, PublishResult, RequestError, RequestResult,
+        SubscribeError, SubscribeResult, Subscription, SubscriptionError,
+    };
+    pub use crate::message::{Headers, Message, RawMessage, TypedMessage, WithHeaders};
+    pub use crate::topic::{
+        Module, Profile, ProfileTopic, Topic, TopicBuf, TopicFilter, TopicLevel,
     };
-    pub use crate::message::Message;
-    pub use crate::topic::{Module, Profile, ProfileTopic, Topic, TopicLevel};
 }
 pub mod topic;
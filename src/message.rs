@@ -3,8 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::encoding::MessageEncoding;
-use crate::topic::Topic;
-use bytes::{Buf, BufMut};
+use crate::topic::{Topic, TopicBuf};
+use bytes::{Buf, BufMut, Bytes};
+use std::collections::BTreeMap;
 
 /// A Message provides functionality to encode/decode (serialize/deserialize)
 /// into something that acts like the Buf or BufMut interfaces provided by
@@ -17,4 +18,256 @@ pub trait Message<E: MessageEncoding>: Sized + Send {
     fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), E::EncodeError>;
     fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(topic: T, buf: B)
         -> Result<Self, E::DecodeError>;
+
+    /// Headers to publish along with the encoded message, none by default
+    fn headers(&self) -> Option<&Headers> {
+        None
+    }
+
+    /// Decode a message received along with headers, which are ignored by
+    /// default
+    fn decode_with_headers<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        topic: T,
+        _headers: &Headers,
+        buf: B,
+    ) -> Result<Self, E::DecodeError> {
+        Self::decode(topic, buf)
+    }
+}
+
+/// Marks messages of a single type, as opposed to the ProfileMessage enum
+/// holding any profile
+///
+/// The encodings implement Message for every TypedMessage as well as for
+/// ProfileMessage, so one bus may publish and subscribe to both. Every
+/// generated message is a TypedMessage, other messages sent over a bus, e.g.
+/// prost messages of your own, need to implement it as well.
+///
+/// The marker is defined here rather than along with the generated messages
+/// as a blanket impl over a trait of another crate would conflict with the
+/// impls for ProfileMessage.
+pub trait TypedMessage {}
+
+macro_rules! impl_typed_message {
+    ($($message:path,)*) => {
+        $(impl TypedMessage for $message {})*
+    };
+}
+
+openfmb_messages::for_each_message!(impl_typed_message);
+
+/// Metadata carried alongside an encoded message, mapped to NATS headers
+///
+/// Any header may be set, the well known ones have their own accessors.
+/// Buses without a place for metadata publish the payload alone and
+/// receive messages with no headers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Headers {
+    entries: BTreeMap<String, String>,
+}
+
+impl Headers {
+    /// Media type of the payload, e.g. `application/protobuf`
+    pub const CONTENT_TYPE: &'static str = "content-type";
+    /// Version of the encoding or schema the payload was written with
+    pub const ENCODING_VERSION: &'static str = "openfmb-encoding-version";
+    /// Identifies the publishing application or device
+    pub const PUBLISHER_ID: &'static str = "openfmb-publisher-id";
+    /// Trace the message belongs to
+    pub const TRACE_ID: &'static str = "openfmb-trace-id";
+    /// Sequence number of the message from its publisher
+    pub const SEQUENCE: &'static str = "openfmb-sequence";
+
+    pub fn new() -> Headers {
+        Headers::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries.get(name).map(String::as_str)
+    }
+
+    /// Set a header, returning its previous value
+    pub fn insert<N: Into<String>, V: Into<String>>(
+        &mut self,
+        name: N,
+        value: V,
+    ) -> Option<String> {
+        self.entries.insert(name.into(), value.into())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.entries.remove(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.get(Headers::CONTENT_TYPE)
+    }
+
+    pub fn set_content_type<V: Into<String>>(&mut self, value: V) {
+        self.insert(Headers::CONTENT_TYPE, value);
+    }
+
+    pub fn encoding_version(&self) -> Option<&str> {
+        self.get(Headers::ENCODING_VERSION)
+    }
+
+    pub fn set_encoding_version<V: Into<String>>(&mut self, value: V) {
+        self.insert(Headers::ENCODING_VERSION, value);
+    }
+
+    pub fn publisher_id(&self) -> Option<&str> {
+        self.get(Headers::PUBLISHER_ID)
+    }
+
+    pub fn set_publisher_id<V: Into<String>>(&mut self, value: V) {
+        self.insert(Headers::PUBLISHER_ID, value);
+    }
+
+    pub fn trace_id(&self) -> Option<&str> {
+        self.get(Headers::TRACE_ID)
+    }
+
+    pub fn set_trace_id<V: Into<String>>(&mut self, value: V) {
+        self.insert(Headers::TRACE_ID, value);
+    }
+
+    /// The sequence number, None if missing or not a number
+    pub fn sequence(&self) -> Option<u64> {
+        self.get(Headers::SEQUENCE)?.parse().ok()
+    }
+
+    pub fn set_sequence(&mut self, sequence: u64) {
+        self.insert(Headers::SEQUENCE, sequence.to_string());
+    }
+}
+
+impl<N: Into<String>, V: Into<String>> std::iter::FromIterator<(N, V)> for Headers {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Headers {
+        Headers {
+            entries: iter
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        }
+    }
+}
+
+/// A message along with its headers, publish one to send headers with the
+/// message and subscribe to them to see the headers of received messages
+///
+/// ```ignore
+/// let mut headers = Headers::new();
+/// headers.set_publisher_id("feeder-7");
+/// bus.publish(topic.iter(), WithHeaders::new(headers, status)).await?;
+///
+/// let mut sub: Subscription<WithHeaders<SwitchStatusProfile>> = bus.subscribe(topic.iter()).await?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithHeaders<M> {
+    pub headers: Headers,
+    pub msg: M,
+}
+
+impl<M> WithHeaders<M> {
+    pub fn new(headers: Headers, msg: M) -> WithHeaders<M> {
+        WithHeaders { headers, msg }
+    }
+}
+
+impl<E: MessageEncoding, M: Message<E>> Message<E> for WithHeaders<M> {
+    fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), E::EncodeError> {
+        self.msg.encode(buf)
+    }
+
+    fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        topic: T,
+        buf: B,
+    ) -> Result<WithHeaders<M>, E::DecodeError> {
+        <WithHeaders<M> as Message<E>>::decode_with_headers(topic, &Headers::default(), buf)
+    }
+
+    fn headers(&self) -> Option<&Headers> {
+        Some(&self.headers)
+    }
+
+    fn decode_with_headers<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        topic: T,
+        headers: &Headers,
+        buf: B,
+    ) -> Result<WithHeaders<M>, E::DecodeError> {
+        Ok(WithHeaders {
+            headers: headers.clone(),
+            msg: M::decode_with_headers(topic, headers, buf)?,
+        })
+    }
+}
+
+/// A message left in its encoded form along with the topic it was received
+/// on, for buses and tools that pass payloads through without knowing their
+/// type. Encoding writes the payload as is, the topic is only filled in when
+/// decoding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawMessage {
+    pub topic: TopicBuf,
+    pub headers: Headers,
+    pub payload: Bytes,
+}
+
+impl RawMessage {
+    pub fn new(topic: TopicBuf, payload: Bytes) -> RawMessage {
+        RawMessage {
+            topic,
+            headers: Headers::default(),
+            payload,
+        }
+    }
+
+    pub fn with_headers(mut self, headers: Headers) -> RawMessage {
+        self.headers = headers;
+        self
+    }
+}
+
+impl<E: MessageEncoding> Message<E> for RawMessage {
+    fn encode<B: BufMut>(&self, buf: &mut B) -> Result<(), E::EncodeError> {
+        buf.put_slice(&self.payload);
+        Ok(())
+    }
+
+    fn decode<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        topic: T,
+        buf: B,
+    ) -> Result<RawMessage, E::DecodeError> {
+        <RawMessage as Message<E>>::decode_with_headers(topic, &Headers::default(), buf)
+    }
+
+    fn headers(&self) -> Option<&Headers> {
+        Some(&self.headers)
+    }
+
+    fn decode_with_headers<S: AsRef<str>, T: Topic<S>, B: Buf>(
+        topic: T,
+        headers: &Headers,
+        mut buf: B,
+    ) -> Result<RawMessage, E::DecodeError> {
+        Ok(RawMessage {
+            topic: TopicBuf::from_topic(topic),
+            headers: headers.clone(),
+            payload: buf.copy_to_bytes(buf.remaining()),
+        })
+    }
 }
@@ -0,0 +1,232 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reading and writing the fields of any generated message by a path of
+//! proto field names, e.g. for configuration or scripting that names points
+//! as strings
+//!
+//! ```ignore
+//! use openfmb::path::FieldPath;
+//! use openfmb::descriptors::dynamic::Value;
+//!
+//! status.set_path(
+//!     "switchStatus.switchStatusXSWI.Pos.phs3.stVal",
+//!     Value::String("closed".to_string()),
+//! )?;
+//! let pos = status.get_path("switchStatus.switchStatusXSWI.Pos.phs3.stVal")?;
+//!
+//! // several fields with one round trip through a DynamicMessage
+//! status.set_paths(vec![
+//!     ("switchStatus.switchStatusXSWI.Pos.phs3.stVal", Value::String("open".to_string())),
+//!     ("statusMessageInfo.messageInfo.messageTimeStamp.seconds", Value::U64(seconds)),
+//! ])?;
+//! ```
+
+use openfmb_descriptors::dynamic::{DescriptorPool, DynamicMessage};
+pub use openfmb_descriptors::dynamic::{PathError, Value};
+use openfmb_messages::FullName;
+
+/// Get and set fields by path, with the segments separated by dots and an
+/// index for elements of repeated fields, e.g. `breakerReading[1].readingMMXU.W.net.cVal.mag`
+///
+/// Implemented for every generated message through the global
+/// DescriptorPool, so each call encodes the message and decodes it as a
+/// DynamicMessage, and setting decodes it back again. Fine for
+/// configuration and tooling, not for a hot path; get_paths and set_paths
+/// take several paths for the cost of one.
+///
+/// UnknownType is returned for the message itself when the global pool
+/// does not know its type, or has a schema it cannot be decoded back from.
+pub trait FieldPath {
+    /// The value at the path as the generated code has it, see
+    /// DynamicMessage::value_at
+    fn get_path(&self, path: &str) -> Result<Option<Value>, PathError>;
+
+    /// Set the value at the path, creating the messages along it that are
+    /// not set, see DynamicMessage::set_path
+    fn set_path(&mut self, path: &str, value: Value) -> Result<(), PathError>;
+
+    /// The values at each of the paths, in their order
+    fn get_paths(&self, paths: &[&str]) -> Result<Vec<Option<Value>>, PathError>;
+
+    /// Set the values at each of the paths in turn, the message is left
+    /// unchanged if any of them fails
+    fn set_paths<'a, I>(&mut self, values: I) -> Result<(), PathError>
+    where
+        I: IntoIterator<Item = (&'a str, Value)>;
+}
+
+impl<M: prost::Message + Default + FullName> FieldPath for M {
+    fn get_path(&self, path: &str) -> Result<Option<Value>, PathError> {
+        dynamic(self)?.value_at(path)
+    }
+
+    fn set_path(&mut self, path: &str, value: Value) -> Result<(), PathError> {
+        self.set_paths(std::iter::once((path, value)))
+    }
+
+    fn get_paths(&self, paths: &[&str]) -> Result<Vec<Option<Value>>, PathError> {
+        let msg = dynamic(self)?;
+        paths.iter().map(|path| msg.value_at(path)).collect()
+    }
+
+    fn set_paths<'a, I>(&mut self, values: I) -> Result<(), PathError>
+    where
+        I: IntoIterator<Item = (&'a str, Value)>,
+    {
+        let mut msg = dynamic(self)?;
+        for (path, value) in values {
+            msg.set_path(path, value)?;
+        }
+        *self = M::decode(prost::Message::encode_to_vec(&msg).as_slice())
+            .map_err(|_| PathError::UnknownType(M::FULL_NAME.to_string()))?;
+        Ok(())
+    }
+}
+
+fn dynamic<M: prost::Message + FullName>(msg: &M) -> Result<DynamicMessage, PathError> {
+    DynamicMessage::decode_type(
+        DescriptorPool::global(),
+        M::FULL_NAME,
+        msg.encode_to_vec().as_slice(),
+    )
+    .map_err(|_| PathError::UnknownType(M::FULL_NAME.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openfmb_messages::{
+        breakermodule::{BreakerReading, BreakerReadingProfile},
+        commonmodule::DbPosKind,
+        switchmodule::SwitchStatusProfile,
+    };
+
+    #[test]
+    fn test_field_path() {
+        let mut status = SwitchStatusProfile::default();
+        let path = "switchStatus.switchStatusXSWI.pos.phs3.stVal";
+        assert_eq!(status.get_path(path), Ok(None));
+
+        status
+            .set_path(path, Value::String("closed".to_string()))
+            .unwrap();
+        assert_eq!(
+            status.get_path(path),
+            Ok(Some(Value::Enum(DbPosKind::Closed as i32)))
+        );
+        let pos = status
+            .switch_status
+            .as_ref()
+            .and_then(|status| status.switch_status_xswi.as_ref())
+            .and_then(|xswi| xswi.pos.as_ref())
+            .and_then(|pos| pos.phs3.as_ref())
+            .unwrap();
+        assert_eq!(pos.st_val, DbPosKind::Closed as i32);
+        // unset scalars read as their default
+        status
+            .set_path(
+                "switchStatus.switchStatusXSWI.pos.phs3.q.test",
+                Value::Bool(true),
+            )
+            .unwrap();
+        assert_eq!(
+            status.get_path("switchStatus.switchStatusXSWI.pos.phs3.q.validity"),
+            Ok(Some(Value::Enum(0)))
+        );
+
+        // wrapper types are set and read by their inner value
+        let name = "statusMessageInfo.messageInfo.identifiedObject.name";
+        status
+            .set_path(name, Value::String("feeder 7".to_string()))
+            .unwrap();
+        assert_eq!(
+            status.get_path(name),
+            Ok(Some(Value::String("feeder 7".to_string())))
+        );
+
+        assert_eq!(
+            status.set_path(path, Value::F32(1.0)),
+            Err(PathError::TypeMismatch(path.to_string()))
+        );
+        // a failed set leaves no empty messages behind along the path
+        let mut msg = dynamic(&SwitchStatusProfile::default()).unwrap();
+        assert_eq!(
+            msg.set_path(path, Value::F32(1.0)),
+            Err(PathError::TypeMismatch(path.to_string()))
+        );
+        assert!(prost::Message::encode_to_vec(&msg).is_empty());
+        assert_eq!(
+            status.get_path("switchStatus.switchStatusXSWI.position"),
+            Err(PathError::UnknownField(
+                "switchStatus.switchStatusXSWI.position".to_string()
+            ))
+        );
+        assert_eq!(
+            status.get_path("switchStatus.switchStatusXSWI.pos.phs3.stVal.value"),
+            Err(PathError::NotAMessage(path.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_repeated_path() {
+        let mut profile = BreakerReadingProfile {
+            breaker_reading: vec![BreakerReading::default()],
+            ..Default::default()
+        };
+        let mag = "breakerReading[1].readingMMXU.W.net.cVal.mag";
+        assert_eq!(profile.get_path(mag), Ok(None));
+
+        // one past the last element appends
+        profile.set_path(mag, Value::F64(42.0)).unwrap();
+        assert_eq!(profile.breaker_reading.len(), 2);
+        assert_eq!(profile.get_path(mag), Ok(Some(Value::F64(42.0))));
+        profile.set_path(mag, Value::F64(7.0)).unwrap();
+        assert_eq!(profile.get_path(mag), Ok(Some(Value::F64(7.0))));
+        assert_eq!(
+            profile.set_path("breakerReading[3].readingMMXU", Value::List(Vec::new())),
+            Err(PathError::InvalidIndex("breakerReading[3]".to_string()))
+        );
+        assert_eq!(
+            profile.get_path("breakerReading.readingMMXU"),
+            Err(PathError::InvalidIndex("breakerReading".to_string()))
+        );
+        match profile.get_path("breakerReading") {
+            Ok(Some(Value::List(readings))) => assert_eq!(readings.len(), 2),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_paths() {
+        let mut status = SwitchStatusProfile::default();
+        let pos = "switchStatus.switchStatusXSWI.pos.phs3.stVal";
+        let seconds = "statusMessageInfo.messageInfo.messageTimeStamp.seconds";
+        status
+            .set_paths(vec![
+                (pos, Value::String("open".to_string())),
+                (seconds, Value::U64(1_600_000_000)),
+            ])
+            .unwrap();
+        assert_eq!(
+            status.get_paths(&[seconds, pos, "switchStatus.switchStatusXSWI.pos.phsA"]),
+            Ok(vec![
+                Some(Value::U64(1_600_000_000)),
+                Some(Value::Enum(DbPosKind::Open as i32)),
+                None,
+            ])
+        );
+
+        // nothing is set when one of the paths fails
+        let before = status.clone();
+        assert_eq!(
+            status.set_paths(vec![
+                (pos, Value::String("closed".to_string())),
+                ("switchStatus.position", Value::Bool(true)),
+            ]),
+            Err(PathError::UnknownField("switchStatus.position".to_string()))
+        );
+        assert_eq!(status, before);
+    }
+}
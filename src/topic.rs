@@ -2,6 +2,8 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::error::TopicError;
+use std::str::FromStr;
 use uuid::Uuid;
 
 pub use openfmb_messages::{Module, Profile};
@@ -25,10 +27,27 @@ pub trait Topic<S: AsRef<str>>: Send + Iterator<Item = TopicLevel<S>> {
     fn prefix_match(&self) -> bool;
 }
 
+/// The level every OpenFMB topic starts with, following any prefix
+/// namespacing it, e.g. `site-a.openfmb.switchmodule...`
+///
+/// Prefix levels must not be `openfmb` themselves, the first such level is
+/// taken to be the root.
+pub const ROOT: &str = "openfmb";
+
+/// Levels of a `.` separated prefix, none for an empty one
+pub(crate) fn prefix_levels(prefix: &str) -> Vec<String> {
+    prefix
+        .split('.')
+        .filter(|lvl| !lvl.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 /// A structured topic for OpenFMB which can be used with a MessageBus
 /// as a BusTopic
 #[derive(Clone, Debug)]
 pub struct ProfileTopic {
+    pub(crate) prefix: Vec<String>,
     pub(crate) module: Module,
     pub(crate) profile: Profile,
     pub(crate) _mrid: Uuid,
@@ -38,6 +57,7 @@ pub struct ProfileTopic {
 impl ProfileTopic {
     pub fn new(module: Module, profile: Profile, mrid: Uuid) -> ProfileTopic {
         ProfileTopic {
+            prefix: Vec::new(),
             module,
             profile,
             _mrid: mrid,
@@ -45,6 +65,67 @@ impl ProfileTopic {
         }
     }
 
+    /// Namespace the topic with `.` separated prefix levels, e.g. `site-a`
+    /// for `site-a.openfmb.switchmodule...`
+    pub fn with_prefix(mut self, prefix: &str) -> ProfileTopic {
+        self.prefix = prefix_levels(prefix);
+        self
+    }
+
+    /// Parse a `.` separated subject such as
+    /// `openfmb.switchmodule.SwitchStatusProfile.<mrid>`, levels before the
+    /// root are kept as the prefix
+    pub fn parse(subject: &str) -> Result<ProfileTopic, TopicError> {
+        ProfileTopic::from_topic(TopicBuf::from_subject(subject).iter())
+    }
+
+    /// Validate the levels of any topic as those of a profile topic
+    pub fn from_topic<S: AsRef<str>, T: Topic<S>>(topic: T) -> Result<ProfileTopic, TopicError> {
+        if topic.prefix_match() {
+            return Err(TopicError::NotExact);
+        }
+        let levels = topic
+            .map(|lvl| match lvl {
+                TopicLevel::Exact(val) => Ok(val),
+                TopicLevel::WildCard => Err(TopicError::NotExact),
+            })
+            .collect::<Result<Vec<S>, TopicError>>()?;
+        if levels.len() < 4 {
+            return Err(TopicError::LevelCount(levels.len()));
+        }
+        let (prefix, levels) = levels.split_at(levels.len() - 4);
+        let level = |i: usize| levels[i].as_ref();
+        if level(0) != ROOT {
+            return Err(TopicError::InvalidRoot(level(0).to_string()));
+        }
+        let module = Module::from_str(level(1))
+            .map_err(|_| TopicError::UnknownModule(level(1).to_string()))?;
+        let profile = Profile::from_str(level(2))
+            .map_err(|_| TopicError::UnknownProfile(level(2).to_string()))?;
+        let mrid =
+            Uuid::parse_str(level(3)).map_err(|_| TopicError::InvalidMrid(level(3).to_string()))?;
+        let mut topic = ProfileTopic::new(module, profile, mrid);
+        topic.prefix = prefix.iter().map(|lvl| lvl.as_ref().to_string()).collect();
+        Ok(topic)
+    }
+
+    /// The levels before the root
+    pub fn prefix(&self) -> &[String] {
+        &self.prefix
+    }
+
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    pub fn mrid(&self) -> Uuid {
+        self._mrid
+    }
+
     pub fn iter<'a>(&'a self) -> ProfileTopicRefIter<'a> {
         ProfileTopicRefIter {
             topic: self,
@@ -53,22 +134,32 @@ impl ProfileTopic {
     }
 }
 
+impl FromStr for ProfileTopic {
+    type Err = TopicError;
+
+    fn from_str(subject: &str) -> Result<ProfileTopic, TopicError> {
+        ProfileTopic::parse(subject)
+    }
+}
+
 /// Iterator for a ProfileTopic that providse string references
 /// and does *not* allocate beyond its state to track where it is.
 pub struct ProfileTopicRefIter<'a> {
     topic: &'a ProfileTopic,
-    pos: u8,
+    pos: usize,
 }
 
 impl<'a> Iterator for ProfileTopicRefIter<'a> {
     type Item = TopicLevel<&'a str>;
 
     fn next(&mut self) -> Option<TopicLevel<&'a str>> {
-        let ret = match self.pos {
-            0 => Some(TopicLevel::Exact("openfmb")),
-            1 => Some(TopicLevel::Exact(self.topic.module.as_str())),
-            2 => Some(TopicLevel::Exact(self.topic.profile.as_str())),
-            3 => Some(TopicLevel::Exact(self.topic.mrid_str.as_str())),
+        let prefix = &self.topic.prefix;
+        let ret = match self.pos.checked_sub(prefix.len()) {
+            None => Some(TopicLevel::Exact(prefix[self.pos].as_str())),
+            Some(0) => Some(TopicLevel::Exact(ROOT)),
+            Some(1) => Some(TopicLevel::Exact(self.topic.module.as_str())),
+            Some(2) => Some(TopicLevel::Exact(self.topic.profile.as_str())),
+            Some(3) => Some(TopicLevel::Exact(self.topic.mrid_str.as_str())),
             _ => None,
         };
         self.pos += 1;
@@ -81,3 +172,479 @@ impl<'a> Topic<&'a str> for ProfileTopicRefIter<'a> {
         false
     }
 }
+
+/// A filter over profile topics, each level is either a given value or any
+///
+/// ```
+/// use openfmb::topic::{Module, TopicFilter};
+///
+/// let filter = TopicFilter::openfmb()
+///     .module(Module::SwitchModule)
+///     .any_profile()
+///     .any_mrid();
+/// assert_eq!(filter.to_string(), "openfmb.switchmodule.*.*");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TopicFilter {
+    prefix: Vec<String>,
+    module: Option<Module>,
+    profile: Option<Profile>,
+    mrid: Option<String>,
+}
+
+impl TopicFilter {
+    /// A filter matching every openfmb profile topic
+    pub fn openfmb() -> TopicFilter {
+        TopicFilter::default()
+    }
+
+    /// Only topics namespaced with these `.` separated prefix levels
+    pub fn prefix(mut self, prefix: &str) -> TopicFilter {
+        self.prefix = prefix_levels(prefix);
+        self
+    }
+
+    pub fn module(mut self, module: Module) -> TopicFilter {
+        self.module = Some(module);
+        self
+    }
+
+    pub fn any_module(mut self) -> TopicFilter {
+        self.module = None;
+        self
+    }
+
+    pub fn profile(mut self, profile: Profile) -> TopicFilter {
+        self.profile = Some(profile);
+        self
+    }
+
+    pub fn any_profile(mut self) -> TopicFilter {
+        self.profile = None;
+        self
+    }
+
+    pub fn mrid(mut self, mrid: Uuid) -> TopicFilter {
+        self.mrid = Some(mrid.as_hyphenated().to_string().to_lowercase());
+        self
+    }
+
+    pub fn any_mrid(mut self) -> TopicFilter {
+        self.mrid = None;
+        self
+    }
+
+    /// Whether a topic passes the filter
+    pub fn matches<S: AsRef<str>, T: Topic<S>>(&self, topic: T) -> bool {
+        matches(self.iter(), topic)
+    }
+
+    pub fn iter<'a>(&'a self) -> TopicFilterIter<'a> {
+        TopicFilterIter {
+            filter: self,
+            pos: 0,
+        }
+    }
+}
+
+/// Displays the filter as a NATS subject
+impl std::fmt::Display for TopicFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", TopicBuf::from_topic(self.iter()))
+    }
+}
+
+/// Iterator for a TopicFilter that provides string references
+pub struct TopicFilterIter<'a> {
+    filter: &'a TopicFilter,
+    pos: usize,
+}
+
+impl<'a> Iterator for TopicFilterIter<'a> {
+    type Item = TopicLevel<&'a str>;
+
+    fn next(&mut self) -> Option<TopicLevel<&'a str>> {
+        let level = |val: Option<&'a str>| val.map_or(TopicLevel::WildCard, TopicLevel::Exact);
+        let prefix = &self.filter.prefix;
+        let ret = match self.pos.checked_sub(prefix.len()) {
+            None => Some(TopicLevel::Exact(prefix[self.pos].as_str())),
+            Some(0) => Some(TopicLevel::Exact(ROOT)),
+            Some(1) => Some(level(self.filter.module.as_ref().map(Module::as_str))),
+            Some(2) => Some(level(self.filter.profile.as_ref().map(Profile::as_str))),
+            Some(3) => Some(level(self.filter.mrid.as_deref())),
+            _ => None,
+        };
+        self.pos += 1;
+        ret
+    }
+}
+
+impl<'a> Topic<&'a str> for TopicFilterIter<'a> {
+    fn prefix_match(&self) -> bool {
+        false
+    }
+}
+
+/// Whether a topic matches a filter, the matching every bus applies to its
+/// subscriptions
+///
+/// Wildcards match exactly one level, a prefix match requires at least one
+/// more level beyond the given ones (the same as `*` and `>` in NATS). A
+/// wildcard level in the topic is only matched by a wildcard.
+pub fn matches<S, F, R, T>(filter: F, mut topic: T) -> bool
+where
+    S: AsRef<str>,
+    F: Topic<S>,
+    R: AsRef<str>,
+    T: Topic<R>,
+{
+    let prefix_match = filter.prefix_match();
+    for lvl in filter {
+        let matched = match (lvl, topic.next()) {
+            (_, None) => false,
+            (TopicLevel::WildCard, Some(_)) => true,
+            (TopicLevel::Exact(exact), Some(TopicLevel::Exact(val))) => {
+                exact.as_ref() == val.as_ref()
+            }
+            (TopicLevel::Exact(_), Some(TopicLevel::WildCard)) => false,
+        };
+        if !matched {
+            return false;
+        }
+    }
+    topic.next().is_some() == prefix_match
+}
+
+/// An owned topic, for when a topic has to be kept around or modified
+/// rather than iterated over once
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TopicBuf {
+    pub levels: Vec<TopicLevel<String>>,
+    pub prefix_match: bool,
+}
+
+impl TopicBuf {
+    pub fn new(levels: Vec<TopicLevel<String>>, prefix_match: bool) -> TopicBuf {
+        TopicBuf {
+            levels,
+            prefix_match,
+        }
+    }
+
+    /// Collect the levels of any topic
+    pub fn from_topic<S: AsRef<str>, T: Topic<S>>(topic: T) -> TopicBuf {
+        let prefix_match = topic.prefix_match();
+        TopicBuf {
+            levels: topic
+                .map(|lvl| match lvl {
+                    TopicLevel::Exact(val) => TopicLevel::Exact(val.as_ref().to_string()),
+                    TopicLevel::WildCard => TopicLevel::WildCard,
+                })
+                .collect(),
+            prefix_match,
+        }
+    }
+
+    /// Parse a `.` separated NATS subject, `*` levels are wildcards and a
+    /// trailing `>` makes the topic prefix matching
+    pub fn from_subject(subject: &str) -> TopicBuf {
+        TopicBuf::parse(subject, '.', ">")
+    }
+
+    /// Parse a `/` separated path as used by zenoh, a leading `/` is ignored,
+    /// `*` levels are wildcards and a trailing `**` makes the topic prefix
+    /// matching
+    pub fn from_path(path: &str) -> TopicBuf {
+        TopicBuf::parse(path.strip_prefix('/').unwrap_or(path), '/', "**")
+    }
+
+    fn parse(subject: &str, separator: char, prefix: &str) -> TopicBuf {
+        let mut levels: Vec<&str> = if subject.is_empty() {
+            Vec::new()
+        } else {
+            subject.split(separator).collect()
+        };
+        let prefix_match = levels.last() == Some(&prefix);
+        if prefix_match {
+            levels.pop();
+        }
+        TopicBuf {
+            levels: levels
+                .into_iter()
+                .map(|lvl| match lvl {
+                    "*" => TopicLevel::WildCard,
+                    val => TopicLevel::Exact(val.to_string()),
+                })
+                .collect(),
+            prefix_match,
+        }
+    }
+
+    /// The levels from the root on, skipping any prefix, None if the topic
+    /// has no root level
+    pub fn root_levels(&self) -> Option<&[TopicLevel<String>]> {
+        let root = self.root_index()?;
+        Some(&self.levels[root..])
+    }
+
+    /// Replace any levels before the root with the given prefix, or prepend
+    /// the prefix to a topic without a root level
+    pub fn set_prefix(&mut self, prefix: &[String]) {
+        let root = self.root_index().unwrap_or(0);
+        self.levels.splice(
+            ..root,
+            prefix.iter().map(|lvl| TopicLevel::Exact(lvl.clone())),
+        );
+    }
+
+    fn root_index(&self) -> Option<usize> {
+        self.levels
+            .iter()
+            .position(|lvl| matches!(lvl, TopicLevel::Exact(val) if val == ROOT))
+    }
+
+    /// The topic as a `/` separated path with a leading `/` as used by zenoh
+    pub fn to_path(&self) -> String {
+        let mut path = String::with_capacity(128);
+        for lvl in self.levels.iter() {
+            path.push('/');
+            match lvl {
+                TopicLevel::Exact(val) => path.push_str(val),
+                TopicLevel::WildCard => path.push('*'),
+            }
+        }
+        if self.prefix_match {
+            path.push_str("/**");
+        }
+        path
+    }
+
+    /// The levels joined by `.` skipping empty ones, as some buses report a
+    /// leading separator as an empty level. Identifies the topic when it is
+    /// covered by a signature or authenticated data.
+    pub(crate) fn canonical_subject(&self) -> String {
+        let levels: Vec<&str> = self
+            .levels
+            .iter()
+            .filter_map(|lvl| match lvl {
+                TopicLevel::Exact(val) if val.is_empty() => None,
+                TopicLevel::Exact(val) => Some(val.as_str()),
+                TopicLevel::WildCard => Some("*"),
+            })
+            .collect();
+        levels.join(".")
+    }
+
+    pub fn iter<'a>(&'a self) -> TopicBufIter<'a> {
+        TopicBufIter {
+            iter: self.levels.iter(),
+            prefix_match: self.prefix_match,
+        }
+    }
+}
+
+/// Displays the topic as a NATS subject, `*` for wildcards and a trailing
+/// `>` for prefix matching topics
+impl std::fmt::Display for TopicBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, lvl) in self.levels.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            match lvl {
+                TopicLevel::Exact(val) => f.write_str(val)?,
+                TopicLevel::WildCard => f.write_str("*")?,
+            }
+        }
+        match (self.prefix_match, self.levels.is_empty()) {
+            (true, true) => f.write_str(">"),
+            (true, false) => f.write_str(".>"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Iterator for a TopicBuf that provides string references
+pub struct TopicBufIter<'a> {
+    iter: std::slice::Iter<'a, TopicLevel<String>>,
+    prefix_match: bool,
+}
+
+impl<'a> Iterator for TopicBufIter<'a> {
+    type Item = TopicLevel<&'a str>;
+
+    fn next(&mut self) -> Option<TopicLevel<&'a str>> {
+        self.iter.next().map(|lvl| match lvl {
+            TopicLevel::Exact(val) => TopicLevel::Exact(val.as_str()),
+            TopicLevel::WildCard => TopicLevel::WildCard,
+        })
+    }
+}
+
+impl<'a> Topic<&'a str> for TopicBufIter<'a> {
+    fn prefix_match(&self) -> bool {
+        self.prefix_match
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matches, Module, Profile, ProfileTopic, TopicBuf, TopicFilter, TopicLevel};
+    use crate::error::TopicError;
+    use uuid::Uuid;
+
+    fn mrid() -> Uuid {
+        Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap()
+    }
+
+    #[test]
+    fn test_matches() {
+        let xyz = TopicBuf::from_subject("x.y.z");
+        let filter = |subject: &str| TopicBuf::from_subject(subject);
+        assert!(matches(filter("x.y.z").iter(), xyz.iter()));
+        assert!(matches(filter("x.*.z").iter(), xyz.iter()));
+        assert!(matches(filter("x.>").iter(), xyz.iter()));
+        assert!(matches(filter("x.*.>").iter(), xyz.iter()));
+        assert!(!matches(filter("x.y").iter(), xyz.iter()));
+        assert!(!matches(filter("x.y.z.>").iter(), xyz.iter()));
+        assert!(!matches(filter("x.y.w").iter(), xyz.iter()));
+        assert!(!matches(filter("x.y.z.w").iter(), xyz.iter()));
+        assert!(!matches(xyz.iter(), filter("x.*.z").iter()));
+    }
+
+    #[test]
+    fn test_topic_filter() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid());
+        let filter = TopicFilter::openfmb()
+            .module(Module::SwitchModule)
+            .any_profile()
+            .any_mrid();
+        assert_eq!(filter.to_string(), "openfmb.switchmodule.*.*");
+        assert!(filter.matches(topic.iter()));
+        assert!(TopicFilter::openfmb().matches(topic.iter()));
+        assert!(TopicFilter::openfmb().mrid(mrid()).matches(topic.iter()));
+        assert!(!filter
+            .clone()
+            .profile(Profile::SwitchReadingProfile)
+            .matches(topic.iter()));
+        assert!(!TopicFilter::openfmb()
+            .module(Module::BreakerModule)
+            .matches(topic.iter()));
+    }
+
+    #[test]
+    fn test_profile_topic_parse() {
+        let topic = ProfileTopic::parse(
+            "openfmb.switchmodule.SwitchStatusProfile.06fb668d-f87a-4b1b-8d99-0949513126ff",
+        )
+        .unwrap();
+        assert_eq!(topic.module(), &Module::SwitchModule);
+        assert_eq!(topic.profile(), Profile::SwitchStatusProfile);
+        assert_eq!(topic.mrid(), mrid());
+        let subject = TopicBuf::from_topic(topic.iter()).to_string();
+        assert_eq!(subject.parse::<ProfileTopic>().unwrap().mrid(), mrid());
+
+        let err = |subject: &str| ProfileTopic::parse(subject).unwrap_err();
+        assert_eq!(err("openfmb.switchmodule"), TopicError::LevelCount(2));
+        assert_eq!(err("openfmb.switchmodule.>"), TopicError::NotExact);
+        assert_eq!(
+            err("openfmb.*.SwitchStatusProfile.06fb668d-f87a-4b1b-8d99-0949513126ff"),
+            TopicError::NotExact
+        );
+        assert_eq!(
+            err("other.switchmodule.SwitchStatusProfile.x"),
+            TopicError::InvalidRoot("other".to_string())
+        );
+        assert_eq!(
+            err("openfmb.valvemodule.SwitchStatusProfile.x"),
+            TopicError::UnknownModule("valvemodule".to_string())
+        );
+        assert_eq!(
+            err("openfmb.switchmodule.ValveStatusProfile.x"),
+            TopicError::UnknownProfile("ValveStatusProfile".to_string())
+        );
+        assert_eq!(
+            err("openfmb.switchmodule.SwitchStatusProfile.x"),
+            TopicError::InvalidMrid("x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_topic_buf_display() {
+        let topic = TopicBuf::new(
+            vec![
+                TopicLevel::Exact("openfmb".to_string()),
+                TopicLevel::WildCard,
+            ],
+            true,
+        );
+        assert_eq!(topic.to_string(), "openfmb.*.>");
+        assert_eq!(TopicBuf::new(vec![], true).to_string(), ">");
+        assert_eq!(topic.iter().count(), 2);
+        let topic = TopicBuf::new(
+            vec![
+                TopicLevel::Exact("".to_string()),
+                TopicLevel::Exact("openfmb".to_string()),
+                TopicLevel::WildCard,
+            ],
+            false,
+        );
+        assert_eq!(topic.canonical_subject(), "openfmb.*");
+    }
+
+    #[test]
+    fn test_topic_buf_subject_path() {
+        let topic = TopicBuf::from_subject("openfmb.switchmodule.*.>");
+        assert_eq!(
+            topic,
+            TopicBuf::new(
+                vec![
+                    TopicLevel::Exact("openfmb".to_string()),
+                    TopicLevel::Exact("switchmodule".to_string()),
+                    TopicLevel::WildCard,
+                ],
+                true,
+            )
+        );
+        assert_eq!(topic.to_path(), "/openfmb/switchmodule/*/**");
+        assert_eq!(TopicBuf::from_path(&topic.to_path()), topic);
+        assert_eq!(TopicBuf::from_path("/**"), TopicBuf::new(vec![], true));
+        let exact = TopicBuf::from_path("/openfmb/switchmodule");
+        assert_eq!(exact.to_string(), "openfmb.switchmodule");
+        assert_eq!(TopicBuf::from_subject(&exact.to_string()), exact);
+    }
+
+    #[test]
+    fn test_prefix() {
+        let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid())
+            .with_prefix("region.site-a");
+        let subject = TopicBuf::from_topic(topic.iter()).to_string();
+        assert_eq!(
+            subject,
+            "region.site-a.openfmb.switchmodule.SwitchStatusProfile.06fb668d-f87a-4b1b-8d99-0949513126ff"
+        );
+        let parsed = ProfileTopic::parse(&subject).unwrap();
+        assert_eq!(parsed.prefix(), ["region", "site-a"]);
+        assert_eq!(parsed.mrid(), mrid());
+        assert!(ProfileTopic::parse(
+            "site-a.other.switchmodule.SwitchStatusProfile.06fb668d-f87a-4b1b-8d99-0949513126ff"
+        )
+        .is_err());
+
+        let filter = TopicFilter::openfmb().module(Module::SwitchModule);
+        assert!(!filter.matches(topic.iter()));
+        assert!(!filter.clone().prefix("site-a").matches(topic.iter()));
+        let filter = filter.prefix("region.site-a");
+        assert_eq!(filter.to_string(), "region.site-a.openfmb.switchmodule.*.*");
+        assert!(filter.matches(topic.iter()));
+
+        let mut buf = TopicBuf::from_topic(topic.iter());
+        assert_eq!(buf.root_levels().unwrap().len(), 4);
+        buf.set_prefix(&["site-b".to_string()]);
+        assert!(buf.to_string().starts_with("site-b.openfmb.switchmodule."));
+        let mut all = TopicBuf::from_subject(">");
+        all.set_prefix(&["site-b".to_string()]);
+        assert_eq!(all.to_string(), "site-b.>");
+        assert_eq!(all.root_levels(), None);
+    }
+}
@@ -0,0 +1,222 @@
+// SPDX-FileCopyrightText: 2021 Open Energy Solutions Inc
+//
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::{env, time::Duration};
+
+use bytes::BytesMut;
+use futures::StreamExt;
+use openfmb::{
+    bus::{Closable, MqttBus, MqttPublishOptions, QoS},
+    encoding::ProtobufEncoding,
+    messages::switchmodule::SwitchStatusProfile,
+    prelude::*,
+};
+use rumqttc::{
+    ConnAck, ConnectReturnCode, MqttOptions, Packet, PingResp, PubAck, Publish, SubAck,
+    SubscribeReasonCode,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Whether an MQTT topic filter with `+` and `#` wildcards matches a topic
+fn filter_matches(filter: &str, topic: &str) -> bool {
+    let mut topic = topic.split('/');
+    for lvl in filter.split('/') {
+        match (lvl, topic.next()) {
+            ("#", _) => return true,
+            (_, None) => return false,
+            ("+", Some(_)) => (),
+            (exact, Some(val)) if exact == val => (),
+            _ => return false,
+        }
+    }
+    topic.next().is_none()
+}
+
+/// A stand-in for a broker, just enough of MQTT 3.1.1 for a client talking
+/// to itself: connects and subscribes are acknowledged and each publish is
+/// sent back on the connection if one of its subscriptions matches
+///
+/// Subscriptions are kept per connection, as for a clean session, and the
+/// subscribe requests received are counted. With drop_first the first
+/// connection is closed after acknowledging its first subscribe.
+async fn stand_in_broker(drop_first: bool) -> (SocketAddr, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let subscribes = Arc::new(AtomicUsize::new(0));
+    let counted = subscribes.clone();
+    tokio::spawn(async move {
+        let mut drop_after_subscribe = drop_first;
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(connection(stream, drop_after_subscribe, counted.clone()));
+            drop_after_subscribe = false;
+        }
+    });
+    (addr, subscribes)
+}
+
+async fn connection(
+    mut stream: TcpStream,
+    drop_after_subscribe: bool,
+    subscribes: Arc<AtomicUsize>,
+) {
+    let mut filters: Vec<String> = Vec::new();
+    let mut read = BytesMut::with_capacity(4096);
+    loop {
+        let packet = match rumqttc::mqttbytes::v4::read(&mut read, 1 << 20) {
+            Ok(packet) => packet,
+            Err(rumqttc::mqttbytes::Error::InsufficientBytes(_)) => {
+                match stream.read_buf(&mut read).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(_) => continue,
+                }
+            }
+            Err(_) => return,
+        };
+        let mut write = BytesMut::new();
+        let subscribed = matches!(packet, Packet::Subscribe(_));
+        let written = match packet {
+            Packet::Connect(_) => ConnAck::new(ConnectReturnCode::Success, false).write(&mut write),
+            Packet::Subscribe(subscribe) => {
+                let codes = subscribe
+                    .filters
+                    .iter()
+                    .map(|filter| SubscribeReasonCode::Success(filter.qos))
+                    .collect();
+                filters.extend(subscribe.filters.into_iter().map(|filter| filter.path));
+                subscribes.fetch_add(1, Ordering::SeqCst);
+                SubAck::new(subscribe.pkid, codes).write(&mut write)
+            }
+            Packet::Publish(publish) => {
+                if publish.qos == QoS::AtLeastOnce {
+                    PubAck::new(publish.pkid).write(&mut write).unwrap();
+                }
+                if filters
+                    .iter()
+                    .any(|filter| filter_matches(filter, &publish.topic))
+                {
+                    Publish::new(publish.topic, QoS::AtMostOnce, publish.payload.to_vec())
+                        .write(&mut write)
+                } else {
+                    Ok(0)
+                }
+            }
+            Packet::PingReq => PingResp.write(&mut write),
+            Packet::Disconnect => return,
+            _ => Ok(0),
+        };
+        if written.is_err() || stream.write_all(&write).await.is_err() {
+            return;
+        }
+        if drop_after_subscribe && subscribed {
+            return;
+        }
+    }
+}
+
+async fn publish_subscribe(options: MqttOptions) {
+    let mut bus =
+        MqttBus::<ProtobufEncoding>::new(options).with_publish_options(MqttPublishOptions {
+            qos: QoS::AtLeastOnce,
+            retain: false,
+        });
+
+    let mrid = uuid::Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+    let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid);
+    let mut statuses: Subscription<SwitchStatusProfile> =
+        bus.subscribe(topic.iter()).await.unwrap();
+    let mut everything: Subscription<SwitchStatusProfile> =
+        bus.subscribe(TopicFilter::openfmb().iter()).await.unwrap();
+
+    // give the broker a moment to acknowledge the subscriptions
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let status = SwitchStatusProfile::default();
+    bus.publish(topic.iter(), status.clone()).await.unwrap();
+
+    for sub in [&mut statuses, &mut everything] {
+        let received = tokio::time::timeout(Duration::from_secs(5), sub.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, status);
+    }
+
+    bus.close();
+}
+
+#[test]
+fn test_filter_matches() {
+    assert!(filter_matches(
+        "openfmb/+/+/+",
+        "openfmb/switchmodule/SwitchStatusProfile/1"
+    ));
+    assert!(filter_matches("openfmb/#", "openfmb/switchmodule"));
+    assert!(!filter_matches(
+        "openfmb/+",
+        "openfmb/switchmodule/SwitchStatusProfile"
+    ));
+    assert!(!filter_matches("openfmb/+/+", "openfmb/switchmodule"));
+}
+
+#[tokio::test]
+async fn test_mqtt_stand_in_broker() {
+    let (addr, _) = stand_in_broker(false).await;
+    let options = MqttOptions::new("openfmb-mqtt-stand-in", addr.ip().to_string(), addr.port());
+    publish_subscribe(options).await;
+}
+
+#[tokio::test]
+async fn test_mqtt_resubscribe() {
+    let (addr, subscribes) = stand_in_broker(true).await;
+    let options = MqttOptions::new(
+        "openfmb-mqtt-resubscribe",
+        addr.ip().to_string(),
+        addr.port(),
+    );
+    let mut bus = MqttBus::<ProtobufEncoding>::new(options);
+
+    let mrid = uuid::Uuid::parse_str("06fb668d-f87a-4b1b-8d99-0949513126ff").unwrap();
+    let topic = ProfileTopic::new(Module::SwitchModule, Profile::SwitchStatusProfile, mrid);
+    let mut statuses: Subscription<SwitchStatusProfile> =
+        bus.subscribe(topic.iter()).await.unwrap();
+
+    // the broker drops the connection after the subscribe, the bus has to
+    // subscribe again once it reconnected
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while subscribes.load(Ordering::SeqCst) < 2 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("no subscribe after reconnecting");
+
+    let status = SwitchStatusProfile::default();
+    bus.publish(topic.iter(), status.clone()).await.unwrap();
+    let received = tokio::time::timeout(Duration::from_secs(5), statuses.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(received, status);
+
+    bus.close();
+}
+
+/// Needs a running broker, e.g.
+/// `MQTT_HOST=127.0.0.1 cargo test --features mqtt --test mqtt_test -- --ignored`
+#[tokio::test]
+#[ignore]
+async fn test_mqtt_publish_subscribe() {
+    let host = env::var("MQTT_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = env::var("MQTT_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(1883);
+    publish_subscribe(MqttOptions::new("openfmb-mqtt-test", host, port)).await;
+}